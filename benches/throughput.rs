@@ -0,0 +1,148 @@
+//! Throughput of the model layer and of paginated crawls.
+//!
+//! Run with `cargo bench`, or `cargo bench --all-features` to include the Markdown importer.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use futures::TryStreamExt;
+use notion::export::outline;
+use notion::models::block::Block;
+use notion::models::search::DatabaseQuery;
+use notion::models::{borrowed, ListResponse, Page};
+use notion::NotionApi;
+use serde_json::{json, Value};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+const PAGES_PER_RESPONSE: usize = 100;
+const RESPONSES: usize = 5;
+
+fn page() -> Value {
+    serde_json::from_str(include_str!("../src/models/tests/page.json")).unwrap()
+}
+
+/// A query response holding `pages` copies of the test page.
+fn query_response(
+    pages: usize,
+    next_cursor: Option<String>,
+) -> Value {
+    json!({
+        "object": "list",
+        "results": vec![page(); pages],
+        "has_more": next_cursor.is_some(),
+        "next_cursor": next_cursor,
+    })
+}
+
+fn deserialization(c: &mut Criterion) {
+    let json = query_response(PAGES_PER_RESPONSE, None).to_string();
+
+    let mut group = c.benchmark_group("deserialize query response");
+    group.throughput(Throughput::Bytes(json.len() as u64));
+    group.bench_function("owned", |b| {
+        b.iter(|| serde_json::from_str::<ListResponse<Page>>(black_box(&json)).unwrap())
+    });
+    group.bench_function("borrowed", |b| {
+        b.iter(|| {
+            serde_json::from_str::<borrowed::ListResponse<borrowed::Page>>(black_box(&json))
+                .unwrap()
+        })
+    });
+    group.finish();
+}
+
+fn block_tree(c: &mut Criterion) {
+    let page: Page = serde_json::from_value(page()).unwrap();
+    let blocks: ListResponse<Block> =
+        serde_json::from_str(include_str!("../src/export/tests/outline_blocks.json")).unwrap();
+    // Repeat the blocks so the outline has a few hundred sections to nest.
+    let blocks: Vec<Block> = blocks
+        .results()
+        .iter()
+        .cycle()
+        .take(blocks.results().len() * 50)
+        .cloned()
+        .collect();
+
+    let mut group = c.benchmark_group("block tree");
+    group.throughput(Throughput::Elements(blocks.len() as u64));
+    group.bench_function("outline", |b| {
+        b.iter(|| outline(black_box(&page), black_box(&blocks)))
+    });
+    group.finish();
+}
+
+#[cfg(feature = "markdown")]
+fn markdown(c: &mut Criterion) {
+    let document = include_str!("../src/import/tests/document.md").repeat(100);
+
+    let mut group = c.benchmark_group("markdown");
+    group.throughput(Throughput::Bytes(document.len() as u64));
+    group.bench_function("import", |b| {
+        b.iter(|| notion::import::markdown(black_box(&document)))
+    });
+    group.finish();
+}
+
+#[cfg(not(feature = "markdown"))]
+fn markdown(_: &mut Criterion) {}
+
+/// Answers a database query with the page of results its `start_cursor` asks for.
+struct PagedQuery;
+
+impl Respond for PagedQuery {
+    fn respond(
+        &self,
+        request: &Request,
+    ) -> ResponseTemplate {
+        let body: Value = serde_json::from_slice(&request.body).unwrap();
+        let index: usize = body["start_cursor"]
+            .as_str()
+            .map_or(0, |cursor| cursor.parse().unwrap());
+        let next_cursor = Some(index + 1)
+            .filter(|next| *next < RESPONSES)
+            .map(|next| next.to_string());
+
+        ResponseTemplate::new(200).set_body_json(query_response(PAGES_PER_RESPONSE, next_cursor))
+    }
+}
+
+fn pagination(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = runtime.block_on(async {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/databases/benchmark/query"))
+            .respond_with(PagedQuery)
+            .mount(&server)
+            .await;
+        server
+    });
+    let api = NotionApi::builder("secret_benchmark".to_string())
+        .base_url(server.uri())
+        .build()
+        .unwrap();
+    let database = "b55c9c91384d452b81dbd1ef79372b75".parse().unwrap();
+
+    let mut group = c.benchmark_group("pagination");
+    group.throughput(Throughput::Elements(
+        (PAGES_PER_RESPONSE * RESPONSES) as u64,
+    ));
+    group.bench_function("query_database_stream", |b| {
+        b.to_async(&runtime).iter_batched(
+            DatabaseQuery::default,
+            |query| async {
+                let pages: Vec<Page> = api
+                    .query_database_stream(&database, query)
+                    .try_collect()
+                    .await
+                    .unwrap();
+                assert_eq!(pages.len(), PAGES_PER_RESPONSE * RESPONSES);
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, deserialization, block_tree, markdown, pagination);
+criterion_main!(benches);
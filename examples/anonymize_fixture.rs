@@ -0,0 +1,18 @@
+//! Anonymizes an API response read from stdin, so it can be contributed as a test fixture.
+//!
+//! ```text
+//! cargo run --example anonymize_fixture < response.json > src/models/tests/fixture.json
+//! ```
+
+use std::io::{stdin, stdout, Read};
+
+fn main() -> anyhow::Result<()> {
+    let mut response = String::new();
+    stdin().read_to_string(&mut response)?;
+
+    let value = serde_json::from_str(&response)?;
+    serde_json::to_writer_pretty(stdout(), &notion::fixture::anonymize(value))?;
+    println!();
+
+    Ok(())
+}
@@ -0,0 +1,273 @@
+//! Reports on how the pages of a database use its schema.
+
+use crate::ids::{AsIdentifier, DatabaseId};
+use crate::models::properties::{
+    CreatePropertyConfiguration, CreateSelect, CreateSelectOption, PropertyConfiguration,
+    PropertyValue, SelectOption, SelectedValue,
+};
+use crate::models::search::DatabaseQuery;
+use crate::models::{Database, DatabaseUpdateRequest, IconObject, Page};
+use crate::{Error, NotionApi};
+use std::collections::HashMap;
+
+/// How many pages of a database use an option of a select, multi-select or status property.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct OptionUsage {
+    pub property: String,
+    pub option: SelectOption,
+    pub pages: usize,
+}
+
+impl OptionUsage {
+    pub fn is_unused(&self) -> bool {
+        self.pages == 0
+    }
+}
+
+/// Queries every page of a database and reports how many use each option of its
+/// select, multi-select and status properties, see [count_option_usage].
+pub async fn option_usage<D: AsIdentifier<DatabaseId>>(
+    api: &NotionApi,
+    database: D,
+) -> Result<Vec<OptionUsage>, Error> {
+    let database = api.get_database(database).await?;
+    let pages = api
+        .query_database_all(&database.id, DatabaseQuery::default())
+        .await?;
+
+    Ok(count_option_usage(&database, &pages))
+}
+
+/// Counts how many of `pages` use each option of the select, multi-select and status
+/// properties of `database`, including options no page uses.
+///
+/// The result is ordered by property name, then by the order of the options in the schema.
+pub fn count_option_usage(
+    database: &Database,
+    pages: &[Page],
+) -> Vec<OptionUsage> {
+    let mut usage: Vec<_> = database
+        .properties
+        .iter()
+        .flat_map(|(property, configuration)| {
+            let options = match configuration {
+                PropertyConfiguration::Select { select, .. }
+                | PropertyConfiguration::MultiSelect {
+                    multi_select: select,
+                    ..
+                } => &select.options[..],
+                PropertyConfiguration::Status { status, .. } => &status.options[..],
+                _ => &[],
+            };
+
+            options.iter().map(move |option| OptionUsage {
+                property: property.clone(),
+                option: option.clone(),
+                pages: pages
+                    .iter()
+                    .filter(|page| uses_option(page, property, option))
+                    .count(),
+            })
+        })
+        .collect();
+
+    usage.sort_by(|a, b| a.property.cmp(&b.property));
+    usage
+}
+
+/// Removes the options of select and multi-select properties that no page of the
+/// database uses, and returns the updated database.
+///
+/// Status options are reported by [option_usage] but left alone,
+/// since Notion doesn't allow changing them through the API.
+pub async fn prune_unused_options<D: AsIdentifier<DatabaseId>>(
+    api: &NotionApi,
+    database: D,
+) -> Result<Database, Error> {
+    let database = api.get_database(database).await?;
+    let pages = api
+        .query_database_all(&database.id, DatabaseQuery::default())
+        .await?;
+
+    let update = prune_request(&database, &count_option_usage(&database, &pages));
+    if update.properties.is_empty() {
+        return Ok(database);
+    }
+
+    api.update_database(&database.id, update).await
+}
+
+fn prune_request(
+    database: &Database,
+    usage: &[OptionUsage],
+) -> DatabaseUpdateRequest {
+    let mut used: HashMap<&str, Vec<CreateSelectOption>> = HashMap::new();
+    let mut pruned = vec![];
+    for usage in usage {
+        let options = used.entry(&usage.property).or_default();
+        if usage.is_unused() {
+            pruned.push(usage.property.as_str());
+        } else {
+            options.push(
+                CreateSelectOption::new(usage.option.name.clone()).with_color(usage.option.color),
+            );
+        }
+    }
+
+    pruned.dedup();
+    pruned
+        .into_iter()
+        .filter_map(|property| {
+            let options = used.remove(property)?;
+            let configuration = match database.properties.get(property)? {
+                PropertyConfiguration::Select { .. } => {
+                    CreatePropertyConfiguration::Select(CreateSelect { options })
+                }
+                PropertyConfiguration::MultiSelect { .. } => {
+                    CreatePropertyConfiguration::MultiSelect(CreateSelect { options })
+                }
+                _ => return None,
+            };
+            Some((property, configuration))
+        })
+        .fold(
+            DatabaseUpdateRequest::default(),
+            |update, (property, configuration)| update.property(property, configuration),
+        )
+}
+
+fn uses_option(
+    page: &Page,
+    property: &str,
+    option: &SelectOption,
+) -> bool {
+    let is_option = |value: &SelectedValue| match &value.id {
+        Some(id) => *id == option.id,
+        None => value.name.as_deref() == Some(option.name.as_str()),
+    };
+
+    match page.properties.properties.get(property) {
+        Some(PropertyValue::Select {
+            select: Some(value),
+            ..
+        })
+        | Some(PropertyValue::Status {
+            status: Some(value),
+            ..
+        }) => is_option(value),
+        Some(PropertyValue::MultiSelect {
+            multi_select: Some(values),
+            ..
+        }) => values.iter().any(is_option),
+        _ => false,
+    }
+}
+
+/// Counts the emoji used as page icons, most used first.
+/// Pages without an icon or with an image icon aren't counted.
+pub fn icon_usage(pages: &[Page]) -> Vec<(String, usize)> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for page in pages {
+        if let Some(IconObject::Emoji { emoji }) = &page.icon {
+            *counts.entry(emoji).or_default() += 1;
+        }
+    }
+
+    let mut counts: Vec<_> = counts
+        .into_iter()
+        .map(|(emoji, count)| (emoji.to_string(), count))
+        .collect();
+    counts.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{count_option_usage, icon_usage, prune_request};
+    use crate::models::{Database, Page};
+    use serde_json::json;
+
+    fn database() -> Database {
+        serde_json::from_value(json!({
+            "id": "database-id",
+            "created_time": "2022-05-12T00:00:00.000Z",
+            "last_edited_time": "2022-05-12T00:00:00.000Z",
+            "title": [],
+            "parent": {"type": "workspace"},
+            "properties": {
+                "Name": {"id": "title", "type": "title", "title": {}},
+                "Status": {"id": "st", "type": "select", "select": {"options": [
+                    {"id": "todo", "name": "Todo", "color": "red"},
+                    {"id": "done", "name": "Done", "color": "green"},
+                ]}},
+                "Tags": {"id": "tg", "type": "multi_select", "multi_select": {"options": [
+                    {"id": "a", "name": "A", "color": "blue"},
+                    {"id": "b", "name": "B", "color": "gray"},
+                ]}},
+            },
+        }))
+        .unwrap()
+    }
+
+    fn page(
+        status: &str,
+        tags: &[&str],
+        icon: serde_json::Value,
+    ) -> Page {
+        let mut page: serde_json::Value =
+            serde_json::from_str(include_str!("models/tests/page.json")).unwrap();
+        page["icon"] = icon;
+        page["properties"] = json!({
+            "Status": {"id": "st", "type": "select", "select": {"id": status, "name": status, "color": "red"}},
+            "Tags": {"id": "tg", "type": "multi_select", "multi_select": tags
+                .iter()
+                .map(|tag| json!({"id": tag, "name": tag, "color": "blue"}))
+                .collect::<Vec<_>>()},
+        });
+        serde_json::from_value(page).unwrap()
+    }
+
+    #[test]
+    fn counts_and_prunes_options() {
+        let database = database();
+        let pages = vec![
+            page("todo", &["a"], json!({"type": "emoji", "emoji": "📝"})),
+            page("todo", &["a"], json!({"type": "emoji", "emoji": "📝"})),
+            page("todo", &[], json!({"type": "emoji", "emoji": "🚀"})),
+        ];
+
+        let usage = count_option_usage(&database, &pages);
+        let counts: Vec<_> = usage
+            .iter()
+            .map(|usage| {
+                (
+                    usage.property.as_str(),
+                    usage.option.name.as_str(),
+                    usage.pages,
+                )
+            })
+            .collect();
+        assert_eq!(
+            counts,
+            vec![
+                ("Status", "Todo", 3),
+                ("Status", "Done", 0),
+                ("Tags", "A", 2),
+                ("Tags", "B", 0),
+            ]
+        );
+
+        assert_eq!(
+            serde_json::to_value(prune_request(&database, &usage)).unwrap(),
+            json!({"properties": {
+                "Status": {"select": {"options": [{"name": "Todo", "color": "red"}]}},
+                "Tags": {"multi_select": {"options": [{"name": "A", "color": "blue"}]}},
+            }})
+        );
+
+        assert_eq!(
+            icon_usage(&pages),
+            vec![("📝".to_string(), 2), ("🚀".to_string(), 1)]
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use crate::ids::BlockId;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::OwnedMutexGuard;
+
+/// Serializes appends to the same parent block, in the order they were submitted.
+///
+/// Each parent gets a fair (first in, first out) lock, so appends that are waiting
+/// run in the order they started waiting. Appends to different parents don't wait
+/// on each other.
+#[derive(Debug, Default)]
+pub(crate) struct AppendQueue {
+    parents: Mutex<HashMap<BlockId, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+/// Held while appending to a parent. Releases the parent's turn when dropped.
+pub(crate) struct AppendTurn<'a> {
+    queue: &'a AppendQueue,
+    parent: BlockId,
+    guard: Option<OwnedMutexGuard<()>>,
+}
+
+impl AppendQueue {
+    /// Waits for the earlier appends to `parent` to finish.
+    pub(crate) async fn turn(
+        &self,
+        parent: &BlockId,
+    ) -> AppendTurn<'_> {
+        let lock = self.parents().entry(parent.clone()).or_default().clone();
+
+        AppendTurn {
+            queue: self,
+            parent: parent.clone(),
+            guard: Some(lock.lock_owned().await),
+        }
+    }
+
+    fn parents(&self) -> std::sync::MutexGuard<'_, HashMap<BlockId, Arc<tokio::sync::Mutex<()>>>> {
+        self.parents.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for AppendTurn<'_> {
+    fn drop(&mut self) {
+        self.guard.take();
+
+        // Forget the parent once nothing else is waiting on it.
+        let mut parents = self.queue.parents();
+        if let Some(lock) = parents.get(&self.parent) {
+            if Arc::strong_count(lock) == 1 {
+                parents.remove(&self.parent);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::append_queue::AppendQueue;
+    use crate::ids::BlockId;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn runs_appends_to_a_parent_in_submission_order() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let queue = Arc::new(AppendQueue::default());
+        let parent: BlockId = "9e891834-6a03-475c-a2b8-421e17f0f3aa".parse()?;
+        let order = Arc::new(Mutex::new(vec![]));
+
+        let first = queue.turn(&parent).await;
+        let mut tasks = vec![];
+        for i in 0..5 {
+            let (queue, parent, order) = (queue.clone(), parent.clone(), order.clone());
+            tasks.push(tokio::spawn(async move {
+                let _turn = queue.turn(&parent).await;
+                // Later tasks get plenty of time to overtake if the queue isn't fair.
+                tokio::time::sleep(Duration::from_millis(5 - i)).await;
+                order.lock().unwrap().push(i);
+            }));
+            // Let the task start waiting before submitting the next one.
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+
+        drop(first);
+        for task in tasks {
+            task.await?;
+        }
+
+        assert_eq!(*order.lock().unwrap(), vec![0, 1, 2, 3, 4]);
+        assert!(queue.parents().is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,503 @@
+use crate::ids::{AsIdentifier, BlockId};
+use crate::models::block::{Block, CreateBlock, TextAndChildren};
+use crate::models::text::TextColor;
+use crate::{Error, NotionApi};
+use futures::future::{BoxFuture, FutureExt};
+
+/// Notion appends at most this many blocks in a single request,
+/// and includes at most this many children with a block.
+pub(crate) const MAX_APPEND: usize = 100;
+
+/// The most levels of children a request can include below the blocks it appends.
+const MAX_NESTING: usize = 2;
+
+/// The most blocks a request can include, counting children at every level.
+const MAX_BLOCKS: usize = 1000;
+
+/// The children that didn't fit in the request that created a block,
+/// to append once it has an id.
+#[derive(Debug, Default)]
+struct Deferred {
+    /// Appended after the children the block was created with.
+    children: Vec<CreateBlock>,
+    /// The deferred children of the children the block was created with, by their position.
+    nested: Vec<(usize, Deferred)>,
+    /// Whether the block was created with a placeholder as its first child,
+    /// to remove once the deferred children are appended.
+    placeholder: bool,
+}
+
+impl Deferred {
+    fn is_empty(&self) -> bool {
+        self.children.is_empty() && self.nested.is_empty() && !self.placeholder
+    }
+}
+
+/// What a block needs to be created, in the blocks its request has to include.
+enum Shape {
+    /// Tables are created with at least one row.
+    Table,
+    /// Column lists are created with all their columns, which each need a block.
+    ColumnList {
+        columns: usize,
+    },
+    /// Columns are created with at least one block.
+    Column,
+    Other,
+}
+
+impl Shape {
+    /// The levels of children the block has to be created with.
+    fn levels(&self) -> usize {
+        match self {
+            Shape::Table | Shape::Column => 1,
+            Shape::ColumnList { .. } => 2,
+            Shape::Other => 0,
+        }
+    }
+
+    /// The blocks the block takes up in its request, counting the children it needs.
+    fn size(&self) -> usize {
+        match self {
+            Shape::Table | Shape::Column => 2,
+            Shape::ColumnList { columns } => 1 + 2 * columns,
+            Shape::Other => 1,
+        }
+    }
+
+    /// How many of its first children the block has to be created with,
+    /// already counted in its [size](Shape::size()).
+    fn required_children(&self) -> usize {
+        match self {
+            Shape::Table | Shape::Column => 1,
+            Shape::ColumnList { columns } => *columns,
+            Shape::Other => 0,
+        }
+    }
+}
+
+impl Shape {
+    fn of(block: &CreateBlock) -> Shape {
+        match block {
+            CreateBlock::Table { .. } => Shape::Table,
+            CreateBlock::ColumnList { column_list } => Shape::ColumnList {
+                columns: column_list.children.len(),
+            },
+            CreateBlock::Column { .. } => Shape::Column,
+            _ => Shape::Other,
+        }
+    }
+}
+
+/// An empty paragraph, holding the place of a block that has to be appended later.
+fn placeholder() -> CreateBlock {
+    CreateBlock::Paragraph {
+        paragraph: TextAndChildren {
+            rich_text: vec![],
+            children: None,
+            color: TextColor::Default,
+        },
+    }
+}
+
+/// Whether the block can be included `depth` levels down a request with `budget` blocks left.
+fn fits(
+    block: &CreateBlock,
+    depth: usize,
+    budget: usize,
+) -> bool {
+    let shape = Shape::of(block);
+    depth + shape.levels() <= MAX_NESTING && shape.size() <= budget
+}
+
+/// Keeps as many of the block's first children as fit in its request, `depth` levels down,
+/// and returns the rest to append later.
+fn trim(
+    block: &mut CreateBlock,
+    depth: usize,
+    budget: &mut usize,
+) -> Deferred {
+    let required = Shape::of(block).required_children();
+    let mut children = block.take_children().into_iter();
+    let mut kept = Vec::new();
+    let mut deferred = Deferred::default();
+
+    for (index, mut child) in children.by_ref().enumerate() {
+        if index < required && depth < MAX_NESTING {
+            // Counted in the size of the block.
+            if depth + 1 + Shape::of(&child).levels() > MAX_NESTING {
+                // The child can't be created this deep with the children it needs, e.g. a table
+                // in a column, so an empty paragraph stands in for it until it's appended.
+                deferred.children.push(child);
+                deferred.placeholder = true;
+                kept.push(placeholder());
+                break;
+            }
+        } else if kept.len() < MAX_APPEND && fits(&child, depth + 1, *budget) {
+            *budget -= Shape::of(&child).size();
+        } else {
+            // Later siblings wait too, so that the children keep their order.
+            deferred.children.push(child);
+            break;
+        }
+
+        let nested = trim(&mut child, depth + 1, budget);
+        if !nested.is_empty() {
+            deferred.nested.push((kept.len(), nested));
+        }
+        kept.push(child);
+    }
+
+    deferred.children.extend(children);
+    block.set_children(kept);
+    deferred
+}
+
+impl NotionApi {
+    /// Appends blocks to the children of a block or page, however many there are
+    /// and however deeply they nest, and returns the appended blocks.
+    ///
+    /// A single [append](NotionApi::append_block_children()) is limited to 100 blocks,
+    /// with two levels of children below them. The blocks are split into as many appends
+    /// as it takes: each includes as much of the tree as fits, and the children left out
+    /// are appended to the blocks once they're created, so the tree keeps its order.
+    /// A block too deep to be created with the children it needs, like a table that is
+    /// the first block of a column, is appended after an empty paragraph that is then removed.
+    ///
+    /// When an append fails part way, the blocks appended before it are left in place.
+    pub async fn append_block_tree<T: AsIdentifier<BlockId>>(
+        &self,
+        block_id: T,
+        children: Vec<CreateBlock>,
+    ) -> Result<Vec<Block>, Error> {
+        self.append_tree(block_id.as_id().clone(), children).await
+    }
+
+    fn append_tree(
+        &self,
+        block_id: BlockId,
+        children: Vec<CreateBlock>,
+    ) -> BoxFuture<'_, Result<Vec<Block>, Error>> {
+        async move {
+            let mut appended = Vec::with_capacity(children.len());
+            let mut children = children.into_iter().peekable();
+
+            while children.peek().is_some() {
+                let mut budget = MAX_BLOCKS;
+                let mut request = Vec::new();
+                let mut deferred = Vec::new();
+                // The first block is always sent, so that every request makes progress.
+                while let Some(mut block) = children.next_if(|block| {
+                    request.len() < MAX_APPEND && (request.is_empty() || fits(block, 0, budget))
+                }) {
+                    budget = budget.saturating_sub(Shape::of(&block).size());
+                    let nested = trim(&mut block, 0, &mut budget);
+                    if !nested.is_empty() {
+                        deferred.push((request.len(), nested));
+                    }
+                    request.push(block);
+                }
+
+                let created = self
+                    .append_block_children(&block_id, request)
+                    .await?
+                    .results;
+                for (index, nested) in deferred {
+                    if let Some(block) = created.get(index) {
+                        self.append_deferred(block.as_id().clone(), nested).await?;
+                    }
+                }
+                appended.extend(created);
+            }
+
+            Ok(appended)
+        }
+        .boxed()
+    }
+
+    /// Appends the children left out of the request that created the block.
+    fn append_deferred(
+        &self,
+        block_id: BlockId,
+        deferred: Deferred,
+    ) -> BoxFuture<'_, Result<(), Error>> {
+        async move {
+            let children = if !deferred.nested.is_empty() || deferred.placeholder {
+                self.get_block_children_all(&block_id).await?
+            } else {
+                vec![]
+            };
+            for (index, nested) in deferred.nested {
+                if let Some(child) = children.get(index) {
+                    self.append_deferred(child.as_id().clone(), nested).await?;
+                }
+            }
+            if !deferred.children.is_empty() {
+                self.append_tree(block_id, deferred.children).await?;
+            }
+            if deferred.placeholder {
+                if let Some(placeholder) = children.first() {
+                    self.trash_block(placeholder.as_id()).await?;
+                }
+            }
+            Ok(())
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ids::BlockId;
+    use crate::models::block::{Block, CreateBlock};
+    use crate::{Error, NotionApi, TransportResponse};
+    use reqwest::{Method, StatusCode};
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    const USER: &str = "6419f912-5293-4ea8-b2c8-9c3ce44f90e3";
+
+    fn block(
+        kind: &str,
+        fields: Value,
+    ) -> Block {
+        serde_json::from_value(json!({
+            "object": "block",
+            "id": "9e891834-6a03-475c-a2b8-421e17f0f3aa",
+            "created_time": "2022-05-12T21:15:00.000Z",
+            "last_edited_time": "2022-05-12T22:10:00.000Z",
+            "created_by": {"object": "user", "id": USER},
+            "last_edited_by": {"object": "user", "id": USER},
+            "has_children": false,
+            "type": kind,
+            kind: fields,
+        }))
+        .unwrap()
+    }
+
+    fn toggle(
+        text: &str,
+        children: Vec<Block>,
+    ) -> Block {
+        block(
+            "toggle",
+            json!({
+                "rich_text": [{"type": "text", "text": {"content": text}, "plain_text": text}],
+                "color": "default",
+                "children": children,
+            }),
+        )
+    }
+
+    /// The children of every block in a fake workspace, which creates blocks like Notion does.
+    #[derive(Default)]
+    struct Workspace {
+        children: HashMap<String, Vec<Value>>,
+        appends: Vec<(String, usize)>,
+        trashed: Vec<String>,
+        created: usize,
+    }
+
+    impl Workspace {
+        fn create(
+            &mut self,
+            parent: &str,
+            mut block: Value,
+        ) -> Value {
+            self.created += 1;
+            let id = format!("00000000-0000-4000-8000-{:012}", self.created);
+            let kind = block["type"].as_str().unwrap().to_string();
+            let children = block[&kind]
+                .as_object_mut()
+                .and_then(|fields| fields.remove("children"));
+            for child in children.iter().flat_map(|c| c.as_array().unwrap().clone()) {
+                self.create(&id, child);
+            }
+
+            let user = json!({"object": "user", "id": USER});
+            let object = block.as_object_mut().unwrap();
+            object.insert("object".into(), "block".into());
+            object.insert("id".into(), id.clone().into());
+            object.insert("created_time".into(), "2022-05-12T21:15:00.000Z".into());
+            object.insert("last_edited_time".into(), "2022-05-12T21:15:00.000Z".into());
+            object.insert("created_by".into(), user.clone());
+            object.insert("last_edited_by".into(), user);
+            object.insert("has_children".into(), false.into());
+            self.children
+                .entry(parent.to_string())
+                .or_default()
+                .push(block.clone());
+            block
+        }
+
+        fn trash(
+            &mut self,
+            block_id: &str,
+        ) -> Value {
+            self.trashed.push(block_id.to_string());
+            for children in self.children.values_mut() {
+                if let Some(index) = children.iter().position(|child| child["id"] == block_id) {
+                    return children.remove(index);
+                }
+            }
+            panic!("no block {}", block_id)
+        }
+
+        /// The text of the blocks under `parent`, or their type, indented by their depth.
+        fn outline(
+            &self,
+            parent: &str,
+            depth: usize,
+        ) -> Vec<String> {
+            let mut lines = vec![];
+            for child in self.children.get(parent).into_iter().flatten() {
+                let kind = child["type"].as_str().unwrap();
+                let text = match kind {
+                    "table_row" => child[kind]["cells"][0][0]["plain_text"].as_str(),
+                    _ => child[kind]["rich_text"][0]["plain_text"].as_str(),
+                };
+                lines.push(format!("{}{}", "  ".repeat(depth), text.unwrap_or(kind)));
+                lines.extend(self.outline(child["id"].as_str().unwrap(), depth + 1));
+            }
+            lines
+        }
+    }
+
+    fn list(results: Vec<Value>) -> String {
+        json!({"object": "list", "results": results, "next_cursor": null, "has_more": false})
+            .to_string()
+    }
+
+    /// A client for the fake workspace.
+    fn client(fake: Arc<Mutex<Workspace>>) -> Result<NotionApi, Error> {
+        NotionApi::builder("secret".to_string())
+            .transport(move |request: reqwest::Request| {
+                let mut workspace = fake.lock().unwrap();
+                let parent = request
+                    .url()
+                    .path()
+                    .trim_start_matches("/v1/blocks/")
+                    .trim_end_matches("/children")
+                    .to_string();
+                let body = match request.method() {
+                    &Method::PATCH if !request.url().path().ends_with("/children") => {
+                        workspace.trash(&parent).to_string()
+                    }
+                    &Method::PATCH => {
+                        let body = request.body().and_then(|body| body.as_bytes()).unwrap();
+                        let body: Value = serde_json::from_slice(body).unwrap();
+                        let children = body["children"].as_array().unwrap().clone();
+                        workspace.appends.push((parent.clone(), children.len()));
+                        let created = children
+                            .into_iter()
+                            .map(|child| workspace.create(&parent, child))
+                            .collect();
+                        list(created)
+                    }
+                    &Method::GET => {
+                        list(workspace.children.get(&parent).cloned().unwrap_or_default())
+                    }
+                    method => panic!("unexpected request {} {}", method, request.url()),
+                };
+                Ok(TransportResponse::new(StatusCode::OK, body))
+            })
+            .build()
+    }
+
+    #[tokio::test]
+    async fn splits_large_and_deep_trees() -> Result<(), Box<dyn std::error::Error>> {
+        let workspace = Arc::new(Mutex::new(Workspace::default()));
+        let api = client(workspace.clone())?;
+
+        let deep = toggle(
+            "a",
+            vec![toggle("b", vec![toggle("c", vec![toggle("d", vec![])])])],
+        );
+        let long = toggle(
+            "long",
+            (0..150).map(|i| toggle(&i.to_string(), vec![])).collect(),
+        );
+        let mut children: Vec<CreateBlock> = vec![deep.into(), long.into()];
+        children.extend((0..120).map(|i| toggle(&format!("top {}", i), vec![]).into()));
+
+        let root: BlockId = "b55c9c91384d452b81dbd1ef79372b75".parse()?;
+        let appended = api.append_block_tree(&root, children).await?;
+        assert_eq!(appended.len(), 122);
+
+        let workspace = workspace.lock().unwrap();
+        let root = root.to_string();
+        assert!(workspace.appends.iter().all(|(_, len)| *len <= 100));
+        assert_eq!(
+            workspace
+                .appends
+                .iter()
+                .filter(|(parent, _)| *parent == root)
+                .map(|(_, len)| *len)
+                .collect::<Vec<_>>(),
+            vec![100, 22]
+        );
+
+        let outline = workspace.outline(&root, 0);
+        assert_eq!(&outline[..4], &["a", "  b", "    c", "      d"]);
+        assert_eq!(outline[4], "long");
+        assert_eq!(
+            outline[5..155],
+            (0..150).map(|i| format!("  {}", i)).collect::<Vec<_>>()[..]
+        );
+        assert_eq!(outline[155], "top 0");
+        assert_eq!(outline.last().unwrap(), "top 119");
+        assert_eq!(outline.len(), 275);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn appends_tables_too_deep_for_their_column() -> Result<(), Box<dyn std::error::Error>> {
+        let workspace = Arc::new(Mutex::new(Workspace::default()));
+        let api = client(workspace.clone())?;
+
+        let text = |content: &str| {
+            let text = json!({"type": "text", "text": {"content": content}, "plain_text": content});
+            json!([text])
+        };
+        let row =
+            |content: &str| json!({"type": "table_row", "table_row": {"cells": [text(content)]}});
+        let columns: CreateBlock = serde_json::from_value(json!({
+            "type": "column_list",
+            "column_list": {"children": [
+                {"type": "column", "column": {"children": [
+                    {"type": "table", "table": {
+                        "table_width": 1, "has_column_header": false, "has_row_header": false,
+                        "children": [row("a"), row("b")]
+                    }},
+                    {"type": "paragraph", "paragraph": {"rich_text": text("after"), "color": "default"}}
+                ]}},
+                {"type": "column", "column": {"children": [
+                    {"type": "paragraph", "paragraph": {"rich_text": text("right"), "color": "default"}}
+                ]}}
+            ]}
+        }))?;
+
+        let root: BlockId = "b55c9c91384d452b81dbd1ef79372b75".parse()?;
+        api.append_block_tree(&root, vec![columns]).await?;
+
+        let workspace = workspace.lock().unwrap();
+        assert_eq!(workspace.trashed.len(), 1);
+        assert_eq!(
+            workspace.outline(&root.to_string(), 0),
+            vec![
+                "column_list",
+                "  column",
+                "    table",
+                "      a",
+                "      b",
+                "    after",
+                "  column",
+                "    right",
+            ]
+        );
+
+        Ok(())
+    }
+}
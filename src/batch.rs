@@ -0,0 +1,181 @@
+//! Reports of operations that make a request per item, like [import_table](crate::import::import_table).
+//!
+//! A failing item doesn't stop the batch: the report lists what succeeded, which items
+//! failed and why, how many requests were [retried](crate::RetryPolicy) along the way
+//! and how long it all took. It prints as a short summary, and serializes for tools
+//! that consume it.
+
+use crate::Error;
+use serde::{Serialize, Serializer};
+use std::cell::Cell;
+use std::fmt::{self, Display, Formatter};
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+tokio::task_local! {
+    /// The retries of the batch the current task is running, if any.
+    static RETRIES: Cell<u32>;
+}
+
+/// Counts a retried request towards the batch it was sent for.
+pub(crate) fn record_retry() {
+    // Requests outside of a batch aren't counted.
+    let _ = RETRIES.try_with(|retries| retries.set(retries.get() + 1));
+}
+
+/// The outcome of a batch of items.
+#[derive(Debug, Serialize)]
+pub struct BatchReport<T> {
+    /// The results of the items that succeeded, in the order of the items.
+    pub succeeded: Vec<T>,
+    /// The items that failed, in the order of the items.
+    pub failed: Vec<BatchFailure>,
+    /// The number of requests that were retried.
+    pub retries: u32,
+    #[serde(rename = "duration_secs", serialize_with = "serialize_secs")]
+    pub duration: Duration,
+}
+
+/// An item of a batch that failed.
+#[derive(Debug, Serialize)]
+pub struct BatchFailure {
+    /// The position of the item in the batch, starting at 0.
+    pub index: usize,
+    #[serde(serialize_with = "serialize_display")]
+    pub error: Error,
+}
+
+impl<T> BatchReport<T> {
+    /// Runs `operation` for each item, one at a time.
+    pub(crate) async fn run<I, F, Fut>(
+        items: I,
+        mut operation: F,
+    ) -> Self
+    where
+        I: IntoIterator,
+        F: FnMut(I::Item) -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let started = Instant::now();
+        RETRIES
+            .scope(Cell::new(0), async move {
+                let mut succeeded = vec![];
+                let mut failed = vec![];
+                for (index, item) in items.into_iter().enumerate() {
+                    match operation(item).await {
+                        Ok(result) => succeeded.push(result),
+                        Err(error) => {
+                            tracing::debug!(index, %error, "Batch item failed");
+                            failed.push(BatchFailure { index, error });
+                        }
+                    }
+                }
+
+                Self {
+                    succeeded,
+                    failed,
+                    retries: RETRIES.with(Cell::get),
+                    duration: started.elapsed(),
+                }
+            })
+            .await
+    }
+
+    /// Whether every item succeeded.
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    /// The number of items in the batch.
+    pub fn len(&self) -> usize {
+        self.succeeded.len() + self.failed.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A summary line, followed by a line per failed item.
+impl<T> Display for BatchReport<T> {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "{} succeeded, {} failed, {} {} in {:.1}s",
+            self.succeeded.len(),
+            self.failed.len(),
+            self.retries,
+            if self.retries == 1 {
+                "retry"
+            } else {
+                "retries"
+            },
+            self.duration.as_secs_f64()
+        )?;
+        for failure in &self.failed {
+            write!(f, "\n  item {}: {}", failure.index, failure.error)?;
+        }
+        Ok(())
+    }
+}
+
+fn serialize_secs<S: Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64())
+}
+
+fn serialize_display<D: Display, S: Serializer>(
+    value: &D,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::batch::{record_retry, BatchReport};
+    use crate::Error;
+    use serde_json::json;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn reports_every_item() {
+        let mut report = BatchReport::run(1..=4, |item| async move {
+            if item % 2 == 0 {
+                record_retry();
+                Err(Error::InvalidEnvironmentVariable {
+                    name: "ITEM",
+                    value: item.to_string(),
+                })
+            } else {
+                Ok(item * 10)
+            }
+        })
+        .await;
+        record_retry();
+
+        assert_eq!(report.succeeded, vec![10, 30]);
+        assert_eq!(
+            report.failed.iter().map(|f| f.index).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+        assert_eq!(report.retries, 2);
+        assert_eq!(report.len(), 4);
+        assert!(!report.is_success());
+
+        report.duration = Duration::from_millis(1500);
+        let summary = report.to_string();
+        assert!(summary.starts_with("2 succeeded, 2 failed, 2 retries in 1.5s\n  item 1: "));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["succeeded"], json!([10, 30]));
+        assert_eq!(json["failed"][1]["index"], 3);
+        assert!(json["failed"][1]["error"].is_string());
+        assert_eq!(json["duration_secs"], 1.5);
+    }
+}
@@ -0,0 +1,853 @@
+use crate::append_queue::AppendQueue;
+use crate::cache::{Cache, ResponseCache};
+use crate::conflict_pacer::ConflictPacer;
+use crate::instrument::{RequestHook, RequestInfo, ResponseHook, ResponseInfo};
+use crate::rate_limit::RateLimiter;
+use crate::transport::{NotionTransport, ReqwestTransport};
+use crate::{ApiVersion, Error, NotionApi, RetryPolicy, NOTION_API_URL, NOTION_API_VERSION};
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::redirect::Policy;
+use reqwest::{header, Client, ClientBuilder, Method, Url};
+use std::convert::TryInto;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub(crate) const DEFAULT_USER_AGENT: &str =
+    concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// Where [NotionApiBuilder::from_env()] looks for a token when `NOTION_API_TOKEN` isn't set.
+const API_TOKEN_FILE: &str = ".api_token";
+
+pub(crate) type RequestSigner = Arc<dyn Fn(&mut RequestParts<'_>) + Send + Sync>;
+
+/// The parts of an outgoing request handed to a
+/// [request signer](NotionApiBuilder::sign_request()).
+///
+/// The body is already JSON encoded, so a signer can compute a digest or HMAC over
+/// the exact bytes that will be sent and attach the result as a header.
+pub struct RequestParts<'a> {
+    method: &'a Method,
+    url: &'a Url,
+    body: Option<&'a [u8]>,
+    headers: &'a mut HeaderMap,
+}
+
+impl<'a> RequestParts<'a> {
+    pub fn method(&self) -> &Method {
+        self.method
+    }
+
+    pub fn url(&self) -> &Url {
+        self.url
+    }
+
+    /// The encoded request body, if the request has one.
+    pub fn body(&self) -> Option<&[u8]> {
+        self.body
+    }
+
+    /// The headers of the request, including the authorization, `Notion-Version`
+    /// and `User-Agent` headers the client adds.
+    pub fn headers(&self) -> &HeaderMap {
+        self.headers
+    }
+
+    pub fn headers_mut(&mut self) -> &mut HeaderMap {
+        self.headers
+    }
+}
+
+pub(crate) fn sign(
+    signer: &RequestSigner,
+    request: &mut reqwest::Request,
+) {
+    let mut headers = request.headers().clone();
+    signer(&mut RequestParts {
+        method: request.method(),
+        url: request.url(),
+        body: request.body().and_then(|body| body.as_bytes()),
+        headers: &mut headers,
+    });
+    *request.headers_mut() = headers;
+}
+
+/// Configures and creates a [NotionApi] client.
+/// Create a builder by using [NotionApi::builder()](NotionApi::builder()).
+pub struct NotionApiBuilder {
+    api_token: String,
+    base_url: String,
+    notion_version: String,
+    user_agent: String,
+    app_identifier: Option<String>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    redirect_policy: Option<Policy>,
+    resolve: Vec<(String, SocketAddr)>,
+    client: Option<Client>,
+    transport: Option<Arc<dyn NotionTransport>>,
+    request_signer: Option<RequestSigner>,
+    on_request: Option<RequestHook>,
+    on_response: Option<ResponseHook>,
+    retry_policy: Option<RetryPolicy>,
+    requests_per_second: Option<u32>,
+    ordered_appends: bool,
+    pace_conflicts: bool,
+    cache: Option<ResponseCache>,
+}
+
+impl NotionApiBuilder {
+    pub(crate) fn new(api_token: String) -> Self {
+        Self {
+            api_token,
+            base_url: NOTION_API_URL.to_string(),
+            notion_version: NOTION_API_VERSION.to_string(),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            app_identifier: None,
+            timeout: None,
+            connect_timeout: None,
+            redirect_policy: None,
+            resolve: vec![],
+            client: None,
+            transport: None,
+            request_signer: None,
+            on_request: None,
+            on_response: None,
+            retry_policy: None,
+            requests_per_second: None,
+            ordered_appends: false,
+            pace_conflicts: false,
+            cache: None,
+        }
+    }
+
+    /// Creates a builder configured from environment variables:
+    ///
+    /// - `NOTION_API_TOKEN`: the API token. When it's not set the token is read from
+    ///   an `.api_token` file in the current directory instead
+    /// - `NOTION_TIMEOUT_MS`: the [request timeout](Self::timeout()) in milliseconds
+    /// - `NOTION_RPS`: the [rate limit](Self::requests_per_second()) in requests per second,
+    ///   which has to be at least 1
+    /// - `NOTION_MAX_RETRIES`: how many times a failed request is [retried](Self::retry_policy()),
+    ///   with the default backoff
+    ///
+    /// Unset or empty variables leave the default in place.
+    pub fn from_env() -> Result<Self, Error> {
+        Self::from_lookup(
+            |name| std::env::var(name).ok(),
+            |path| std::fs::read_to_string(path).ok(),
+        )
+    }
+
+    fn from_lookup<F, R>(
+        lookup: F,
+        read_file: R,
+    ) -> Result<Self, Error>
+    where
+        F: Fn(&str) -> Option<String>,
+        R: Fn(&str) -> Option<String>,
+    {
+        let var = |name: &'static str| lookup(name).filter(|value| !value.trim().is_empty());
+        let parse = |name: &'static str| -> Result<Option<u64>, Error> {
+            var(name)
+                .map(|value| {
+                    value
+                        .trim()
+                        .parse()
+                        .map_err(|_| Error::InvalidEnvironmentVariable { name, value })
+                })
+                .transpose()
+        };
+
+        let api_token = var("NOTION_API_TOKEN")
+            .or_else(|| read_file(API_TOKEN_FILE))
+            .map(|token| token.trim().to_string())
+            .filter(|token| !token.is_empty())
+            .ok_or_else(|| Error::MissingApiToken {
+                checked: vec![
+                    "environment variable NOTION_API_TOKEN".to_string(),
+                    format!("file {}", API_TOKEN_FILE),
+                ],
+            })?;
+        let mut builder = Self::new(api_token);
+
+        if let Some(timeout) = parse("NOTION_TIMEOUT_MS")? {
+            builder = builder.timeout(Duration::from_millis(timeout));
+        }
+        if let Some(requests_per_second) = parse("NOTION_RPS")? {
+            if requests_per_second == 0 {
+                return Err(Error::InvalidEnvironmentVariable {
+                    name: "NOTION_RPS",
+                    value: var("NOTION_RPS").unwrap_or_default(),
+                });
+            }
+            builder =
+                builder.requests_per_second(requests_per_second.try_into().unwrap_or(u32::MAX));
+        }
+        if let Some(retries) = parse("NOTION_MAX_RETRIES")? {
+            builder = builder.retry_policy(RetryPolicy::new(
+                retries.saturating_add(1).try_into().unwrap_or(u32::MAX),
+            ));
+        }
+
+        Ok(builder)
+    }
+
+    /// Sends requests to `base_url` instead of `https://api.notion.com/v1`,
+    /// e.g. to go through a proxy or talk to a mock server in tests.
+    pub fn base_url<S: Into<String>>(
+        self,
+        base_url: S,
+    ) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            ..self
+        }
+    }
+
+    /// Pins the `Notion-Version` header sent with every request to a version this crate knows,
+    /// so requests the version doesn't support fail before they're sent.
+    /// The models in this crate are written against [the default version](ApiVersion::default()).
+    pub fn api_version(
+        self,
+        api_version: ApiVersion,
+    ) -> Self {
+        self.notion_version(api_version.as_str())
+    }
+
+    /// Overrides the `Notion-Version` header sent with every request.
+    /// The models in this crate are written against the default version,
+    /// so responses of other versions may fail to parse.
+    /// Prefer [api_version](NotionApiBuilder::api_version()) for the versions this crate knows.
+    pub fn notion_version<S: Into<String>>(
+        self,
+        notion_version: S,
+    ) -> Self {
+        Self {
+            notion_version: notion_version.into(),
+            ..self
+        }
+    }
+
+    /// Replaces the `User-Agent` header sent with every request,
+    /// which defaults to this crate's name and version, e.g. `notion/0.6.0`.
+    pub fn user_agent<S: Into<String>>(
+        self,
+        user_agent: S,
+    ) -> Self {
+        Self {
+            user_agent: user_agent.into(),
+            ..self
+        }
+    }
+
+    /// Identifies the application using the client by appending `app_identifier`
+    /// (e.g. `"calendar-sync/1.2"`) to the `User-Agent` header,
+    /// so its traffic can be told apart from other tools sharing a workspace.
+    pub fn app_identifier<S: Into<String>>(
+        self,
+        app_identifier: S,
+    ) -> Self {
+        Self {
+            app_identifier: Some(app_identifier.into()),
+            ..self
+        }
+    }
+
+    /// Fails requests that take longer than `timeout`, from connecting until the response has been read.
+    pub fn timeout(
+        self,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Fails requests that can't connect within `connect_timeout`.
+    /// Has no effect when a [client](Self::client()) is provided, configure that client instead.
+    pub fn connect_timeout(
+        self,
+        connect_timeout: Duration,
+    ) -> Self {
+        Self {
+            connect_timeout: Some(connect_timeout),
+            ..self
+        }
+    }
+
+    /// Sets how redirects are followed, e.g. [Policy::none()] to refuse them.
+    /// Has no effect when a [client](Self::client()) is provided, configure that client instead.
+    pub fn redirect_policy(
+        self,
+        redirect_policy: Policy,
+    ) -> Self {
+        Self {
+            redirect_policy: Some(redirect_policy),
+            ..self
+        }
+    }
+
+    /// Connects to `address` for requests to `domain` instead of looking the domain up,
+    /// e.g. to pin `api.notion.com` to known IPs. The port of `address` is ignored in
+    /// favor of the request's port. Can be called once for each domain to override.
+    /// Has no effect when a [client](Self::client()) is provided, configure that client instead.
+    pub fn resolve<D: Into<String>>(
+        mut self,
+        domain: D,
+        address: SocketAddr,
+    ) -> Self {
+        self.resolve.push((domain.into(), address));
+        self
+    }
+
+    /// Sends requests with a pre-built [reqwest::Client], e.g. one with custom TLS or proxy settings.
+    /// The authorization, `Notion-Version` and `User-Agent` headers are still added to every request.
+    pub fn client(
+        self,
+        client: Client,
+    ) -> Self {
+        Self {
+            client: Some(client),
+            ..self
+        }
+    }
+
+    /// Sends requests with `transport` instead of over HTTP, e.g. to answer them with mocks
+    /// in tests. Requests are still built with the [client](Self::client()).
+    pub fn transport<T: NotionTransport + 'static>(
+        self,
+        transport: T,
+    ) -> Self {
+        Self {
+            transport: Some(Arc::new(transport)),
+            ..self
+        }
+    }
+
+    /// Registers a hook that runs on every request right before it is sent,
+    /// e.g. to add the extra auth headers or body signatures an internal gateway requires.
+    pub fn sign_request<F>(
+        mut self,
+        signer: F,
+    ) -> Self
+    where
+        F: Fn(&mut RequestParts<'_>) + Send + Sync + 'static,
+    {
+        self.request_signer = Some(Arc::new(signer));
+        self
+    }
+
+    /// Registers a hook that runs before every request is sent, including retries,
+    /// e.g. to count requests per endpoint in an app's metrics.
+    ///
+    /// Requests are also traced: each gets a `request` span with its endpoint, status,
+    /// number of retries and latency, under the `notion::request` target.
+    pub fn on_request<F>(
+        mut self,
+        hook: F,
+    ) -> Self
+    where
+        F: Fn(&RequestInfo) + Send + Sync + 'static,
+    {
+        self.on_request = Some(Arc::new(hook));
+        self
+    }
+
+    /// Registers a hook that runs after every response is received, or a request fails
+    /// to get one, including retries, e.g. to record the latency and status of requests.
+    pub fn on_response<F>(
+        mut self,
+        hook: F,
+    ) -> Self
+    where
+        F: Fn(&ResponseInfo) + Send + Sync + 'static,
+    {
+        self.on_response = Some(Arc::new(hook));
+        self
+    }
+
+    /// Retries requests that are rate limited or fail with a server error according to `policy`.
+    /// By default requests are not retried.
+    pub fn retry_policy(
+        self,
+        policy: RetryPolicy,
+    ) -> Self {
+        Self {
+            retry_policy: Some(policy),
+            ..self
+        }
+    }
+
+    /// Throttles the client to send at most `requests_per_second` requests each second,
+    /// after an initial burst of as many requests.
+    /// The limit is shared by all clones of the client, so concurrent tasks are throttled together.
+    /// Notion's documented limit is an average of 3 requests per second.
+    /// A limit of 0 would never send a request, so it's raised to 1 request per second.
+    pub fn requests_per_second(
+        self,
+        requests_per_second: u32,
+    ) -> Self {
+        Self {
+            requests_per_second: Some(requests_per_second),
+            ..self
+        }
+    }
+
+    /// Sends concurrent [appends](NotionApi::append_block_children()) to the same parent
+    /// block one at a time, in the order they were made, so their blocks aren't interleaved.
+    /// Applies to all clones of the client. Disabled by default.
+    pub fn ordered_appends(
+        self,
+        ordered_appends: bool,
+    ) -> Self {
+        Self {
+            ordered_appends,
+            ..self
+        }
+    }
+
+    /// Slows down requests to a page, block or database after Notion reports a write
+    /// conflict (`409`) on it, backing off further with each conflict and speeding back up
+    /// as requests go through. Requests to other objects aren't slowed down.
+    /// With a [retry policy](Self::retry_policy()), conflicted requests are retried too.
+    /// Applies to all clones of the client. Disabled by default.
+    pub fn pace_conflicts(
+        self,
+        pace_conflicts: bool,
+    ) -> Self {
+        Self {
+            pace_conflicts,
+            ..self
+        }
+    }
+
+    /// Caches the pages and databases read with [get_page_cached](NotionApi::get_page_cached())
+    /// and [get_database_cached](NotionApi::get_database_cached()) in `cache`, and serves them
+    /// from it for up to `max_age`. Objects this client changes are dropped from the cache.
+    /// See [crate::cache].
+    pub fn cache<C: Cache + 'static>(
+        self,
+        cache: C,
+        max_age: Duration,
+    ) -> Self {
+        Self {
+            cache: Some(ResponseCache::new(Arc::new(cache), max_age)),
+            ..self
+        }
+    }
+
+    /// Creates the [NotionApi] client.
+    /// May fail if the provided api_token or a header value is an improper value.
+    pub fn build(self) -> Result<NotionApi, Error> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "Notion-Version",
+            HeaderValue::from_str(&self.notion_version).map_err(|source| {
+                Error::InvalidHeaderValue {
+                    name: "Notion-Version",
+                    source,
+                }
+            })?,
+        );
+
+        headers.insert(header::AUTHORIZATION, bearer(&self.api_token)?);
+
+        let user_agent = match &self.app_identifier {
+            Some(app_identifier) => format!("{} {}", self.user_agent, app_identifier),
+            None => self.user_agent,
+        };
+        headers.insert(
+            header::USER_AGENT,
+            HeaderValue::from_str(&user_agent).map_err(|source| Error::InvalidHeaderValue {
+                name: "User-Agent",
+                source,
+            })?,
+        );
+
+        let client = match self.client {
+            Some(client) => client,
+            None => {
+                let mut builder = ClientBuilder::new();
+                if let Some(connect_timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(connect_timeout);
+                }
+                if let Some(redirect_policy) = self.redirect_policy {
+                    builder = builder.redirect(redirect_policy);
+                }
+                for (domain, address) in &self.resolve {
+                    builder = builder.resolve(domain, *address);
+                }
+                builder
+                    .build()
+                    .map_err(|source| Error::ErrorBuildingClient { source })?
+            }
+        };
+
+        let transport = match self.transport {
+            Some(transport) => transport,
+            None => Arc::new(ReqwestTransport::new(client.clone())),
+        };
+
+        Ok(NotionApi {
+            client,
+            transport,
+            base_url: self.base_url,
+            headers,
+            timeout: self.timeout,
+            request_signer: self.request_signer,
+            on_request: self.on_request,
+            on_response: self.on_response,
+            retry_policy: self.retry_policy,
+            rate_limiter: self
+                .requests_per_second
+                .map(|requests_per_second| Arc::new(RateLimiter::new(requests_per_second))),
+            append_queue: if self.ordered_appends {
+                Some(Arc::new(AppendQueue::default()))
+            } else {
+                None
+            },
+            conflict_pacer: if self.pace_conflicts {
+                Some(Arc::new(ConflictPacer::default()))
+            } else {
+                None
+            },
+            cache: self.cache,
+            api_version: self.notion_version.parse().ok(),
+        })
+    }
+}
+
+/// The `Authorization` header for `api_token`, hidden from debug output.
+pub(crate) fn bearer(api_token: &str) -> Result<HeaderValue, Error> {
+    let mut value = HeaderValue::from_str(&format!("Bearer {}", api_token))
+        .map_err(|source| Error::InvalidApiToken { source })?;
+    value.set_sensitive(true);
+    Ok(value)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::builder::{sign, RequestSigner};
+    use crate::ids::PageId;
+    use crate::{Error, NotionApi, NotionApiBuilder, RetryPolicy, TransportResponse};
+    use reqwest::header::HeaderValue;
+    use reqwest::StatusCode;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn signer_sees_encoded_body_and_adds_headers() -> Result<(), Box<dyn std::error::Error>> {
+        let signer: RequestSigner = Arc::new(|parts| {
+            let length = parts.body().map(|body| body.len()).unwrap_or_default();
+            parts
+                .headers_mut()
+                .insert("X-Body-Length", HeaderValue::from(length));
+        });
+
+        let mut request = reqwest::Client::new()
+            .post("https://api.notion.com/v1/search")
+            .json(&serde_json::json!({"query": "notes"}))
+            .build()?;
+
+        sign(&signer, &mut request);
+
+        assert_eq!(request.headers()["X-Body-Length"], "17");
+        assert_eq!(
+            request.headers()[reqwest::header::CONTENT_TYPE],
+            "application/json"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn signer_sees_and_overrides_client_headers() -> Result<(), Box<dyn std::error::Error>> {
+        let sent = Arc::new(Mutex::new(vec![]));
+        let api = NotionApi::builder("secret".to_string())
+            .sign_request(|parts| {
+                let token = parts.headers()[reqwest::header::AUTHORIZATION]
+                    .to_str()
+                    .unwrap()
+                    .to_string();
+                let signed = format!("Gateway {}", token.trim_start_matches("Bearer "));
+                parts.headers_mut().insert(
+                    reqwest::header::AUTHORIZATION,
+                    HeaderValue::from_str(&signed).unwrap(),
+                );
+            })
+            .transport({
+                let sent = sent.clone();
+                move |request: reqwest::Request| {
+                    sent.lock().unwrap().push(request.headers().clone());
+                    Ok(TransportResponse::new(
+                        StatusCode::OK,
+                        include_str!("models/tests/page.json"),
+                    ))
+                }
+            })
+            .build()?;
+
+        api.get_page("b55c9c91384d452b81dbd1ef79372b75".parse::<PageId>()?)
+            .await?;
+
+        let sent = sent.lock().unwrap();
+        assert_eq!(sent[0][reqwest::header::AUTHORIZATION], "Gateway secret");
+        assert!(sent[0].contains_key("Notion-Version"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn configures_from_environment() -> Result<(), Box<dyn std::error::Error>> {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        let no_file = |_: &str| None;
+
+        let builder = NotionApiBuilder::from_lookup(
+            env(&[
+                ("NOTION_API_TOKEN", "secret"),
+                ("NOTION_TIMEOUT_MS", "1500"),
+                ("NOTION_RPS", "3"),
+                ("NOTION_MAX_RETRIES", "4"),
+            ]),
+            no_file,
+        )?;
+        assert_eq!(builder.api_token, "secret");
+        assert_eq!(builder.timeout, Some(Duration::from_millis(1500)));
+        assert_eq!(builder.requests_per_second, Some(3));
+        assert_eq!(
+            builder.retry_policy.map(|policy| policy.max_attempts()),
+            Some(5)
+        );
+
+        let builder = NotionApiBuilder::from_lookup(
+            env(&[("NOTION_API_TOKEN", "secret"), ("NOTION_RPS", "")]),
+            no_file,
+        )?;
+        assert_eq!(builder.requests_per_second, None);
+
+        let invalid = |vars| {
+            matches!(
+                NotionApiBuilder::from_lookup(env(vars), no_file),
+                Err(Error::InvalidEnvironmentVariable {
+                    name: "NOTION_RPS",
+                    ..
+                })
+            )
+        };
+        assert!(invalid(&[
+            ("NOTION_API_TOKEN", "secret"),
+            ("NOTION_RPS", "fast")
+        ]));
+        assert!(invalid(&[
+            ("NOTION_API_TOKEN", "secret"),
+            ("NOTION_RPS", "0")
+        ]));
+        Ok(())
+    }
+
+    #[test]
+    fn reads_token_file_when_environment_variable_is_missing(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let builder =
+            NotionApiBuilder::from_lookup(|_| None, |path| Some(format!("{}-token\n", path)))?;
+        assert_eq!(builder.api_token, ".api_token-token");
+
+        match NotionApiBuilder::from_lookup(|_| None, |_| None) {
+            Err(error @ Error::MissingApiToken { .. }) => assert_eq!(
+                error.to_string(),
+                "No Notion API token found, checked: environment variable NOTION_API_TOKEN, file .api_token"
+            ),
+            _ => panic!("expected a missing token error"),
+        }
+
+        Ok(())
+    }
+
+    /// Answers a single request with `body` and returns the request's head.
+    pub(crate) async fn serve_once(
+        listener: TcpListener,
+        body: &'static str,
+    ) -> std::io::Result<String> {
+        let (mut socket, _) = listener.accept().await?;
+        let mut request = vec![];
+        while !request.ends_with(b"\r\n\r\n") {
+            let mut byte = [0; 1];
+            socket.read_exact(&mut byte).await?;
+            request.push(byte[0]);
+        }
+
+        let response = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        socket.write_all(response.as_bytes()).await?;
+
+        Ok(String::from_utf8_lossy(&request).to_lowercase())
+    }
+
+    #[tokio::test]
+    async fn sends_configured_headers_to_base_url() -> Result<(), Box<dyn std::error::Error>> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let base_url = format!("http://{}/v1/", listener.local_addr()?);
+        let server = tokio::spawn(serve_once(listener, include_str!("models/tests/page.json")));
+
+        let api = NotionApi::builder("secret".to_string())
+            .base_url(base_url)
+            .notion_version("2021-08-16")
+            .app_identifier("notion-tests/1.0")
+            .build()?;
+        let page = api
+            .get_page("b55c9c91384d452b81dbd1ef79372b75".parse::<PageId>()?)
+            .await?;
+        let request = server.await??;
+
+        assert_eq!(page.id.to_string(), "b55c9c91-384d-452b-81db-d1ef79372b75");
+        assert!(
+            request.starts_with("get /v1/pages/b55c9c91-384d-452b-81db-d1ef79372b75 http/1.1\r\n")
+        );
+        assert!(request.contains("\r\nauthorization: bearer secret\r\n"));
+        assert!(request.contains("\r\nnotion-version: 2021-08-16\r\n"));
+        assert!(request.contains(&format!(
+            "\r\nuser-agent: notion/{} notion-tests/1.0\r\n",
+            env!("CARGO_PKG_VERSION")
+        )));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sends_overridden_tokens() -> Result<(), Box<dyn std::error::Error>> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let base_url = format!("http://{}/v1", listener.local_addr()?);
+        let server = tokio::spawn(serve_once(listener, include_str!("models/tests/page.json")));
+
+        let api = NotionApi::builder("secret".to_string())
+            .base_url(base_url)
+            .requests_per_second(3)
+            .build()?;
+        let tenant = api.with_token("tenant-secret")?;
+        tenant
+            .get_page("b55c9c91384d452b81dbd1ef79372b75".parse::<PageId>()?)
+            .await?;
+        let request = server.await??;
+
+        assert!(request.contains("\r\nauthorization: bearer tenant-secret\r\n"));
+        assert!(request.contains("\r\nnotion-version: "));
+        assert!(matches!(
+            api.with_token("bad\ntoken"),
+            Err(Error::InvalidApiToken { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sends_requests_with_custom_transports() -> Result<(), Box<dyn std::error::Error>> {
+        let attempts = AtomicUsize::new(0);
+        let api = NotionApi::builder("secret".to_string())
+            .retry_policy(RetryPolicy::new(2).with_initial_backoff(Duration::ZERO))
+            .transport(move |request: reqwest::Request| {
+                assert_eq!(
+                    request.url().path(),
+                    "/v1/pages/b55c9c91-384d-452b-81db-d1ef79372b75"
+                );
+                assert!(request.headers().contains_key("notion-version"));
+                Ok(match attempts.fetch_add(1, Ordering::SeqCst) {
+                    0 => TransportResponse::new(StatusCode::SERVICE_UNAVAILABLE, ""),
+                    _ => TransportResponse::new(
+                        StatusCode::OK,
+                        include_str!("models/tests/page.json"),
+                    ),
+                })
+            })
+            .build()?;
+
+        let page = api
+            .get_page("b55c9c91384d452b81dbd1ef79372b75".parse::<PageId>()?)
+            .await?;
+        assert_eq!(page.id.to_string(), "b55c9c91-384d-452b-81db-d1ef79372b75");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reports_every_attempt_to_hooks() -> Result<(), Box<dyn std::error::Error>> {
+        let attempts = AtomicUsize::new(0);
+        let requests = Arc::new(Mutex::new(vec![]));
+        let responses = Arc::new(Mutex::new(vec![]));
+        let (sent, received) = (requests.clone(), responses.clone());
+        let api = NotionApi::builder("secret".to_string())
+            .retry_policy(RetryPolicy::new(2).with_initial_backoff(Duration::ZERO))
+            .on_request(move |request| {
+                sent.lock()
+                    .unwrap()
+                    .push((request.endpoint.clone(), request.attempt))
+            })
+            .on_response(move |response| {
+                received.lock().unwrap().push((
+                    response.endpoint.clone(),
+                    response.status,
+                    response.attempt,
+                ))
+            })
+            .transport(move |_| {
+                Ok(match attempts.fetch_add(1, Ordering::SeqCst) {
+                    0 => TransportResponse::new(StatusCode::TOO_MANY_REQUESTS, ""),
+                    _ => TransportResponse::new(
+                        StatusCode::OK,
+                        include_str!("models/tests/page.json"),
+                    ),
+                })
+            })
+            .build()?;
+
+        api.get_page("b55c9c91384d452b81dbd1ef79372b75".parse::<PageId>()?)
+            .await?;
+
+        let endpoint = "GET /pages/{id}".to_string();
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec![(endpoint.clone(), 1), (endpoint.clone(), 2)]
+        );
+        assert_eq!(
+            *responses.lock().unwrap(),
+            vec![
+                (endpoint.clone(), Some(StatusCode::TOO_MANY_REQUESTS), 1),
+                (endpoint, Some(StatusCode::OK), 2)
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn resolves_overridden_domains() -> Result<(), Box<dyn std::error::Error>> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let address = listener.local_addr()?;
+        let server = tokio::spawn(serve_once(listener, include_str!("models/tests/page.json")));
+
+        let api = NotionApi::builder("secret".to_string())
+            .base_url(format!("http://api.notion.test:{}/v1", address.port()))
+            .resolve("api.notion.test", address)
+            .build()?;
+        api.get_page("b55c9c91384d452b81dbd1ef79372b75".parse::<PageId>()?)
+            .await?;
+        let request = server.await??;
+
+        assert!(request.contains(&format!("\r\nhost: api.notion.test:{}\r\n", address.port())));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,293 @@
+//! Caching of pages and databases for clients that read the same objects often,
+//! like dashboards that poll Notion.
+//!
+//! Notion doesn't support conditional requests, so a cached object is served until it's
+//! older than the client's max age, and dropped once the client has changed the object.
+//! Changes made elsewhere show up once the cached object expires.
+//!
+//! Enable it with [NotionApiBuilder::cache()](crate::NotionApiBuilder::cache()), then read
+//! objects with [NotionApi::get_page_cached()] and [NotionApi::get_database_cached()].
+
+use crate::ids::{AsIdentifier, DatabaseId, PageId};
+use crate::models::{Database, Page};
+use crate::{Error, NotionApi};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Stores cached objects, e.g. in memory with [MemoryCache] or in a shared store
+/// so that several processes share a cache.
+///
+/// Keys are the kind of object and its id, lowercased without dashes,
+/// e.g. `pages/b55c9c91384d452b81dbd1ef79372b75`.
+pub trait Cache: Send + Sync {
+    fn get(
+        &self,
+        key: &str,
+    ) -> Option<CachedObject>;
+
+    fn insert(
+        &self,
+        key: String,
+        object: CachedObject,
+    );
+
+    fn remove(
+        &self,
+        key: &str,
+    );
+}
+
+/// An object as Notion returned it, with when it was fetched.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct CachedObject {
+    pub value: serde_json::Value,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// A [Cache] in the memory of the process. Objects are kept until they're replaced or removed.
+#[derive(Debug, Default)]
+pub struct MemoryCache {
+    objects: Mutex<HashMap<String, CachedObject>>,
+}
+
+impl Cache for MemoryCache {
+    fn get(
+        &self,
+        key: &str,
+    ) -> Option<CachedObject> {
+        self.objects.lock().unwrap().get(key).cloned()
+    }
+
+    fn insert(
+        &self,
+        key: String,
+        object: CachedObject,
+    ) {
+        self.objects.lock().unwrap().insert(key, object);
+    }
+
+    fn remove(
+        &self,
+        key: &str,
+    ) {
+        self.objects.lock().unwrap().remove(key);
+    }
+}
+
+/// The cache of a client and how long its objects are served.
+#[derive(Clone)]
+pub(crate) struct ResponseCache {
+    cache: Arc<dyn Cache>,
+    max_age: Duration,
+}
+
+impl ResponseCache {
+    pub(crate) fn new(
+        cache: Arc<dyn Cache>,
+        max_age: Duration,
+    ) -> Self {
+        Self { cache, max_age }
+    }
+
+    /// The cached object, unless it's missing, expired or no longer parses.
+    fn get<T: DeserializeOwned>(
+        &self,
+        key: &str,
+    ) -> Option<T> {
+        let object = self.cache.get(key)?;
+        let age = (Utc::now() - object.fetched_at)
+            .to_std()
+            .unwrap_or_default();
+        if age >= self.max_age {
+            return None;
+        }
+        serde_json::from_value(object.value).ok()
+    }
+
+    fn insert<T: Serialize>(
+        &self,
+        key: String,
+        value: &T,
+    ) {
+        if let Ok(value) = serde_json::to_value(value) {
+            self.cache.insert(
+                key,
+                CachedObject {
+                    value,
+                    fetched_at: Utc::now(),
+                },
+            );
+        }
+    }
+
+    /// Drops the cached page or database with the id, as normalized by
+    /// [object_id](crate::conflict_pacer::object_id()).
+    pub(crate) fn invalidate(
+        &self,
+        object: &str,
+    ) {
+        self.cache.remove(&format!("pages/{}", object));
+        self.cache.remove(&format!("databases/{}", object));
+    }
+}
+
+fn key(
+    kind: &str,
+    id: &str,
+) -> String {
+    format!("{}/{}", kind, id.replace('-', "").to_lowercase())
+}
+
+impl NotionApi {
+    /// Gets a page like [get_page](NotionApi::get_page()), from the client's
+    /// [cache](crate::NotionApiBuilder::cache()) while it's fresh.
+    /// Without a cache, the page is always fetched.
+    pub async fn get_page_cached<T: AsIdentifier<PageId>>(
+        &self,
+        page_id: T,
+    ) -> Result<Page, Error> {
+        let cache = match &self.cache {
+            Some(cache) => cache,
+            None => return self.get_page(page_id).await,
+        };
+
+        let key = key("pages", &page_id.as_id().to_string());
+        if let Some(page) = cache.get(&key) {
+            return Ok(page);
+        }
+        let page = self.get_page(page_id).await?;
+        cache.insert(key, &page);
+        Ok(page)
+    }
+
+    /// Gets a database like [get_database](NotionApi::get_database()), from the client's
+    /// [cache](crate::NotionApiBuilder::cache()) while it's fresh.
+    /// Without a cache, the database is always fetched.
+    pub async fn get_database_cached<T: AsIdentifier<DatabaseId>>(
+        &self,
+        database_id: T,
+    ) -> Result<Database, Error> {
+        let cache = match &self.cache {
+            Some(cache) => cache,
+            None => return self.get_database(database_id).await,
+        };
+
+        let key = key("databases", &database_id.as_id().to_string());
+        if let Some(database) = cache.get(&key) {
+            return Ok(database);
+        }
+        let database = self.get_database(database_id).await?;
+        cache.insert(key, &database);
+        Ok(database)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cache::{Cache, CachedObject, MemoryCache};
+    use crate::ids::PageId;
+    use crate::{NotionApi, TransportResponse};
+    use chrono::Utc;
+    use reqwest::{Method, StatusCode};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn serves_pages_until_they_change() -> Result<(), Box<dyn std::error::Error>> {
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let counted = fetches.clone();
+        let api = NotionApi::builder("secret".to_string())
+            .cache(MemoryCache::default(), Duration::from_secs(60))
+            .transport(move |request: reqwest::Request| {
+                if request.method() == Method::GET {
+                    counted.fetch_add(1, Ordering::SeqCst);
+                }
+                Ok(TransportResponse::new(
+                    StatusCode::OK,
+                    include_str!("models/tests/page.json"),
+                ))
+            })
+            .build()?;
+        let page_id: PageId = "b55c9c91-384d-452b-81db-d1ef79372b75".parse()?;
+
+        let page = api.get_page_cached(&page_id).await?;
+        assert_eq!(api.get_page_cached(&page_id).await?, page);
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        api.archive_page(&page_id).await?;
+        api.get_page_cached(&page_id).await?;
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+
+        let uncached = api.with_token("other")?;
+        uncached.get_page_cached(&page_id).await?;
+        assert_eq!(fetches.load(Ordering::SeqCst), 3);
+
+        Ok(())
+    }
+
+    /// A cache shared with the test.
+    struct Shared(Arc<MemoryCache>);
+
+    impl Cache for Shared {
+        fn get(
+            &self,
+            key: &str,
+        ) -> Option<CachedObject> {
+            self.0.get(key)
+        }
+
+        fn insert(
+            &self,
+            key: String,
+            object: CachedObject,
+        ) {
+            self.0.insert(key, object)
+        }
+
+        fn remove(
+            &self,
+            key: &str,
+        ) {
+            self.0.remove(key)
+        }
+    }
+
+    #[tokio::test]
+    async fn drops_pages_read_while_they_change() -> Result<(), Box<dyn std::error::Error>> {
+        let cache = Arc::new(MemoryCache::default());
+        let shared = cache.clone();
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let counted = fetches.clone();
+        let api = NotionApi::builder("secret".to_string())
+            .cache(Shared(cache), Duration::from_secs(60))
+            .transport(move |request: reqwest::Request| {
+                let page = include_str!("models/tests/page.json");
+                if request.method() == Method::GET {
+                    counted.fetch_add(1, Ordering::SeqCst);
+                } else {
+                    // A read that completes while the page is being changed.
+                    shared.insert(
+                        "pages/b55c9c91384d452b81dbd1ef79372b75".to_string(),
+                        CachedObject {
+                            value: serde_json::from_str(page).unwrap(),
+                            fetched_at: Utc::now(),
+                        },
+                    );
+                }
+                Ok(TransportResponse::new(StatusCode::OK, page))
+            })
+            .build()?;
+        let page_id: PageId = "b55c9c91-384d-452b-81db-d1ef79372b75".parse()?;
+
+        api.archive_page(&page_id).await?;
+        api.get_page_cached(&page_id).await?;
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,298 @@
+//! Configuration for command line tools: the API token and the database and page
+//! the tool works with by default.
+//!
+//! [CliConfig::load()] reads `$XDG_CONFIG_HOME/<app>/config.toml`
+//! (`~/.config/<app>/config.toml` when `XDG_CONFIG_HOME` isn't set),
+//! then applies these environment variables on top:
+//!
+//! - `NOTION_API_TOKEN`
+//! - `NOTION_DATABASE_ID`
+//! - `NOTION_PAGE_ID`
+//!
+//! A config file holding the API token must only be readable by its owner.
+
+use crate::ids::{DatabaseId, PageId};
+use crate::{Error, NotionApi};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("Error reading config file {}: {}", .path.display(), source)]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Error writing config file {}: {}", .path.display(), source)]
+    Write {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Error parsing config file {}: {}", .path.display(), source)]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+
+    #[error("Error serializing config: {}", source)]
+    Serialize { source: toml::ser::Error },
+
+    #[error(
+        "Config file {} holds an API token but can be read by other users (mode {:o}), run `chmod 600` on it",
+        .path.display(),
+        mode
+    )]
+    InsecurePermissions { path: PathBuf, mode: u32 },
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Eq, PartialEq, Clone)]
+pub struct CliConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_token: Option<String>,
+    /// The database the tool works with when none is given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_database: Option<DatabaseId>,
+    /// The page the tool works with when none is given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_page: Option<PageId>,
+}
+
+impl CliConfig {
+    /// Where the config file of `app` is, if a config directory can be found.
+    pub fn path(app: &str) -> Option<PathBuf> {
+        config_path(app, |name| std::env::var(name).ok())
+    }
+
+    /// Reads the config file of `app` and applies the environment variables on top of it.
+    pub fn load(app: &str) -> Result<Self, ConfigError> {
+        let config = match Self::path(app) {
+            Some(path) => Self::from_file(&path)?,
+            None => Self::default(),
+        };
+        Ok(config.with_lookup(|name| std::env::var(name).ok()))
+    }
+
+    /// Reads a config file. A missing file is an empty config.
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Self::default())
+            }
+            Err(source) => {
+                return Err(ConfigError::Read {
+                    path: path.to_path_buf(),
+                    source,
+                })
+            }
+        };
+        let config: Self = toml::from_str(&contents).map_err(|source| ConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        if config.api_token.is_some() {
+            check_permissions(path)?;
+        }
+        Ok(config)
+    }
+
+    /// Writes the config to `path`, creating its directory if needed.
+    /// The file is only readable by its owner.
+    pub fn save(
+        &self,
+        path: &Path,
+    ) -> Result<(), ConfigError> {
+        let contents = toml::to_string(self).map_err(|source| ConfigError::Serialize { source })?;
+        let write_error = |source| ConfigError::Write {
+            path: path.to_path_buf(),
+            source,
+        };
+
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory).map_err(write_error)?;
+        }
+        let mut file = open_private(path).map_err(write_error)?;
+        file.write_all(contents.as_bytes()).map_err(write_error)
+    }
+
+    /// Creates a client with the configured API token.
+    pub fn api(&self) -> Result<NotionApi, Error> {
+        match &self.api_token {
+            Some(api_token) => NotionApi::new(api_token.clone()),
+            None => Err(Error::MissingApiToken {
+                checked: vec![
+                    "environment variable NOTION_API_TOKEN".to_string(),
+                    "config file".to_string(),
+                ],
+            }),
+        }
+    }
+
+    fn with_lookup<F: Fn(&str) -> Option<String>>(
+        self,
+        lookup: F,
+    ) -> Self {
+        let var = |name| {
+            lookup(name)
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+
+        Self {
+            api_token: var("NOTION_API_TOKEN").or(self.api_token),
+            default_database: var("NOTION_DATABASE_ID")
+                .and_then(|id| id.parse().ok())
+                .or(self.default_database),
+            default_page: var("NOTION_PAGE_ID")
+                .and_then(|id| id.parse().ok())
+                .or(self.default_page),
+        }
+    }
+}
+
+fn config_path<F: Fn(&str) -> Option<String>>(
+    app: &str,
+    lookup: F,
+) -> Option<PathBuf> {
+    let directory = match lookup("XDG_CONFIG_HOME").filter(|value| !value.is_empty()) {
+        Some(directory) => PathBuf::from(directory),
+        None => PathBuf::from(lookup("HOME")?).join(".config"),
+    };
+    Some(directory.join(app).join("config.toml"))
+}
+
+#[cfg(unix)]
+fn check_permissions(path: &Path) -> Result<(), ConfigError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let metadata = std::fs::metadata(path).map_err(|source| ConfigError::Read {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let mode = metadata.permissions().mode() & 0o777;
+    if mode & 0o077 != 0 {
+        return Err(ConfigError::InsecurePermissions {
+            path: path.to_path_buf(),
+            mode,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_permissions(_path: &Path) -> Result<(), ConfigError> {
+    Ok(())
+}
+
+#[cfg(unix)]
+fn open_private(path: &Path) -> std::io::Result<std::fs::File> {
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    let file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    // The mode only applies to new files.
+    file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    Ok(file)
+}
+
+#[cfg(not(unix))]
+fn open_private(path: &Path) -> std::io::Result<std::fs::File> {
+    std::fs::File::create(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{config_path, CliConfig, ConfigError};
+    use std::path::PathBuf;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("notion-config-{}-{}", name, std::process::id()))
+            .join("config.toml")
+    }
+
+    #[test]
+    fn finds_the_config_file() {
+        let lookup = |xdg: Option<&'static str>| {
+            move |name: &str| match name {
+                "XDG_CONFIG_HOME" => xdg.map(str::to_string),
+                "HOME" => Some("/home/me".to_string()),
+                _ => None,
+            }
+        };
+
+        assert_eq!(
+            config_path("todo", lookup(Some("/xdg"))),
+            Some(PathBuf::from("/xdg/todo/config.toml"))
+        );
+        assert_eq!(
+            config_path("todo", lookup(None)),
+            Some(PathBuf::from("/home/me/.config/todo/config.toml"))
+        );
+        assert_eq!(config_path("todo", |_| None), None);
+    }
+
+    #[test]
+    fn saves_and_loads_with_environment_overrides() -> Result<(), Box<dyn std::error::Error>> {
+        let path = temp_path("round-trip");
+        assert_eq!(CliConfig::from_file(&path)?, CliConfig::default());
+
+        let config = CliConfig {
+            api_token: Some("secret_from_file".to_string()),
+            default_database: Some("668d797c-76fa-4934-9b05-ad288df2d136".parse()?),
+            default_page: None,
+        };
+        config.save(&path)?;
+        assert_eq!(CliConfig::from_file(&path)?, config);
+
+        let config = CliConfig::from_file(&path)?.with_lookup(|name| match name {
+            "NOTION_API_TOKEN" => Some("secret_from_env".to_string()),
+            "NOTION_PAGE_ID" => Some("98ad959b2b6a477480ee00246fb0ea9b".to_string()),
+            "NOTION_DATABASE_ID" => Some(" ".to_string()),
+            _ => None,
+        });
+        assert_eq!(config.api_token.as_deref(), Some("secret_from_env"));
+        assert_eq!(
+            config.default_database,
+            Some("668d797c-76fa-4934-9b05-ad288df2d136".parse()?)
+        );
+        assert_eq!(
+            config.default_page,
+            Some("98ad959b-2b6a-4774-80ee-00246fb0ea9b".parse()?)
+        );
+
+        std::fs::remove_dir_all(path.parent().unwrap())?;
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_tokens_readable_by_others() -> Result<(), Box<dyn std::error::Error>> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = temp_path("insecure");
+        std::fs::create_dir_all(path.parent().unwrap())?;
+        std::fs::write(&path, "api_token = \"secret\"\n")?;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644))?;
+
+        match CliConfig::from_file(&path) {
+            Err(ConfigError::InsecurePermissions { mode, .. }) => assert_eq!(mode, 0o644),
+            result => panic!("Expected insecure permissions, got {:?}", result),
+        }
+
+        // Without a token the file doesn't need to be private.
+        std::fs::write(&path, "default_page = \"page\"\n")?;
+        assert!(CliConfig::from_file(&path).is_ok());
+
+        std::fs::remove_dir_all(path.parent().unwrap())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,152 @@
+use reqwest::Url;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The first delay after a conflict, when Notion doesn't send a `Retry-After` header.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(250);
+const MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+/// Paces requests to objects that recently had write conflicts (`409`),
+/// shared by every clone of a [NotionApi](crate::NotionApi) client.
+///
+/// Each conflict doubles the object's backoff, and requests to the object are then
+/// sent at most one per backoff. Each request that doesn't conflict halves it again,
+/// until the object is forgotten. Requests to other objects are never delayed.
+#[derive(Debug, Default)]
+pub(crate) struct ConflictPacer {
+    objects: Mutex<HashMap<String, Pace>>,
+}
+
+#[derive(Debug)]
+struct Pace {
+    backoff: Duration,
+    /// When the next request to the object may be sent.
+    next: Instant,
+}
+
+impl ConflictPacer {
+    /// Waits until a request to `object` may be sent.
+    pub(crate) async fn acquire(
+        &self,
+        object: &str,
+    ) {
+        let delay = self.reserve(object, Instant::now());
+        if !delay.is_zero() {
+            tracing::trace!(
+                object,
+                delay_ms = delay.as_millis() as u64,
+                "Pacing request to conflicted object"
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Takes the next slot for a request to `object` and returns how long to wait for it.
+    fn reserve(
+        &self,
+        object: &str,
+        now: Instant,
+    ) -> Duration {
+        match self.objects().get_mut(object) {
+            Some(pace) => {
+                let slot = pace.next.max(now);
+                pace.next = slot + pace.backoff;
+                slot - now
+            }
+            None => Duration::ZERO,
+        }
+    }
+
+    /// Backs off from `object` after a conflict, for `retry_after` when Notion asks for it.
+    pub(crate) fn conflicted(
+        &self,
+        object: &str,
+        retry_after: Option<Duration>,
+        now: Instant,
+    ) {
+        let mut objects = self.objects();
+        let pace = objects.entry(object.to_string()).or_insert(Pace {
+            backoff: Duration::ZERO,
+            next: now,
+        });
+        pace.backoff =
+            retry_after.unwrap_or_else(|| (pace.backoff * 2).clamp(INITIAL_BACKOFF, MAX_BACKOFF));
+        pace.next = pace.next.max(now + pace.backoff);
+    }
+
+    /// Speeds requests to `object` back up after one went through without a conflict.
+    pub(crate) fn settled(
+        &self,
+        object: &str,
+    ) {
+        let mut objects = self.objects();
+        if let Some(pace) = objects.get_mut(object) {
+            pace.backoff /= 2;
+            if pace.backoff < INITIAL_BACKOFF {
+                objects.remove(object);
+            }
+        }
+    }
+
+    fn objects(&self) -> std::sync::MutexGuard<'_, HashMap<String, Pace>> {
+        self.objects.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// The page, block or database a request is for, e.g. `abc` for `/v1/blocks/abc/children`.
+pub(crate) fn object_id(url: &Url) -> Option<String> {
+    let mut segments = url.path_segments()?;
+    segments.find(|segment| matches!(*segment, "pages" | "blocks" | "databases"))?;
+    let id = segments.next().filter(|id| !id.is_empty())?;
+    // Ids are accepted with and without dashes.
+    Some(id.replace('-', "").to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::conflict_pacer::{object_id, ConflictPacer, INITIAL_BACKOFF, MAX_BACKOFF};
+    use reqwest::Url;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn paces_only_the_conflicted_object() {
+        let pacer = ConflictPacer::default();
+        let now = Instant::now();
+
+        pacer.conflicted("hot", None, now);
+        assert_eq!(pacer.reserve("hot", now), INITIAL_BACKOFF);
+        assert_eq!(pacer.reserve("hot", now), INITIAL_BACKOFF * 2);
+        assert_eq!(pacer.reserve("cold", now), Duration::ZERO);
+
+        pacer.conflicted("hot", None, now);
+        pacer.conflicted("hot", None, now);
+        pacer.conflicted("hot", Some(Duration::from_secs(3)), now);
+        pacer.conflicted("hot", None, now);
+        assert!(pacer.objects()["hot"].backoff <= MAX_BACKOFF);
+
+        for _ in 0..6 {
+            pacer.settled("hot");
+        }
+        assert!(pacer.objects().is_empty());
+        assert_eq!(pacer.reserve("hot", now), Duration::ZERO);
+    }
+
+    #[test]
+    fn finds_the_object_of_a_request() -> Result<(), Box<dyn std::error::Error>> {
+        let id = |url: &str| Url::parse(url).map(|url| object_id(&url));
+
+        assert_eq!(
+            id("https://api.notion.com/v1/blocks/B55C9C91-384d-452b-81db-d1ef79372b75/children")?,
+            Some("b55c9c91384d452b81dbd1ef79372b75".to_string())
+        );
+        assert_eq!(
+            id("https://api.notion.com/v1/pages/b55c9c91384d452b81dbd1ef79372b75")?,
+            Some("b55c9c91384d452b81dbd1ef79372b75".to_string())
+        );
+        assert_eq!(id("https://api.notion.com/v1/pages")?, None);
+        assert_eq!(id("https://api.notion.com/v1/search")?, None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,801 @@
+//! Comparisons between two versions of Notion objects, and the updates that turn one into the other.
+
+use crate::ids::{AsIdentifier, BlockId, PageId};
+use crate::models::block::{Block, CreateBlock};
+use crate::models::properties::{
+    CreatePropertyValue, CreateSelectedValue, PropertyValue, RelationValue, SelectedValue,
+};
+use crate::models::{Page, PageUpdateRequest, Properties};
+use crate::{Error, NotionApi};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::mem::discriminant;
+
+/// A change to a single property between two versions of a page's [Properties].
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum PropertyChange {
+    /// The property only exists in the new version, e.g. after a schema change.
+    Added { name: String, value: PropertyValue },
+    /// The property only exists in the old version.
+    Removed { name: String, value: PropertyValue },
+    /// The property had a value and is now empty.
+    Cleared { name: String, old: PropertyValue },
+    /// A select or status property now has a different option selected.
+    SelectChanged {
+        name: String,
+        from: Option<String>,
+        to: Option<String>,
+    },
+    /// Options were added to or removed from a multi-select property.
+    MultiSelectChanged {
+        name: String,
+        added: Vec<String>,
+        removed: Vec<String>,
+    },
+    /// Pages were added to or removed from a relation property.
+    RelationChanged {
+        name: String,
+        added: Vec<PageId>,
+        removed: Vec<PageId>,
+    },
+    /// Any other change of a property's value.
+    Changed {
+        name: String,
+        old: PropertyValue,
+        new: PropertyValue,
+    },
+}
+
+impl PropertyChange {
+    /// The name of the property that changed.
+    pub fn name(&self) -> &str {
+        use PropertyChange::*;
+        match self {
+            Added { name, .. }
+            | Removed { name, .. }
+            | Cleared { name, .. }
+            | SelectChanged { name, .. }
+            | MultiSelectChanged { name, .. }
+            | RelationChanged { name, .. }
+            | Changed { name, .. } => name,
+        }
+    }
+}
+
+impl Display for PropertyChange {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        use PropertyChange::*;
+        match self {
+            Added { name, .. } => write!(f, "{} added", name),
+            Removed { name, .. } => write!(f, "{} removed", name),
+            Cleared { name, .. } => write!(f, "{} cleared", name),
+            SelectChanged { name, from, to } => write!(
+                f,
+                "{}: {} → {}",
+                name,
+                from.as_deref().unwrap_or("(none)"),
+                to.as_deref().unwrap_or("(none)")
+            ),
+            MultiSelectChanged {
+                name,
+                added,
+                removed,
+            } => write_added_and_removed(f, name, added, removed),
+            RelationChanged {
+                name,
+                added,
+                removed,
+            } => write_added_and_removed(f, name, added, removed),
+            Changed { name, .. } => write!(f, "{} changed", name),
+        }
+    }
+}
+
+fn write_added_and_removed<T: Display>(
+    f: &mut Formatter<'_>,
+    name: &str,
+    added: &[T],
+    removed: &[T],
+) -> std::fmt::Result {
+    let join = |items: &[T]| {
+        items
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    write!(f, "{}:", name)?;
+    if !added.is_empty() {
+        write!(f, " added {}", join(added))?;
+    }
+    if !added.is_empty() && !removed.is_empty() {
+        write!(f, ";")?;
+    }
+    if !removed.is_empty() {
+        write!(f, " removed {}", join(removed))?;
+    }
+    Ok(())
+}
+
+/// Compares two versions of a page's properties, returning the changes ordered by property name.
+/// Properties with equal values are not reported.
+pub fn diff_properties(
+    old: &Properties,
+    new: &Properties,
+) -> Vec<PropertyChange> {
+    let mut changes: Vec<PropertyChange> = old
+        .properties
+        .iter()
+        .filter_map(|(name, old_value)| match new.properties.get(name) {
+            None => Some(PropertyChange::Removed {
+                name: name.clone(),
+                value: old_value.clone(),
+            }),
+            Some(new_value) if new_value != old_value => {
+                Some(diff_value(name, old_value, new_value))
+            }
+            Some(_) => None,
+        })
+        .collect();
+
+    changes.extend(
+        new.properties
+            .iter()
+            .filter(|(name, _)| !old.properties.contains_key(*name))
+            .map(|(name, value)| PropertyChange::Added {
+                name: name.clone(),
+                value: value.clone(),
+            }),
+    );
+
+    changes.sort_by(|a, b| a.name().cmp(b.name()));
+    changes
+}
+
+fn diff_value(
+    name: &str,
+    old: &PropertyValue,
+    new: &PropertyValue,
+) -> PropertyChange {
+    let name = name.to_string();
+
+    if is_empty(new) && !is_empty(old) {
+        return PropertyChange::Cleared {
+            name,
+            old: old.clone(),
+        };
+    }
+
+    match (old, new) {
+        (PropertyValue::Select { select: from, .. }, PropertyValue::Select { select: to, .. })
+        | (PropertyValue::Status { status: from, .. }, PropertyValue::Status { status: to, .. }) => {
+            PropertyChange::SelectChanged {
+                name,
+                from: from.as_ref().and_then(|value| value.name.clone()),
+                to: to.as_ref().and_then(|value| value.name.clone()),
+            }
+        }
+        (
+            PropertyValue::MultiSelect {
+                multi_select: old, ..
+            },
+            PropertyValue::MultiSelect {
+                multi_select: new, ..
+            },
+        ) => {
+            let old = option_names(old.as_deref().unwrap_or_default());
+            let new = option_names(new.as_deref().unwrap_or_default());
+            let (added, removed) = added_and_removed(&old, &new);
+            PropertyChange::MultiSelectChanged {
+                name,
+                added,
+                removed,
+            }
+        }
+        (
+            PropertyValue::Relation { relation: old, .. },
+            PropertyValue::Relation { relation: new, .. },
+        ) => {
+            let old = relation_ids(old.as_deref().unwrap_or_default());
+            let new = relation_ids(new.as_deref().unwrap_or_default());
+            let (added, removed) = added_and_removed(&old, &new);
+            PropertyChange::RelationChanged {
+                name,
+                added,
+                removed,
+            }
+        }
+        _ => PropertyChange::Changed {
+            name,
+            old: old.clone(),
+            new: new.clone(),
+        },
+    }
+}
+
+fn option_names(options: &[SelectedValue]) -> Vec<String> {
+    options
+        .iter()
+        .filter_map(|option| option.name.clone())
+        .collect()
+}
+
+fn relation_ids(relations: &[RelationValue]) -> Vec<PageId> {
+    relations
+        .iter()
+        .map(|relation| relation.id.clone())
+        .collect()
+}
+
+fn added_and_removed<T: PartialEq + Clone>(
+    old: &[T],
+    new: &[T],
+) -> (Vec<T>, Vec<T>) {
+    let added = new
+        .iter()
+        .filter(|item| !old.contains(item))
+        .cloned()
+        .collect();
+    let removed = old
+        .iter()
+        .filter(|item| !new.contains(item))
+        .cloned()
+        .collect();
+    (added, removed)
+}
+
+fn is_empty(value: &PropertyValue) -> bool {
+    use PropertyValue::*;
+    match value {
+        Title { title, .. } => title.is_empty(),
+        Text { rich_text, .. } => rich_text.is_empty(),
+        Number { number, .. } => number.is_none(),
+        Select { select, .. } => select.is_none(),
+        Status { status, .. } => status.is_none(),
+        MultiSelect { multi_select, .. } => multi_select.as_deref().unwrap_or_default().is_empty(),
+        Date { date, .. } => date.is_none(),
+        Relation { relation, .. } => relation.as_deref().unwrap_or_default().is_empty(),
+        People { people, .. } => people.is_empty(),
+        Files { files, .. } => files.as_deref().unwrap_or_default().is_empty(),
+        Url { url, .. } => url.is_none(),
+        Email { email, .. } => email.is_none(),
+        PhoneNumber { phone_number, .. } => phone_number.is_empty(),
+        _ => false,
+    }
+}
+
+/// The smallest update that turns `old` into `new`: the properties, icon and cover that changed.
+///
+/// Properties Notion computes itself, like formulas, are left out, as are properties
+/// only `old` has and an icon or cover `new` no longer has, which an update can't remove.
+pub fn page_update(
+    old: &Page,
+    new: &Page,
+) -> PageUpdateRequest {
+    let properties = new
+        .properties
+        .properties
+        .iter()
+        .filter(|(name, value)| old.properties.properties.get(*name) != Some(value))
+        .filter_map(|(name, value)| Some((name.clone(), CreatePropertyValue::from_value(value)?)))
+        .collect();
+
+    PageUpdateRequest {
+        properties,
+        icon: new.icon.clone().filter(|_| new.icon != old.icon),
+        cover: new.cover.clone().filter(|_| new.cover != old.cover),
+    }
+}
+
+/// The smallest update that gives `page` the `desired` property values, for jobs that
+/// reconcile pages with another source on every run.
+///
+/// Values are compared the way Notion stores them: text matches regardless of the
+/// annotations and plain text Notion fills in, and options match by id or by name,
+/// whichever `desired` uses. Properties the page doesn't have are always included.
+pub fn reconcile_properties(
+    page: &Page,
+    desired: &HashMap<String, CreatePropertyValue>,
+) -> PageUpdateRequest {
+    let properties = desired
+        .iter()
+        .filter(
+            |(name, desired)| match page.properties.properties.get(*name) {
+                Some(current) => !matches_value(current, desired),
+                None => true,
+            },
+        )
+        .map(|(name, desired)| (name.clone(), desired.clone()))
+        .collect();
+
+    PageUpdateRequest {
+        properties,
+        ..PageUpdateRequest::default()
+    }
+}
+
+fn matches_value(
+    current: &PropertyValue,
+    desired: &CreatePropertyValue,
+) -> bool {
+    match (current, desired) {
+        (PropertyValue::Select { select, .. }, CreatePropertyValue::Select(desired))
+        | (PropertyValue::Status { status: select, .. }, CreatePropertyValue::Status(desired)) => {
+            match (select, desired) {
+                (Some(current), Some(desired)) => matches_option(current, desired),
+                (current, desired) => current.is_none() && desired.is_none(),
+            }
+        }
+        (
+            PropertyValue::MultiSelect { multi_select, .. },
+            CreatePropertyValue::MultiSelect(desired),
+        ) => {
+            let current = multi_select.as_deref().unwrap_or_default();
+            current.len() == desired.len()
+                && current
+                    .iter()
+                    .zip(desired)
+                    .all(|(current, desired)| matches_option(current, desired))
+        }
+        (current, desired) => match CreatePropertyValue::from_value(current) {
+            Some(current) => normalized(&current) == normalized(desired),
+            None => false,
+        },
+    }
+}
+
+fn matches_option(
+    current: &SelectedValue,
+    desired: &CreateSelectedValue,
+) -> bool {
+    match desired {
+        CreateSelectedValue::Id { id } => current.id.as_ref() == Some(id),
+        CreateSelectedValue::Name { name } => current.name.as_ref() == Some(name),
+    }
+}
+
+/// A change to the children of a block, see [diff_blocks],
+/// applied with [NotionApi::apply_block_operations()].
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum BlockOperation {
+    /// Replace the content of the block, keeping its id and position,
+    /// with [NotionApi::update_block()].
+    Update {
+        block_id: BlockId,
+        block: CreateBlock,
+    },
+    /// Delete the block, along with its children, with [NotionApi::trash_block()].
+    Delete { block_id: BlockId },
+    /// Append the blocks after the remaining children, with [NotionApi::append_block_tree()].
+    Append { blocks: Vec<CreateBlock> },
+}
+
+/// The operations that turn the `current` children of a block into the `desired` ones,
+/// in the order to apply them: updates, then deletes, then a single append.
+///
+/// Notion only appends at the end, so blocks are matched by position. Blocks of the same kind
+/// are updated in place; from the first block that can't be, the remaining children are
+/// deleted and the remaining desired blocks appended. Blocks are compared without their
+/// children, which Notion doesn't return along with a block, so diff those separately.
+pub fn diff_blocks(
+    current: &[Block],
+    desired: &[CreateBlock],
+) -> Vec<BlockOperation> {
+    let mut operations = vec![];
+    let mut kept = 0;
+
+    for (block, wanted) in current.iter().zip(desired) {
+        let existing = without_children(CreateBlock::from(block.clone()));
+        let wanted = without_children(wanted.clone());
+        if normalized(&existing) != normalized(&wanted) {
+            if discriminant(&existing) != discriminant(&wanted) || !is_updatable(&wanted) {
+                break;
+            }
+            operations.push(BlockOperation::Update {
+                block_id: block.common().id.clone(),
+                block: wanted,
+            });
+        }
+        kept += 1;
+    }
+
+    operations.extend(current[kept..].iter().map(|block| BlockOperation::Delete {
+        block_id: block.common().id.clone(),
+    }));
+    if kept < desired.len() {
+        operations.push(BlockOperation::Append {
+            blocks: desired[kept..].to_vec(),
+        });
+    }
+    operations
+}
+
+impl NotionApi {
+    /// Applies the operations [diff_blocks] returned for the children of `block_id`, in order.
+    ///
+    /// When an operation fails, the ones before it are left applied.
+    pub async fn apply_block_operations<T: AsIdentifier<BlockId>>(
+        &self,
+        block_id: T,
+        operations: Vec<BlockOperation>,
+    ) -> Result<(), Error> {
+        for operation in operations {
+            match operation {
+                BlockOperation::Update { block_id, block } => {
+                    self.update_block(block_id, block).await?;
+                }
+                BlockOperation::Delete { block_id } => {
+                    self.trash_block(block_id).await?;
+                }
+                BlockOperation::Append { blocks } => {
+                    self.append_block_tree(block_id.as_id(), blocks).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn without_children(mut block: CreateBlock) -> CreateBlock {
+    block.take_children();
+    block
+}
+
+/// The kinds of blocks whose content Notion lets an update change.
+fn is_updatable(block: &CreateBlock) -> bool {
+    use CreateBlock::*;
+    matches!(
+        block,
+        Paragraph { .. }
+            | Heading1 { .. }
+            | Heading2 { .. }
+            | Heading3 { .. }
+            | Callout { .. }
+            | Quote { .. }
+            | BulletedListItem { .. }
+            | NumberedListItem { .. }
+            | ToDo { .. }
+            | Toggle { .. }
+            | Code { .. }
+            | Embed { .. }
+            | Image { .. }
+            | Video { .. }
+            | Audio { .. }
+            | File { .. }
+            | Pdf { .. }
+            | Bookmark { .. }
+            | Equation { .. }
+            | TableOfContents { .. }
+            | LinkToPage { .. }
+            | TableRow { .. }
+    )
+}
+
+/// The JSON of a write model without what Notion fills in itself: the plain text and
+/// links of rich text, and annotations left at their defaults.
+fn normalized<T: serde::Serialize>(value: &T) -> Value {
+    let mut value = serde_json::to_value(value).unwrap_or_default();
+    normalize(&mut value);
+    value
+}
+
+fn normalize(value: &mut Value) {
+    match value {
+        Value::Object(fields) => {
+            if fields.contains_key("plain_text") || fields.contains_key("annotations") {
+                fields.remove("plain_text");
+                fields.remove("href");
+                let default_annotations = match fields.get_mut("annotations") {
+                    Some(Value::Object(annotations)) => {
+                        annotations.retain(|_, value| {
+                            *value != Value::Bool(false) && value.as_str() != Some("default")
+                        });
+                        annotations.is_empty()
+                    }
+                    _ => false,
+                };
+                if default_annotations {
+                    fields.remove("annotations");
+                }
+            }
+            fields.values_mut().for_each(normalize);
+        }
+        Value::Array(items) => items.iter_mut().for_each(normalize),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diff::{
+        diff_blocks, diff_properties, page_update, reconcile_properties, BlockOperation,
+        PropertyChange,
+    };
+    use crate::ids::BlockId;
+    use crate::models::block::{Block, CreateBlock, TextAndChildren};
+    use crate::models::properties::CreatePropertyValue;
+    use crate::models::text::{RichText, TextColor};
+    use crate::models::{IconObject, Page, Properties};
+    use crate::{NotionApi, TransportResponse};
+    use reqwest::StatusCode;
+    use serde_json::json;
+    use std::collections::HashMap;
+    use std::str::FromStr;
+    use std::sync::{Arc, Mutex};
+
+    fn properties(value: serde_json::Value) -> Properties {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn page(properties: serde_json::Value) -> Page {
+        let mut page: serde_json::Value =
+            serde_json::from_str(include_str!("models/tests/page.json")).unwrap();
+        page["properties"] = properties;
+        serde_json::from_value(page).unwrap()
+    }
+
+    fn paragraph_block(
+        id: &str,
+        content: &str,
+    ) -> Block {
+        serde_json::from_value(json!({
+            "object": "block",
+            "id": id,
+            "created_time": "2022-05-12T21:15:00.000Z",
+            "last_edited_time": "2022-05-12T21:15:00.000Z",
+            "created_by": {"object": "user", "id": "6419f912-5293-4ea8-b2c8-9c3ce44f90e3"},
+            "last_edited_by": {"object": "user", "id": "6419f912-5293-4ea8-b2c8-9c3ce44f90e3"},
+            "has_children": false,
+            "type": "paragraph",
+            "paragraph": {
+                "rich_text": [{
+                    "type": "text",
+                    "text": {"content": content, "link": null},
+                    "annotations": {
+                        "bold": false, "italic": false, "strikethrough": false,
+                        "underline": false, "code": false, "color": "default"
+                    },
+                    "plain_text": content,
+                    "href": null
+                }],
+                "color": "default"
+            }
+        }))
+        .unwrap()
+    }
+
+    fn paragraph(content: &str) -> CreateBlock {
+        CreateBlock::Paragraph {
+            paragraph: TextAndChildren {
+                rich_text: vec![RichText::text(content)],
+                children: None,
+                color: TextColor::Default,
+            },
+        }
+    }
+
+    #[test]
+    fn reports_typed_changes() {
+        let old = properties(json!({
+            "Status": {"id": "s", "type": "select", "select": {"name": "Todo", "color": "red"}},
+            "Due": {"id": "d", "type": "date", "date": {"start": "2022-05-12", "end": null, "time_zone": null}},
+            "Project": {"id": "p", "type": "relation", "relation": [{"id": "a3b1"}]},
+            "Score": {"id": "n", "type": "number", "number": 1},
+            "Done": {"id": "c", "type": "checkbox", "checkbox": false},
+            "Notes": {"id": "t", "type": "rich_text", "rich_text": []}
+        }));
+        let new = properties(json!({
+            "Status": {"id": "s", "type": "select", "select": {"name": "Done", "color": "green"}},
+            "Due": {"id": "d", "type": "date", "date": null},
+            "Project": {"id": "p", "type": "relation", "relation": [{"id": "a3b1"}, {"id": "c4d2a6e8-1f3b-4d5c-9e7a-0b1c2d3e4f50"}]},
+            "Score": {"id": "n", "type": "number", "number": 2},
+            "Done": {"id": "c", "type": "checkbox", "checkbox": false},
+            "Owner": {"id": "o", "type": "people", "people": []}
+        }));
+
+        let changes = diff_properties(&old, &new);
+        let names: Vec<_> = changes.iter().map(PropertyChange::name).collect();
+        assert_eq!(
+            names,
+            ["Due", "Notes", "Owner", "Project", "Score", "Status"]
+        );
+
+        assert!(matches!(changes[0], PropertyChange::Cleared { .. }));
+        assert!(matches!(changes[1], PropertyChange::Removed { .. }));
+        assert!(matches!(changes[2], PropertyChange::Added { .. }));
+        assert_eq!(
+            changes[3],
+            PropertyChange::RelationChanged {
+                name: "Project".to_string(),
+                added: vec![
+                    crate::ids::PageId::from_str("c4d2a6e8-1f3b-4d5c-9e7a-0b1c2d3e4f50").unwrap()
+                ],
+                removed: vec![],
+            }
+        );
+        assert!(matches!(changes[4], PropertyChange::Changed { .. }));
+        assert_eq!(
+            changes[5],
+            PropertyChange::SelectChanged {
+                name: "Status".to_string(),
+                from: Some("Todo".to_string()),
+                to: Some("Done".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn updates_only_changed_properties() {
+        let old = page(json!({
+            "Name": {"id": "title", "type": "title", "title": []},
+            "Status": {"id": "s", "type": "select", "select": {"id": "1", "name": "Todo", "color": "red"}},
+            "Score": {"id": "n", "type": "number", "number": 1},
+            "Total": {"id": "f", "type": "formula", "formula": {"type": "number", "number": 1}}
+        }));
+        let mut new = page(json!({
+            "Name": {"id": "title", "type": "title", "title": []},
+            "Status": {"id": "s", "type": "select", "select": {"id": "2", "name": "Done", "color": "green"}},
+            "Score": {"id": "n", "type": "number", "number": 1},
+            "Total": {"id": "f", "type": "formula", "formula": {"type": "number", "number": 2}}
+        }));
+        new.icon = Some(IconObject::Emoji {
+            emoji: "🥬".to_string(),
+        });
+
+        assert_eq!(
+            serde_json::to_value(page_update(&old, &new)).unwrap(),
+            json!({
+                "properties": {"Status": {"select": {"id": "2"}}},
+                "icon": {"type": "emoji", "emoji": "🥬"}
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(page_update(&new, &new)).unwrap(),
+            json!({})
+        );
+    }
+
+    #[test]
+    fn reconciles_desired_properties() {
+        let current = page(json!({
+            "Name": {"id": "title", "type": "title", "title": [{
+                "type": "text",
+                "text": {"content": "Tuscan kale", "link": null},
+                "annotations": {
+                    "bold": false, "italic": false, "strikethrough": false,
+                    "underline": false, "code": false, "color": "default"
+                },
+                "plain_text": "Tuscan kale",
+                "href": null
+            }]},
+            "Status": {"id": "s", "type": "select", "select": {"id": "1", "name": "Todo", "color": "red"}},
+            "Tags": {"id": "t", "type": "multi_select", "multi_select": [{"id": "g", "name": "Green", "color": "green"}]},
+            "Done": {"id": "c", "type": "checkbox", "checkbox": false}
+        }));
+        let desired: HashMap<_, _> = vec![
+            (
+                "Name",
+                CreatePropertyValue::Title(vec![RichText::text("Tuscan kale")]),
+            ),
+            ("Status", CreatePropertyValue::Select(Some("Todo".into()))),
+            (
+                "Tags",
+                CreatePropertyValue::MultiSelect(vec!["Green".into()]),
+            ),
+            ("Done", CreatePropertyValue::Checkbox(true)),
+            ("Price", CreatePropertyValue::Number(Some(3.into()))),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect();
+
+        let update = reconcile_properties(&current, &desired);
+        let mut names: Vec<_> = update.properties.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        assert_eq!(names, ["Done", "Price"]);
+    }
+
+    #[test]
+    fn diffs_blocks_by_position() {
+        let first = "9e891834-6a03-475c-a2b8-421e17f0f3aa";
+        let second = "00e8829a-a7b8-4075-884a-8f53be145d2f";
+        let third = "7c1d2e3f-4a5b-4c6d-8e9f-0a1b2c3d4e5f";
+        let current = vec![
+            paragraph_block(first, "Kept"),
+            paragraph_block(second, "Edited"),
+            paragraph_block(third, "Replaced"),
+        ];
+        let desired = vec![
+            paragraph("Kept"),
+            paragraph("Edited again"),
+            CreateBlock::Divider,
+            paragraph("Replaced"),
+        ];
+
+        assert_eq!(
+            diff_blocks(&current, &desired),
+            vec![
+                BlockOperation::Update {
+                    block_id: BlockId::from_str(second).unwrap(),
+                    block: paragraph("Edited again"),
+                },
+                BlockOperation::Delete {
+                    block_id: BlockId::from_str(third).unwrap(),
+                },
+                BlockOperation::Append {
+                    blocks: vec![CreateBlock::Divider, paragraph("Replaced")],
+                },
+            ]
+        );
+        assert_eq!(diff_blocks(&current[..1], &desired[..1]), vec![]);
+    }
+
+    #[tokio::test]
+    async fn applies_block_operations() -> Result<(), Box<dyn std::error::Error>> {
+        let page = "b55c9c91-384d-452b-81db-d1ef79372b75";
+        let first = "9e891834-6a03-475c-a2b8-421e17f0f3aa";
+        let second = "00e8829a-a7b8-4075-884a-8f53be145d2f";
+        let current = vec![
+            paragraph_block(first, "Edited"),
+            paragraph_block(second, "Replaced"),
+        ];
+        let desired = vec![paragraph("Edited again"), CreateBlock::Divider];
+
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+        let api = NotionApi::builder("secret".to_string())
+            .transport(move |request: reqwest::Request| {
+                let body: serde_json::Value = request
+                    .body()
+                    .and_then(|body| body.as_bytes())
+                    .map(|body| serde_json::from_slice(body).unwrap())
+                    .unwrap_or_default();
+                let path = request.url().path().to_string();
+                recorded
+                    .lock()
+                    .unwrap()
+                    .push((request.method().to_string(), path.clone(), body));
+
+                let mut block =
+                    serde_json::to_value(paragraph_block(first, "Edited again")).unwrap();
+                block["object"] = json!("block");
+                let response = if path.ends_with("/children") {
+                    json!({"object": "list", "results": [block], "next_cursor": null, "has_more": false})
+                } else {
+                    block
+                };
+                Ok(TransportResponse::new(StatusCode::OK, response.to_string()))
+            })
+            .build()?;
+
+        api.apply_block_operations(BlockId::from_str(page)?, diff_blocks(&current, &desired))
+            .await?;
+
+        let requests = requests.lock().unwrap();
+        let calls: Vec<_> = requests
+            .iter()
+            .map(|(method, path, _)| (method.as_str(), path.as_str()))
+            .collect();
+        assert_eq!(
+            calls,
+            vec![
+                ("PATCH", format!("/v1/blocks/{}", first).as_str()),
+                ("PATCH", format!("/v1/blocks/{}", second).as_str()),
+                ("PATCH", format!("/v1/blocks/{}/children", page).as_str()),
+            ]
+        );
+        assert_eq!(
+            requests[0].2["paragraph"]["rich_text"][0]["text"]["content"],
+            "Edited again"
+        );
+        assert_eq!(requests[1].2, json!({"in_trash": true}));
+        assert_eq!(requests[2].2["children"][0]["type"], "divider");
+
+        Ok(())
+    }
+}
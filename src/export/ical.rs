@@ -0,0 +1,209 @@
+use crate::ids::{notion_url, AsIdentifier, DatabaseId};
+use crate::models::properties::{DateOrDateTime, DateValue, PropertyValue};
+use crate::models::search::{DatabaseQuery, DateCondition, FilterCondition, PropertyCondition};
+use crate::models::text::RichText;
+use crate::models::Page;
+use crate::{Error, NotionApi};
+use chrono::{DateTime, Duration, Utc};
+
+/// Queries a database and renders every page with a value in `date_property` as an event
+/// of an RFC 5545 (iCalendar) `VCALENDAR`, titled by `title_property`.
+///
+/// See [pages_to_ical] for how pages are turned into events.
+pub async fn ical<D: AsIdentifier<DatabaseId>>(
+    api: &NotionApi,
+    database: D,
+    date_property: &str,
+    title_property: &str,
+) -> Result<String, Error> {
+    let pages = api
+        .query_database_all(
+            database,
+            DatabaseQuery {
+                filter: Some(FilterCondition::Property {
+                    property: date_property.to_string(),
+                    condition: PropertyCondition::Date(DateCondition::IsNotEmpty),
+                }),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+    Ok(pages_to_ical(&pages, date_property, title_property))
+}
+
+/// Renders pages as events of an RFC 5545 (iCalendar) `VCALENDAR`.
+///
+/// Pages without a value in `date_property` are skipped. Dates without a time become
+/// all-day events, date ranges span from their start to their end, and each event
+/// links back to its page. When `title_property` is missing the page title is used.
+pub fn pages_to_ical(
+    pages: &[Page],
+    date_property: &str,
+    title_property: &str,
+) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//notion-rs//notion export//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+
+    for page in pages {
+        let date = match page.properties.properties.get(date_property) {
+            Some(PropertyValue::Date {
+                date: Some(date), ..
+            }) => date,
+            _ => continue,
+        };
+
+        let title = match page.properties.properties.get(title_property) {
+            Some(PropertyValue::Title { title: text, .. })
+            | Some(PropertyValue::Text {
+                rich_text: text, ..
+            }) => text.iter().map(RichText::plain_text).collect(),
+            _ => page.title().unwrap_or_default(),
+        };
+
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}@notion.so", page.id));
+        lines.push(format!(
+            "DTSTAMP:{}",
+            format_date_time(&page.last_edited_time)
+        ));
+        lines.extend(event_dates(date));
+        lines.push(format!("SUMMARY:{}", escape_text(&title)));
+        lines.push(format!("URL:{}", notion_url(&page.id)));
+        lines.push("END:VEVENT".to_string());
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    lines
+        .iter()
+        .map(|line| fold_line(line))
+        .collect::<Vec<_>>()
+        .join("\r\n")
+        + "\r\n"
+}
+
+fn event_dates(date: &DateValue) -> Vec<String> {
+    let time_zone = date.time_zone.as_deref();
+    let mut lines = vec![format!("DTSTART{}", format_date(&date.start, time_zone))];
+
+    match &date.end {
+        Some(end @ (DateOrDateTime::DateTime(_) | DateOrDateTime::LocalDateTime(_))) => {
+            lines.push(format!("DTEND{}", format_date(end, time_zone)));
+        }
+        // The end of an all-day event is exclusive, so it ends the day after the range does.
+        Some(DateOrDateTime::Date(end)) => {
+            lines.push(format!(
+                "DTEND{}",
+                format_date(&DateOrDateTime::Date(*end + Duration::days(1)), None)
+            ));
+        }
+        None => {}
+    }
+
+    lines
+}
+
+fn format_date(
+    date: &DateOrDateTime,
+    time_zone: Option<&str>,
+) -> String {
+    let local = "%Y%m%dT%H%M%S";
+    match (date, time_zone) {
+        (DateOrDateTime::Date(date), _) => format!(";VALUE=DATE:{}", date.format("%Y%m%d")),
+        (DateOrDateTime::DateTime(date_time), _) => format!(":{}", format_date_time(date_time)),
+        (DateOrDateTime::LocalDateTime(date_time), Some(time_zone)) => {
+            format!(";TZID={}:{}", time_zone, date_time.format(local))
+        }
+        // A floating time, the same time in every time zone.
+        (DateOrDateTime::LocalDateTime(date_time), None) => {
+            format!(":{}", date_time.format(local))
+        }
+    }
+}
+
+fn format_date_time(date_time: &DateTime<Utc>) -> String {
+    date_time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escapes a TEXT value, see <https://www.rfc-editor.org/rfc/rfc5545#section-3.3.11>
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Folds lines longer than 75 octets, see <https://www.rfc-editor.org/rfc/rfc5545#section-3.1>
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut line_length = 0;
+
+    for c in line.chars() {
+        if line_length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            line_length = 1;
+        }
+        folded.push(c);
+        line_length += c.len_utf8();
+    }
+
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::ical::fold_line;
+    use crate::export::pages_to_ical;
+    use crate::models::Page;
+    use serde_json::json;
+
+    fn page(date: serde_json::Value) -> Page {
+        let mut page: serde_json::Value =
+            serde_json::from_str(include_str!("../models/tests/page.json")).unwrap();
+        page["properties"]["Due"] = json!({"id": "d", "type": "date", "date": date});
+        serde_json::from_value(page).unwrap()
+    }
+
+    #[test]
+    fn renders_all_day_and_timed_events() {
+        let pages = vec![
+            page(json!({"start": "2022-05-12", "end": "2022-05-13", "time_zone": null})),
+            page(json!({"start": "2022-05-14T09:00:00.000Z", "end": null, "time_zone": null})),
+            page(
+                json!({"start": "2022-05-15T09:00:00", "end": "2022-05-15T10:00:00", "time_zone": "Europe/Berlin"}),
+            ),
+            page(json!(null)),
+        ];
+
+        let calendar = pages_to_ical(&pages, "Due", "Name");
+        let events: Vec<_> = calendar.split("BEGIN:VEVENT").skip(1).collect();
+
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(events.len(), 3);
+        assert!(events[0].contains("DTSTART;VALUE=DATE:20220512\r\nDTEND;VALUE=DATE:20220514\r\n"));
+        assert!(events[0].contains("SUMMARY:Stuffsome\r\n"));
+        assert!(
+            events[0].contains("URL:https://www.notion.so/b55c9c91384d452b81dbd1ef79372b75\r\n")
+        );
+        assert!(events[1].contains("DTSTART:20220514T090000Z\r\nSUMMARY"));
+        assert!(events[2].contains(
+            "DTSTART;TZID=Europe/Berlin:20220515T090000\r\nDTEND;TZID=Europe/Berlin:20220515T100000\r\n"
+        ));
+    }
+
+    #[test]
+    fn folds_long_lines() {
+        let folded = fold_line(&format!("SUMMARY:{}", "é".repeat(40)));
+        let lines: Vec<_> = folded.split("\r\n").collect();
+
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|line| line.len() <= 75));
+        assert!(lines[1].starts_with(' '));
+    }
+}
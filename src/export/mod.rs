@@ -0,0 +1,9 @@
+//! Conversions of Notion content into formats other tools understand.
+
+mod ical;
+mod outline;
+mod redact;
+
+pub use ical::{ical, pages_to_ical};
+pub use outline::{outline, OutlineNode};
+pub use redact::Redactor;
@@ -0,0 +1,225 @@
+use crate::ids::notion_url;
+use crate::models::block::{Block, LinkToPageFields};
+use crate::models::text::{plain_text, RichText};
+use crate::models::Page;
+use serde::{Deserialize, Serialize};
+
+/// A node of a generic hierarchical outline,
+/// suitable for mind-mapping tools or canvas formats like Obsidian's JSON Canvas.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct OutlineNode {
+    /// Id of the page or block the node was created from.
+    pub id: String,
+    /// The Notion type of the node, e.g. `page`, `heading_1` or `paragraph`.
+    pub kind: String,
+    /// Plain text of the node; the title for pages.
+    pub text: String,
+    /// Urls referenced by the node.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<OutlineNode>,
+}
+
+/// Builds an outline of a page from its (top level) blocks.
+///
+/// Blocks following a heading are nested under that heading until a heading of the
+/// same or a higher level starts a new section. Nested block children are kept
+/// under their parent block.
+pub fn outline(
+    page: &Page,
+    blocks: &[Block],
+) -> OutlineNode {
+    let root = OutlineNode {
+        id: page.id.to_string(),
+        kind: "page".to_string(),
+        text: page.title().unwrap_or_default(),
+        links: vec![],
+        children: vec![],
+    };
+
+    nest(root, blocks)
+}
+
+fn nest(
+    parent: OutlineNode,
+    blocks: &[Block],
+) -> OutlineNode {
+    let mut sections: Vec<(u8, OutlineNode)> = vec![(0, parent)];
+
+    for block in blocks
+        .iter()
+        .filter(|block| !matches!(block, Block::Unknown { .. }))
+    {
+        let node = nest(block_node(block), block.children());
+        match heading_level(block) {
+            Some(level) => {
+                close_sections(&mut sections, level);
+                sections.push((level, node));
+            }
+            None => sections.last_mut().unwrap().1.children.push(node),
+        }
+    }
+
+    close_sections(&mut sections, 1);
+    sections.pop().unwrap().1
+}
+
+/// Moves every open section with a level of at least `level` into its parent.
+fn close_sections(
+    sections: &mut Vec<(u8, OutlineNode)>,
+    level: u8,
+) {
+    while sections.len() > 1 && sections.last().unwrap().0 >= level {
+        let (_, node) = sections.pop().unwrap();
+        sections.last_mut().unwrap().1.children.push(node);
+    }
+}
+
+fn heading_level(block: &Block) -> Option<u8> {
+    match block {
+        Block::Heading1 { .. } => Some(1),
+        Block::Heading2 { .. } => Some(2),
+        Block::Heading3 { .. } => Some(3),
+        _ => None,
+    }
+}
+
+fn block_node(block: &Block) -> OutlineNode {
+    use crate::ids::AsIdentifier;
+
+    let (kind, text, mut links): (&str, String, Vec<String>) = match block {
+        Block::Paragraph { paragraph, .. } => {
+            ("paragraph", plain_text(&paragraph.rich_text), vec![])
+        }
+        Block::Heading1 { heading_1, .. } => {
+            ("heading_1", plain_text(&heading_1.rich_text), vec![])
+        }
+        Block::Heading2 { heading_2, .. } => {
+            ("heading_2", plain_text(&heading_2.rich_text), vec![])
+        }
+        Block::Heading3 { heading_3, .. } => {
+            ("heading_3", plain_text(&heading_3.rich_text), vec![])
+        }
+        Block::Callout { callout, .. } => ("callout", plain_text(&callout.rich_text), vec![]),
+        Block::Quote { quote, .. } => ("quote", plain_text(&quote.rich_text), vec![]),
+        Block::BulletedListItem {
+            bulleted_list_item, ..
+        } => (
+            "bulleted_list_item",
+            plain_text(&bulleted_list_item.rich_text),
+            vec![],
+        ),
+        Block::NumberedListItem {
+            numbered_list_item, ..
+        } => (
+            "numbered_list_item",
+            plain_text(&numbered_list_item.rich_text),
+            vec![],
+        ),
+        Block::ToDo { to_do, .. } => ("to_do", plain_text(&to_do.rich_text), vec![]),
+        Block::Toggle { toggle, .. } => ("toggle", plain_text(&toggle.rich_text), vec![]),
+        Block::Code { code, .. } => ("code", plain_text(&code.rich_text), vec![]),
+        Block::ChildPage { common, child_page } => (
+            "child_page",
+            child_page.title.clone(),
+            vec![notion_url(&common.id)],
+        ),
+        Block::ChildDatabase {
+            common,
+            child_database,
+        } => (
+            "child_database",
+            child_database.title.clone(),
+            vec![notion_url(&common.id)],
+        ),
+        Block::Embed { embed, .. } => ("embed", String::new(), vec![embed.url.clone()]),
+        Block::Bookmark { bookmark, .. } => (
+            "bookmark",
+            plain_text(&bookmark.caption),
+            vec![bookmark.url.clone()],
+        ),
+        Block::LinkPreview { link_preview, .. } => (
+            "link_preview",
+            String::new(),
+            vec![link_preview.url.clone()],
+        ),
+        Block::LinkToPage { link_to_page, .. } => {
+            let target = match link_to_page {
+                LinkToPageFields::PageId { page_id } => notion_url(page_id),
+                LinkToPageFields::DatabaseId { database_id } => notion_url(database_id),
+            };
+            ("link_to_page", String::new(), vec![target])
+        }
+        Block::Equation { equation, .. } => ("equation", equation.expression.clone(), vec![]),
+        Block::Template { template, .. } => ("template", plain_text(&template.rich_text), vec![]),
+        Block::TableRow { table_row, .. } => ("table_row", table_row.plain_text(), vec![]),
+        Block::Image { .. } => ("image", String::new(), vec![]),
+        Block::Video { .. } => ("video", String::new(), vec![]),
+        Block::Audio { .. } => ("audio", String::new(), vec![]),
+        Block::File { .. } => ("file", String::new(), vec![]),
+        Block::Pdf { .. } => ("pdf", String::new(), vec![]),
+        Block::Divider { .. } => ("divider", String::new(), vec![]),
+        Block::TableOfContents { .. } => ("table_of_contents", String::new(), vec![]),
+        Block::Breadcrumb { .. } => ("breadcrumb", String::new(), vec![]),
+        Block::ColumnList { .. } => ("column_list", String::new(), vec![]),
+        Block::Column { .. } => ("column", String::new(), vec![]),
+        Block::Table { .. } => ("table", String::new(), vec![]),
+        Block::SyncedBlock { .. } => ("synced_block", String::new(), vec![]),
+        Block::Unsupported { .. } | Block::Unknown { .. } => ("unsupported", String::new(), vec![]),
+    };
+
+    links.extend(block.rich_text().iter().filter_map(|text| match text {
+        RichText::Text { rich_text, .. } | RichText::Mention { rich_text, .. } => {
+            rich_text.href.clone()
+        }
+        RichText::Equation { .. } => None,
+    }));
+
+    OutlineNode {
+        id: block.as_id().to_string(),
+        kind: kind.to_string(),
+        text,
+        links,
+        children: vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::outline;
+    use crate::models::block::Block;
+    use crate::models::{ListResponse, Page};
+
+    #[test]
+    fn nests_blocks_under_headings() {
+        let page: Page = serde_json::from_str(include_str!("../models/tests/page.json")).unwrap();
+        let blocks: ListResponse<Block> =
+            serde_json::from_str(include_str!("tests/outline_blocks.json")).unwrap();
+
+        let outline = outline(&page, blocks.results());
+
+        assert_eq!(outline.kind, "page");
+        assert_eq!(outline.text, "Stuffsome");
+
+        let kinds: Vec<_> = outline.children.iter().map(|c| c.kind.as_str()).collect();
+        assert_eq!(kinds, ["paragraph", "heading_1", "heading_1"]);
+
+        let first_section = &outline.children[1];
+        assert_eq!(first_section.text, "Plans");
+        assert_eq!(first_section.children.len(), 2);
+        assert_eq!(first_section.children[0].kind, "heading_2");
+        assert_eq!(first_section.children[0].children[0].text, "Nested item");
+        assert_eq!(
+            first_section.children[0].children[0].children[0].text,
+            "Child of the nested item"
+        );
+        assert_eq!(first_section.children[1].kind, "heading_2");
+
+        let second_section = &outline.children[2];
+        assert_eq!(
+            second_section.children[0].links,
+            ["https://developers.notion.com/"]
+        );
+    }
+}
@@ -0,0 +1,313 @@
+use crate::export::OutlineNode;
+use crate::models::properties::PropertyValue;
+use crate::models::text::{Link, RichText, Text};
+use crate::models::Page;
+
+type Rule = Box<dyn Fn(&str) -> String + Send + Sync>;
+
+/// Masks sensitive text before it's exported.
+///
+/// Every rule is applied in turn to each rich text run and text-like property value
+/// (title, rich text, url, email and phone number) of a page, and to the text and
+/// links of each outline node. Redact pages before exporting them, e.g. with
+/// [pages_to_ical](crate::export::pages_to_ical), and outlines after building them.
+///
+/// ```
+/// use notion::export::Redactor;
+///
+/// let redactor = Redactor::new()
+///     .emails()
+///     .phone_numbers()
+///     .rule(|text| text.replace("Project Falcon", "[project]"));
+///
+/// assert_eq!(
+///     redactor.redact("Ask jane@example.com or +1 555 010 9999 about Project Falcon"),
+///     "Ask [email] or [phone] about [project]"
+/// );
+/// ```
+#[derive(Default)]
+pub struct Redactor {
+    rules: Vec<Rule>,
+}
+
+impl Redactor {
+    /// A redactor without rules, which leaves text as it is.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a rule, which receives text and returns it with anything sensitive masked.
+    pub fn rule<F>(
+        mut self,
+        rule: F,
+    ) -> Self
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        self.rules.push(Box::new(rule));
+        self
+    }
+
+    /// Replaces email addresses with `[email]`.
+    pub fn emails(self) -> Self {
+        self.rule(mask_emails)
+    }
+
+    /// Replaces phone numbers with `[phone]`.
+    ///
+    /// Phone numbers are runs of at least 7 digits that start with `+` or are
+    /// broken up by spaces, dashes, dots or parentheses, so plain numbers are kept.
+    pub fn phone_numbers(self) -> Self {
+        self.rule(mask_phone_numbers)
+    }
+
+    /// Applies every rule to `text`.
+    pub fn redact(
+        &self,
+        text: &str,
+    ) -> String {
+        self.rules
+            .iter()
+            .fold(text.to_string(), |text, rule| rule(&text))
+    }
+
+    /// Redacts each run of the rich text, including the urls it links to.
+    /// Mentions are replaced with text runs of what they show, redacted like other text.
+    pub fn rich_text(
+        &self,
+        rich_text: &[RichText],
+    ) -> Vec<RichText> {
+        rich_text
+            .iter()
+            .cloned()
+            .map(|mut run| {
+                match &mut run {
+                    RichText::Text { rich_text, text } => {
+                        text.content = self.redact(&text.content);
+                        if let Some(link) = &mut text.link {
+                            link.url = self.redact(&link.url);
+                        }
+                        rich_text.plain_text = self.redact(&rich_text.plain_text);
+                        rich_text.href = rich_text.href.as_deref().map(|href| self.redact(href));
+                    }
+                    // The user, page, date or link a mention refers to isn't redacted,
+                    // so mentions become text with what they show.
+                    RichText::Mention { rich_text, .. } => {
+                        let mut rich_text = rich_text.clone();
+                        rich_text.plain_text = self.redact(&rich_text.plain_text);
+                        rich_text.href = rich_text.href.as_deref().map(|href| self.redact(href));
+                        return RichText::Text {
+                            text: Text {
+                                content: rich_text.plain_text.clone(),
+                                link: rich_text.href.clone().map(|url| Link { url }),
+                            },
+                            rich_text,
+                        };
+                    }
+                    RichText::Equation { rich_text, .. } => {
+                        rich_text.plain_text = self.redact(&rich_text.plain_text);
+                        rich_text.href = rich_text.href.as_deref().map(|href| self.redact(href));
+                    }
+                }
+                run
+            })
+            .collect()
+    }
+
+    /// A copy of the page with its text-like property values redacted.
+    pub fn page(
+        &self,
+        page: &Page,
+    ) -> Page {
+        let mut page = page.clone();
+        for value in page.properties.properties.values_mut() {
+            match value {
+                PropertyValue::Title { title: text, .. }
+                | PropertyValue::Text {
+                    rich_text: text, ..
+                } => *text = self.rich_text(text),
+                PropertyValue::Url {
+                    url: Some(text), ..
+                }
+                | PropertyValue::Email {
+                    email: Some(text), ..
+                }
+                | PropertyValue::PhoneNumber {
+                    phone_number: text, ..
+                } => *text = self.redact(text),
+                _ => {}
+            }
+        }
+        page
+    }
+
+    /// Redacts the text and links of every node of the outline.
+    pub fn outline(
+        &self,
+        node: OutlineNode,
+    ) -> OutlineNode {
+        OutlineNode {
+            text: self.redact(&node.text),
+            links: node.links.iter().map(|link| self.redact(link)).collect(),
+            children: node
+                .children
+                .into_iter()
+                .map(|child| self.outline(child))
+                .collect(),
+            ..node
+        }
+    }
+}
+
+fn mask_emails(text: &str) -> String {
+    let is_local = |c: char| c.is_alphanumeric() || "._%+-".contains(c);
+    let is_domain = |c: char| c.is_alphanumeric() || ".-".contains(c);
+
+    let mut masked = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(at) = rest.find('@') {
+        let start = rest[..at]
+            .rfind(|c: char| !is_local(c))
+            .map_or(0, |i| i + rest[i..].chars().next().unwrap().len_utf8());
+        let domain = &rest[at + 1..];
+        let end = domain.find(|c: char| !is_domain(c)).unwrap_or(domain.len());
+        // Sentences can end right after an address.
+        let domain = domain[..end].trim_end_matches('.');
+
+        if start < at && domain.contains('.') && !domain.starts_with('.') {
+            masked.push_str(&rest[..start]);
+            masked.push_str("[email]");
+            rest = &rest[at + 1 + domain.len()..];
+        } else {
+            masked.push_str(&rest[..=at]);
+            rest = &rest[at + 1..];
+        }
+    }
+    masked.push_str(rest);
+    masked
+}
+
+fn mask_phone_numbers(text: &str) -> String {
+    let is_part = |c: char| c.is_ascii_digit() || " -.()".contains(c);
+
+    let mut masked = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(|c: char| c.is_ascii_digit() || c == '+' || c == '(') {
+        masked.push_str(&rest[..start]);
+        let candidate = &rest[start..];
+        let len = 1 + candidate[1..]
+            .find(|c: char| !is_part(c))
+            .unwrap_or(candidate.len() - 1);
+        // Don't swallow the separators after the number.
+        let number = candidate[..len].trim_end_matches(|c: char| !c.is_ascii_digit());
+        // Dates are broken up by dashes too.
+        let number = if starts_with_date(number) {
+            &number[..DATE_LEN]
+        } else {
+            number
+        };
+
+        let digits = number.chars().filter(char::is_ascii_digit).count();
+        let separated = number.starts_with('+') || number.contains(|c: char| " -.()".contains(c));
+        let kept = match number.len() {
+            0 => 1,
+            len => len,
+        };
+        if digits >= 7 && separated && !starts_with_date(number) {
+            masked.push_str("[phone]");
+        } else {
+            masked.push_str(&candidate[..kept]);
+        }
+        rest = &candidate[kept..];
+    }
+    masked.push_str(rest);
+    masked
+}
+
+/// The length of an ISO 8601 date, e.g. `2022-06-01`.
+const DATE_LEN: usize = 10;
+
+fn starts_with_date(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    bytes.len() >= DATE_LEN
+        && bytes[..DATE_LEN].iter().enumerate().all(|(i, b)| match i {
+            4 | 7 => *b == b'-',
+            _ => b.is_ascii_digit(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::{outline, Redactor};
+    use crate::models::block::Block;
+    use crate::models::properties::PropertyValue;
+    use crate::models::text::RichText;
+    use crate::models::{ListResponse, Page};
+
+    #[test]
+    fn masks_emails_and_phone_numbers() {
+        let redactor = Redactor::new().emails().phone_numbers();
+
+        assert_eq!(
+            redactor.redact("Mail jane.doe+notion@example.co.uk."),
+            "Mail [email]."
+        );
+        assert_eq!(
+            redactor.redact("@mentions and a@b stay"),
+            "@mentions and a@b stay"
+        );
+        assert_eq!(
+            redactor.redact("Call (555) 010-9999, or +44 20 7946 0018!"),
+            "Call [phone], or [phone]!"
+        );
+        assert_eq!(
+            redactor.redact("Shipped 1000000 units on 2022-06-01 10:00"),
+            "Shipped 1000000 units on 2022-06-01 10:00"
+        );
+    }
+
+    #[test]
+    fn replaces_mentions_with_redacted_text() {
+        let redactor = Redactor::new().rule(|text| text.replace("John Doe", "[person]"));
+        let mention: RichText = serde_json::from_str(include_str!(
+            "../models/tests/rich_text_mention_user_person.json"
+        ))
+        .unwrap();
+
+        let redacted = redactor.rich_text(&[mention]);
+        let json = serde_json::to_string(&redacted).unwrap();
+        assert!(!json.contains("John Doe"), "{}", json);
+        assert!(!json.contains("john.doe@gmail.com"), "{}", json);
+        match &redacted[..] {
+            [RichText::Text { text, .. }] => assert_eq!(text.content, "@[person]"),
+            text => panic!("Expected a text run, got {:?}", text),
+        }
+    }
+
+    #[test]
+    fn redacts_pages_and_outlines() {
+        let redactor = Redactor::new().rule(|text| text.replace("Stuff", "[redacted]"));
+        let page: Page = serde_json::from_str(include_str!("../models/tests/page.json")).unwrap();
+        let blocks: ListResponse<Block> =
+            serde_json::from_str(include_str!("tests/outline_blocks.json")).unwrap();
+
+        let redacted = redactor.page(&page);
+        assert_eq!(redacted.title(), Some("[redacted]some".to_string()));
+        match redacted.properties.properties.get("Name") {
+            Some(PropertyValue::Title { title, .. }) => match &title[0] {
+                RichText::Text { text, .. } => assert_eq!(text.content, "[redacted]"),
+                text => panic!("Expected a text run, got {:?}", text),
+            },
+            value => panic!("Expected a title, got {:?}", value),
+        }
+
+        let outline = redactor.outline(outline(&page, blocks.results()));
+        assert_eq!(outline.text, "[redacted]some");
+        let redactor = Redactor::new().rule(|text| text.replace("developers", "[docs]"));
+        let outline = redactor.outline(outline);
+        assert_eq!(
+            outline.children[2].children[0].links,
+            ["https://[docs].notion.com/"]
+        );
+    }
+}
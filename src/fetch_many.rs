@@ -0,0 +1,156 @@
+use crate::ids::{AsIdentifier, BlockId, DatabaseId, PageId};
+use crate::models::block::Block;
+use crate::models::{Database, Page};
+use crate::{Error, NotionApi};
+use futures::{stream, Future, StreamExt};
+
+impl NotionApi {
+    /// Gets many pages, up to `concurrency` at the same time,
+    /// with a result per page in the order of `page_ids`.
+    ///
+    /// The requests go through the client's [rate limit](crate::NotionApiBuilder::requests_per_second())
+    /// and [retry policy](crate::NotionApiBuilder::retry_policy()), so a high concurrency
+    /// waits for the rate limit rather than failing. A page that fails doesn't stop the others.
+    pub async fn get_pages<I>(
+        &self,
+        page_ids: I,
+        concurrency: usize,
+    ) -> Vec<Result<Page, Error>>
+    where
+        I: IntoIterator,
+        I::Item: AsIdentifier<PageId>,
+    {
+        fetch_many(page_ids, concurrency, |page_id| self.get_page(page_id)).await
+    }
+
+    /// Gets many databases like [get_pages](NotionApi::get_pages()).
+    pub async fn get_databases<I>(
+        &self,
+        database_ids: I,
+        concurrency: usize,
+    ) -> Vec<Result<Database, Error>>
+    where
+        I: IntoIterator,
+        I::Item: AsIdentifier<DatabaseId>,
+    {
+        fetch_many(database_ids, concurrency, |database_id| {
+            self.get_database(database_id)
+        })
+        .await
+    }
+
+    /// Gets [all the children](NotionApi::get_block_children_all()) of many blocks,
+    /// up to `concurrency` blocks at the same time, like [get_pages](NotionApi::get_pages()).
+    pub async fn get_block_children_many<I>(
+        &self,
+        block_ids: I,
+        concurrency: usize,
+    ) -> Vec<Result<Vec<Block>, Error>>
+    where
+        I: IntoIterator,
+        I::Item: AsIdentifier<BlockId>,
+    {
+        fetch_many(block_ids, concurrency, |block_id| {
+            self.get_block_children_all(block_id)
+        })
+        .await
+    }
+}
+
+/// Runs `fetch` for every item, up to `concurrency` at the same time,
+/// and collects the results in the order of the items.
+async fn fetch_many<I, F, Fut, T>(
+    items: I,
+    concurrency: usize,
+    fetch: F,
+) -> Vec<Result<T, Error>>
+where
+    I: IntoIterator,
+    F: FnMut(I::Item) -> Fut,
+    Fut: Future<Output = Result<T, Error>>,
+{
+    stream::iter(items)
+        .map(fetch)
+        .buffered(concurrency.max(1))
+        .collect()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ids::PageId;
+    use crate::{Error, NotionApi, NotionTransport, TransportResponse};
+    use futures::future::{BoxFuture, FutureExt};
+    use reqwest::{Request, StatusCode};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// Answers requests for later pages sooner, and counts the requests in flight.
+    #[derive(Default)]
+    struct SlowPages {
+        in_flight: AtomicUsize,
+        most_in_flight: AtomicUsize,
+    }
+
+    impl NotionTransport for Arc<SlowPages> {
+        fn send(
+            &self,
+            request: Request,
+        ) -> BoxFuture<'_, Result<TransportResponse, Error>> {
+            async move {
+                let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                self.most_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+                let id = request.url().path().trim_start_matches("/v1/pages/");
+                let index = u64::from(id.as_bytes()[id.len() - 1] - b'0');
+                tokio::time::sleep(Duration::from_millis(50 - 10 * index)).await;
+                self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                if index == 3 {
+                    let error = serde_json::json!({
+                        "object": "error",
+                        "status": 404,
+                        "code": "object_not_found",
+                        "message": "Could not find page"
+                    });
+                    return Ok(TransportResponse::new(
+                        StatusCode::NOT_FOUND,
+                        error.to_string(),
+                    ));
+                }
+                let mut page: serde_json::Value =
+                    serde_json::from_str(include_str!("models/tests/page.json")).unwrap();
+                page["id"] = id.into();
+                Ok(TransportResponse::new(StatusCode::OK, page.to_string()))
+            }
+            .boxed()
+        }
+    }
+
+    #[tokio::test]
+    async fn gets_pages_concurrently_in_order() -> Result<(), Box<dyn std::error::Error>> {
+        let transport = Arc::new(SlowPages::default());
+        let api = NotionApi::builder("secret".to_string())
+            .transport(transport.clone())
+            .build()?;
+        let page_ids = (1..=4)
+            .map(|index| format!("00000000-0000-4000-8000-00000000000{}", index).parse())
+            .collect::<Result<Vec<PageId>, _>>()?;
+
+        let pages = api.get_pages(&page_ids, 2).await;
+
+        assert_eq!(transport.most_in_flight.load(Ordering::SeqCst), 2);
+        assert_eq!(pages.len(), 4);
+        for (index, page) in pages.iter().enumerate() {
+            match page {
+                Ok(page) => assert_eq!(page.id, page_ids[index]),
+                Err(error) => {
+                    assert_eq!(index, 2);
+                    assert_eq!(error.status(), Some(StatusCode::NOT_FOUND));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
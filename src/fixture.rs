@@ -0,0 +1,241 @@
+//! Anonymizes real API responses, so they can be contributed as test fixtures.
+//!
+//! Ids are replaced with random ones, consistently across everything an [Anonymizer]
+//! sees, so references between objects still line up. User names, email addresses
+//! and urls are replaced with placeholders. Page content is left as it is; use
+//! [Redactor](crate::export::Redactor) on the models for that.
+//!
+//! The `anonymize_fixture` example does this for a response read from stdin:
+//!
+//! ```text
+//! cargo run --example anonymize_fixture < response.json > src/models/tests/fixture.json
+//! ```
+
+use serde_json::{Map, Value};
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+
+/// Replaces ids, user names, email addresses and urls in JSON values.
+#[derive(Debug, Default)]
+pub struct Anonymizer {
+    /// Replacement ids keyed by the original id, without dashes.
+    ids: HashMap<String, String>,
+    /// Replacements keyed by the original name, email address or url.
+    replacements: HashMap<String, String>,
+}
+
+impl Anonymizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Anonymizes a response, or any part of one.
+    pub fn anonymize(
+        &mut self,
+        value: Value,
+    ) -> Value {
+        match value {
+            Value::Object(object) => Value::Object(self.object(object)),
+            Value::Array(values) => Value::Array(
+                values
+                    .into_iter()
+                    .map(|value| self.anonymize(value))
+                    .collect(),
+            ),
+            Value::String(text) => Value::String(self.string(&text)),
+            value => value,
+        }
+    }
+
+    fn object(
+        &mut self,
+        object: Map<String, Value>,
+    ) -> Map<String, Value> {
+        let is_user = object.get("object").and_then(Value::as_str) == Some("user");
+        object
+            .into_iter()
+            .map(|(key, value)| {
+                let value = match (key.as_str(), value) {
+                    ("name", Value::String(name)) if is_user => {
+                        Value::String(self.replace(&name, |n| format!("User {}", n)))
+                    }
+                    (_, value) => self.anonymize(value),
+                };
+                (key, value)
+            })
+            .collect()
+    }
+
+    fn string(
+        &mut self,
+        text: &str,
+    ) -> String {
+        if let Some(id) = normalize_id(text) {
+            let replacement = self.id(&id);
+            return if text.contains('-') {
+                dashed(&replacement)
+            } else {
+                replacement
+            };
+        }
+        if text.starts_with("http://") || text.starts_with("https://") {
+            return self.url(text);
+        }
+        if is_email(text) {
+            return self.replace(text, |n| format!("user{}@example.com", n));
+        }
+        text.to_string()
+    }
+
+    /// Notion urls end with the id of the page they link to, which is kept linked.
+    fn url(
+        &mut self,
+        url: &str,
+    ) -> String {
+        let path = url.split(['?', '#']).next().unwrap_or_default();
+        let id = path
+            .rsplit(['/', '-'])
+            .next()
+            .and_then(normalize_id)
+            .filter(|_| path.contains("notion.so/"));
+
+        match id {
+            Some(id) => format!("https://www.notion.so/{}", self.id(&id)),
+            None => self.replace(url, |n| format!("https://example.com/{}", n)),
+        }
+    }
+
+    fn id(
+        &mut self,
+        id: &str,
+    ) -> String {
+        self.ids
+            .entry(id.to_string())
+            .or_insert_with(random_id)
+            .clone()
+    }
+
+    /// The replacement of `original`, made from how many replacements came before it.
+    fn replace<F: FnOnce(usize) -> String>(
+        &mut self,
+        original: &str,
+        replacement: F,
+    ) -> String {
+        let count = self.replacements.len() + 1;
+        self.replacements
+            .entry(original.to_string())
+            .or_insert_with(|| replacement(count))
+            .clone()
+    }
+}
+
+/// Anonymizes a single response, see [Anonymizer].
+pub fn anonymize(value: Value) -> Value {
+    Anonymizer::new().anonymize(value)
+}
+
+/// The id without dashes, if `text` is a UUID with or without dashes.
+fn normalize_id(text: &str) -> Option<String> {
+    let id = text.replace('-', "").to_lowercase();
+    if id.len() != 32 || !id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    (!text.contains('-') || dashed(&id) == text.to_lowercase()).then_some(id)
+}
+
+fn dashed(id: &str) -> String {
+    format!(
+        "{}-{}-{}-{}-{}",
+        &id[..8],
+        &id[8..12],
+        &id[12..16],
+        &id[16..20],
+        &id[20..]
+    )
+}
+
+fn is_email(text: &str) -> bool {
+    match text.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty() && domain.contains('.') && !text.contains(char::is_whitespace)
+        }
+        None => false,
+    }
+}
+
+/// A random version 4 UUID, without dashes.
+fn random_id() -> String {
+    let random = || RandomState::new().build_hasher().finish();
+    let id = (u128::from(random()) << 64 | u128::from(random())) & !(0xf << 76) & !(0x3 << 62)
+        | (0x4 << 76)
+        | (0x2 << 62);
+    format!("{:032x}", id)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixture::{anonymize, normalize_id};
+    use crate::models::Page;
+    use serde_json::json;
+
+    #[test]
+    fn keeps_references_between_ids() {
+        let anonymized = anonymize(json!({
+            "id": "b55c9c91-384d-452b-81db-d1ef79372b75",
+            "parent": {"type": "page_id", "page_id": "b55c9c91384d452b81dbd1ef79372b75"},
+            "other": "6e5ea1d8-17b1-4a68-9a56-7f0b4f5ac8a6",
+            "url": "https://www.notion.so/Stuff-b55c9c91384d452b81dbd1ef79372b75",
+        }));
+
+        let id = anonymized["id"].as_str().unwrap();
+        assert_ne!(id, "b55c9c91-384d-452b-81db-d1ef79372b75");
+        assert_eq!(id.len(), 36);
+        assert_eq!(id.as_bytes()[14], b'4');
+        let id = id.replace('-', "");
+        assert_eq!(anonymized["parent"]["page_id"], id.as_str());
+        assert_ne!(anonymized["other"].as_str().unwrap().replace('-', ""), id);
+        assert_eq!(
+            anonymized["url"],
+            format!("https://www.notion.so/{}", id).as_str()
+        );
+    }
+
+    #[test]
+    fn scrubs_users_emails_and_urls() {
+        let anonymized = anonymize(json!({
+            "people": [
+                {"object": "user", "name": "Jane Doe", "person": {"email": "jane@example.org"}},
+                {"object": "user", "name": "Jane Doe"},
+            ],
+            "name": "Status",
+            "href": "https://github.com/jakeswenson/notion",
+            "plain_text": "Some text",
+        }));
+
+        let name = anonymized["people"][0]["name"].as_str().unwrap();
+        assert!(name.starts_with("User "));
+        assert_eq!(anonymized["people"][1]["name"], name);
+        let email = anonymized["people"][0]["person"]["email"].as_str().unwrap();
+        assert!(email.starts_with("user") && email.ends_with("@example.com"));
+        assert_eq!(anonymized["name"], "Status");
+        assert!(anonymized["href"]
+            .as_str()
+            .unwrap()
+            .starts_with("https://example.com/"));
+        assert_eq!(anonymized["plain_text"], "Some text");
+    }
+
+    #[test]
+    fn anonymized_fixtures_still_parse() {
+        let page: serde_json::Value =
+            serde_json::from_str(include_str!("models/tests/page.json")).unwrap();
+        let page: Page = serde_json::from_value(anonymize(page)).unwrap();
+
+        assert!(normalize_id(&page.id.to_string()).is_some());
+        assert_ne!(
+            page.id.to_string().replace('-', ""),
+            "b55c9c91384d452b81dbd1ef79372b75"
+        );
+    }
+}
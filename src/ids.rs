@@ -1,3 +1,4 @@
+use reqwest::Url;
 use std::fmt::Display;
 use std::fmt::Error;
 
@@ -30,6 +31,51 @@ where
 
 macro_rules! identifer {
     ($name:ident) => {
+        identifer!(@common $name);
+
+        /// Parses an id with or without dashes, normalized to the dashed, lowercase form
+        /// the API returns. Anything else is rejected before it's sent to the API.
+        impl std::str::FromStr for $name {
+            type Err = InvalidId;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                normalize_id(s.trim())
+                    .map($name)
+                    .ok_or_else(|| InvalidId {
+                        input: s.to_string(),
+                    })
+            }
+        }
+
+        #[cfg(feature = "uuid")]
+        impl From<uuid::Uuid> for $name {
+            fn from(uuid: uuid::Uuid) -> Self {
+                $name(uuid.hyphenated().to_string())
+            }
+        }
+
+        #[cfg(feature = "uuid")]
+        impl $name {
+            /// The id as a UUID. Fails for ids that aren't UUIDs,
+            /// which the API doesn't return but deserialization doesn't check.
+            pub fn to_uuid(&self) -> Result<uuid::Uuid, uuid::Error> {
+                uuid::Uuid::parse_str(&self.0)
+            }
+        }
+    };
+    // Ids that aren't UUIDs, which any string parses into.
+    ($name:ident, any) => {
+        identifer!(@common $name);
+
+        impl std::str::FromStr for $name {
+            type Err = Error;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Ok($name(s.to_string()))
+            }
+        }
+    };
+    (@common $name:ident) => {
         #[derive(serde::Serialize, serde::Deserialize, Debug, Eq, PartialEq, Hash, Clone)]
         #[serde(transparent)]
         pub struct $name(String);
@@ -48,14 +94,6 @@ macro_rules! identifer {
                 self.0.fmt(f)
             }
         }
-
-        impl std::str::FromStr for $name {
-            type Err = Error;
-
-            fn from_str(s: &str) -> Result<Self, Self::Err> {
-                Ok($name(s.to_string()))
-            }
-        }
     };
 }
 
@@ -63,10 +101,226 @@ identifer!(DatabaseId);
 identifer!(PageId);
 identifer!(BlockId);
 identifer!(UserId);
-identifer!(PropertyId);
+// Property ids are short, url-encoded strings like `title` or `%3AdRb`.
+identifer!(PropertyId, any);
 
 impl From<PageId> for BlockId {
     fn from(page_id: PageId) -> Self {
         BlockId(page_id.0)
     }
 }
+
+impl PageId {
+    /// The id of the page a Notion url links to, see [parse_notion_url()].
+    /// For a database url with a page open over it, that's the open page.
+    pub fn from_url(url: &str) -> Result<Self, InvalidId> {
+        let url = parse_notion_url(url)?;
+        Ok(PageId(url.peeked_page_id.unwrap_or(url.id)))
+    }
+}
+
+impl DatabaseId {
+    /// The id of the database a Notion url links to, see [parse_notion_url()].
+    pub fn from_url(url: &str) -> Result<Self, InvalidId> {
+        Ok(DatabaseId(parse_notion_url(url)?.id))
+    }
+}
+
+/// A string that isn't a Notion id, or a url that doesn't link to a Notion page or database.
+#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
+#[error("Not a Notion id or url: {:?}", input)]
+pub struct InvalidId {
+    pub input: String,
+}
+
+/// The ids in a url of a Notion page or database, like the ones Notion's "Copy link" gives.
+/// Ids are normalized to the dashed, lowercase form the API returns.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct NotionUrl {
+    /// The page or database the url links to.
+    pub id: String,
+    /// The view of a database, from `?v=`.
+    pub view_id: Option<String>,
+    /// A page opened over a database, from `?p=`.
+    pub peeked_page_id: Option<String>,
+    /// The block the url links to within the page, from `#`.
+    pub block_id: Option<String>,
+}
+
+/// Parses a url of a Notion page or database, on `notion.so` or a `notion.site` public site,
+/// including app links and urls pasted without a scheme.
+///
+/// ```
+/// use notion::ids::parse_notion_url;
+///
+/// let url = parse_notion_url(
+///     "https://www.notion.so/acme/Tasks-b55c9c91384d452b81dbd1ef79372b75?v=9e8918346a03475ca2b8421e17f0f3aa",
+/// )?;
+/// assert_eq!(url.id, "b55c9c91-384d-452b-81db-d1ef79372b75");
+/// assert_eq!(url.view_id.as_deref(), Some("9e891834-6a03-475c-a2b8-421e17f0f3aa"));
+/// # Ok::<(), notion::ids::InvalidId>(())
+/// ```
+pub fn parse_notion_url(url: &str) -> Result<NotionUrl, InvalidId> {
+    let invalid = || InvalidId {
+        input: url.to_string(),
+    };
+    let trimmed = url.trim();
+    let parsed = Url::parse(trimmed)
+        .ok()
+        .filter(Url::has_host)
+        .or_else(|| Url::parse(&format!("https://{}", trimmed)).ok())
+        .ok_or_else(invalid)?;
+
+    let host = parsed.host_str().unwrap_or_default();
+    let is_notion = |domain: &str| host == domain || host.ends_with(&format!(".{}", domain));
+    if !is_notion("notion.so") && !is_notion("notion.site") {
+        return Err(invalid());
+    }
+
+    let id = parsed
+        .path_segments()
+        .and_then(|mut segments| segments.rfind(|segment| !segment.is_empty()))
+        .and_then(trailing_id)
+        .ok_or_else(invalid)?;
+    let query = |name: &str| {
+        parsed
+            .query_pairs()
+            .find(|(key, _)| key == name)
+            .and_then(|(_, value)| normalize_id(&value))
+    };
+
+    Ok(NotionUrl {
+        id,
+        view_id: query("v"),
+        peeked_page_id: query("p"),
+        block_id: parsed.fragment().and_then(normalize_id),
+    })
+}
+
+/// The id at the end of a path segment like `Tasks-b55c9c91384d452b81dbd1ef79372b75`.
+fn trailing_id(segment: &str) -> Option<String> {
+    let hex: String = segment.chars().filter(|c| *c != '-').collect();
+    let start = hex.len().checked_sub(32)?;
+    hex.get(start..).and_then(normalize_id)
+}
+
+/// An id with or without dashes, in the dashed, lowercase form the API returns.
+pub(crate) fn normalize_id(id: &str) -> Option<String> {
+    let hex: String = id.chars().filter(|c| *c != '-').collect();
+    if hex.len() != 32 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let hex = hex.to_ascii_lowercase();
+    Some(format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    ))
+}
+
+/// The url Notion uses for a page, database or block with the given id.
+pub(crate) fn notion_url<T: Identifier>(id: &T) -> String {
+    format!("https://www.notion.so/{}", id.value().replace('-', ""))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ids::{parse_notion_url, DatabaseId, InvalidId, PageId, PropertyId};
+
+    const ID: &str = "b55c9c91-384d-452b-81db-d1ef79372b75";
+
+    #[test]
+    fn parses_ids_from_urls() {
+        for url in [
+            "https://www.notion.so/b55c9c91384d452b81dbd1ef79372b75",
+            "https://www.notion.so/acme/Some-Page-B55C9C91384D452B81DBD1EF79372B75",
+            "www.notion.so/Stuff-b55c9c91384d452b81dbd1ef79372b75/",
+            "notion://www.notion.so/acme/b55c9c91-384d-452b-81db-d1ef79372b75",
+            "https://acme.notion.site/Stuff-b55c9c91384d452b81dbd1ef79372b75?pvs=4",
+        ] {
+            assert_eq!(
+                parse_notion_url(url).map(|url| url.id),
+                Ok(ID.to_string()),
+                "{}",
+                url
+            );
+        }
+    }
+
+    #[test]
+    fn parses_views_peeked_pages_and_blocks() {
+        let url = "https://www.notion.so/acme/9e8918346a03475ca2b8421e17f0f3aa\
+            ?v=00e8829aa7b84075884a8f53be145d2f&p=b55c9c91384d452b81dbd1ef79372b75&pm=s";
+
+        let parsed = parse_notion_url(url).unwrap();
+        assert_eq!(
+            parsed.view_id.as_deref(),
+            Some("00e8829a-a7b8-4075-884a-8f53be145d2f")
+        );
+        assert_eq!(
+            DatabaseId::from_url(url).unwrap().to_string(),
+            "9e891834-6a03-475c-a2b8-421e17f0f3aa"
+        );
+        assert_eq!(PageId::from_url(url).unwrap().to_string(), ID);
+
+        let block = parse_notion_url(
+            "https://www.notion.so/Stuff-b55c9c91384d452b81dbd1ef79372b75#9e8918346a03475ca2b8421e17f0f3aa",
+        )
+        .unwrap();
+        assert_eq!(
+            block.block_id.as_deref(),
+            Some("9e891834-6a03-475c-a2b8-421e17f0f3aa")
+        );
+    }
+
+    #[test]
+    fn parses_and_normalizes_ids() {
+        assert_eq!(
+            " B55C9C91384D452B81DBD1EF79372B75 "
+                .parse::<PageId>()
+                .unwrap()
+                .to_string(),
+            ID
+        );
+        assert_eq!(ID.parse::<DatabaseId>().unwrap().to_string(), ID);
+        assert_eq!(
+            "some-page".parse::<PageId>(),
+            Err(InvalidId {
+                input: "some-page".to_string()
+            })
+        );
+        assert_eq!(
+            "%3AdRb".parse::<PropertyId>().unwrap().to_string(),
+            "%3AdRb"
+        );
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn converts_to_and_from_uuids() {
+        let uuid = uuid::Uuid::parse_str(ID).unwrap();
+        let page_id = PageId::from(uuid);
+        assert_eq!(page_id.to_string(), ID);
+        assert_eq!(page_id.to_uuid().unwrap(), uuid);
+    }
+
+    #[test]
+    fn rejects_other_urls() {
+        for url in [
+            "https://example.com/b55c9c91384d452b81dbd1ef79372b75",
+            "https://www.notion.so/acme/Stuff",
+            "https://www.notion.so/acme/Stuff-b55c9c91384d452b81dbd1ef79372",
+            "",
+        ] {
+            assert_eq!(
+                parse_notion_url(url),
+                Err(InvalidId {
+                    input: url.to_string()
+                })
+            );
+        }
+    }
+}
@@ -0,0 +1,437 @@
+use crate::models::block::{
+    CodeFields, CodeLanguage, CreateBlock, Text, TextAndChildren, ToDoFields,
+};
+use crate::models::text::{Annotations, Link, RichText, RichTextCommon, TextColor};
+use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag};
+
+/// Notion rejects text objects with more characters than this.
+const MAX_TEXT_LENGTH: usize = 2000;
+
+/// Converts a Markdown document into blocks that can be passed as the children
+/// of a [PageCreateRequest](crate::models::PageCreateRequest).
+///
+/// Headings, paragraphs, block quotes, bulleted, numbered and task lists, code fences
+/// and horizontal rules become their Notion counterparts. Bold, italic, strikethrough,
+/// inline code and links are kept as rich text annotations, and images become links.
+/// Nested lists become the children of the item they're in, to any depth: pass deeply nested
+/// lists to [append_block_tree](crate::NotionApi::append_block_tree()), which handles
+/// Notion's limits on nesting. Headings deeper than level 3 become level 3 headings.
+pub fn markdown(document: &str) -> Vec<CreateBlock> {
+    let mut converter = Converter::default();
+    let options = Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+
+    for event in Parser::new_ext(document, options) {
+        converter.event(event);
+    }
+
+    converter.blocks
+}
+
+#[derive(Default)]
+struct Converter {
+    blocks: Vec<CreateBlock>,
+    text: Vec<RichText>,
+    bold: usize,
+    italic: usize,
+    strikethrough: usize,
+    link: Option<String>,
+    /// Whether each open list is numbered.
+    lists: Vec<bool>,
+    /// The items of the open lists nested in other list items, innermost last.
+    nested: Vec<Vec<CreateBlock>>,
+    checked: Option<bool>,
+    quotes: usize,
+    code: Option<CodeLanguage>,
+}
+
+impl Converter {
+    fn event(
+        &mut self,
+        event: Event<'_>,
+    ) {
+        match event {
+            Event::Start(Tag::List(start)) => {
+                self.flush_item();
+                if !self.lists.is_empty() {
+                    self.nested.push(vec![]);
+                }
+                self.lists.push(start.is_some());
+            }
+            Event::End(Tag::List(_)) => {
+                self.lists.pop();
+                if !self.lists.is_empty() {
+                    let items = self.nested.pop().unwrap_or_default();
+                    self.nest(items);
+                }
+            }
+            Event::Start(Tag::Item) => self.checked = None,
+            Event::End(Tag::Item) => self.flush_item(),
+            Event::TaskListMarker(checked) => self.checked = Some(checked),
+            Event::End(Tag::Paragraph) => {
+                if self.lists.is_empty() {
+                    self.flush_paragraph();
+                } else {
+                    self.push_text("\n", false);
+                }
+            }
+            Event::End(Tag::Heading(level, ..)) => {
+                let heading = Text {
+                    rich_text: self.take_text(),
+                };
+                self.push(match level {
+                    HeadingLevel::H1 => CreateBlock::Heading1 { heading_1: heading },
+                    HeadingLevel::H2 => CreateBlock::Heading2 { heading_2: heading },
+                    _ => CreateBlock::Heading3 { heading_3: heading },
+                });
+            }
+            Event::Start(Tag::BlockQuote) => self.quotes += 1,
+            Event::End(Tag::BlockQuote) => self.quotes -= 1,
+            Event::Start(Tag::CodeBlock(kind)) => {
+                self.code = Some(match kind {
+                    CodeBlockKind::Fenced(info) => code_language(&info),
+                    CodeBlockKind::Indented => CodeLanguage::PlainText,
+                });
+            }
+            Event::End(Tag::CodeBlock(_)) => {
+                let language = self.code.take().unwrap_or(CodeLanguage::PlainText);
+                let rich_text = self.take_text();
+                self.push(CreateBlock::Code {
+                    code: CodeFields {
+                        rich_text,
+                        caption: vec![],
+                        language,
+                    },
+                });
+            }
+            Event::Start(Tag::Strong) => self.bold += 1,
+            Event::End(Tag::Strong) => self.bold -= 1,
+            Event::Start(Tag::Emphasis) => self.italic += 1,
+            Event::End(Tag::Emphasis) => self.italic -= 1,
+            Event::Start(Tag::Strikethrough) => self.strikethrough += 1,
+            Event::End(Tag::Strikethrough) => self.strikethrough -= 1,
+            Event::Start(Tag::Link(_, url, _)) | Event::Start(Tag::Image(_, url, _)) => {
+                self.link = Some(url.to_string())
+            }
+            Event::End(Tag::Link(..)) | Event::End(Tag::Image(..)) => self.link = None,
+            Event::Text(text) => self.push_text(&text, false),
+            Event::Code(code) => self.push_text(&code, true),
+            Event::SoftBreak => self.push_text(" ", false),
+            Event::HardBreak => self.push_text("\n", false),
+            Event::Rule => self.push(CreateBlock::Divider),
+            _ => {}
+        }
+    }
+
+    /// Adds a block to the innermost open nested list, or to the document.
+    fn push(
+        &mut self,
+        block: CreateBlock,
+    ) {
+        self.nested
+            .last_mut()
+            .unwrap_or(&mut self.blocks)
+            .push(block);
+    }
+
+    /// Makes the items of a nested list the children of the item before it.
+    fn nest(
+        &mut self,
+        items: Vec<CreateBlock>,
+    ) {
+        let blocks = self.nested.last_mut().unwrap_or(&mut self.blocks);
+        match blocks.last_mut() {
+            Some(
+                item @ (CreateBlock::BulletedListItem { .. }
+                | CreateBlock::NumberedListItem { .. }
+                | CreateBlock::ToDo { .. }),
+            ) => {
+                let mut children = item.take_children();
+                children.extend(items);
+                item.set_children(children);
+            }
+            // A list without an item to nest in, e.g. in an item without text.
+            _ => blocks.extend(items),
+        }
+    }
+
+    fn push_text(
+        &mut self,
+        content: &str,
+        code: bool,
+    ) {
+        let annotations = Annotations {
+            bold: Some(self.bold > 0),
+            code: Some(code),
+            color: Some(TextColor::Default),
+            italic: Some(self.italic > 0),
+            strikethrough: Some(self.strikethrough > 0),
+            underline: Some(false),
+        };
+
+        // Extend the previous text object when it has the same formatting.
+        if let Some(RichText::Text { rich_text, text }) = self.text.last_mut() {
+            let same_format =
+                rich_text.annotations.as_ref() == Some(&annotations) && rich_text.href == self.link;
+            if same_format
+                && text.content.chars().count() + content.chars().count() <= MAX_TEXT_LENGTH
+            {
+                text.content.push_str(content);
+                rich_text.plain_text.push_str(content);
+                return;
+            }
+        }
+
+        let chars: Vec<char> = content.chars().collect();
+        for chunk in chars.chunks(MAX_TEXT_LENGTH) {
+            let content: String = chunk.iter().collect();
+            self.text.push(RichText::Text {
+                rich_text: RichTextCommon {
+                    plain_text: content.clone(),
+                    href: self.link.clone(),
+                    annotations: Some(annotations.clone()),
+                },
+                text: crate::models::text::Text {
+                    content,
+                    link: self.link.clone().map(|url| Link { url }),
+                },
+            });
+        }
+    }
+
+    /// Takes the text collected so far, without trailing line breaks.
+    fn take_text(&mut self) -> Vec<RichText> {
+        let mut text = std::mem::take(&mut self.text);
+        while let Some(RichText::Text {
+            rich_text,
+            text: content,
+        }) = text.last_mut()
+        {
+            let trimmed = content.content.trim_end_matches('\n').len();
+            if trimmed == content.content.len() {
+                break;
+            }
+            if trimmed == 0 {
+                text.pop();
+            } else {
+                content.content.truncate(trimmed);
+                rich_text.plain_text = content.content.clone();
+            }
+        }
+        text
+    }
+
+    fn flush_paragraph(&mut self) {
+        let text = TextAndChildren {
+            rich_text: self.take_text(),
+            children: None,
+            color: TextColor::Default,
+        };
+
+        self.push(if self.quotes > 0 {
+            CreateBlock::Quote { quote: text }
+        } else {
+            CreateBlock::Paragraph { paragraph: text }
+        });
+    }
+
+    fn flush_item(&mut self) {
+        if self.text.is_empty() {
+            return;
+        }
+
+        let rich_text = self.take_text();
+        let text = |rich_text| TextAndChildren {
+            rich_text,
+            children: None,
+            color: TextColor::Default,
+        };
+
+        let checked = self.checked.take();
+        self.push(match (checked, self.lists.last()) {
+            (Some(checked), _) => CreateBlock::ToDo {
+                to_do: ToDoFields {
+                    rich_text,
+                    checked,
+                    children: None,
+                    color: TextColor::Default,
+                },
+            },
+            (None, Some(true)) => CreateBlock::NumberedListItem {
+                numbered_list_item: text(rich_text),
+            },
+            (None, _) => CreateBlock::BulletedListItem {
+                bulleted_list_item: text(rich_text),
+            },
+        });
+    }
+}
+
+/// Maps the info string of a code fence, e.g. `rust` or `sh`, to a Notion code language.
+fn code_language(info: &str) -> CodeLanguage {
+    let language = info
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    let language = match language.as_str() {
+        "" | "text" | "txt" | "plain" => "plain text",
+        "rs" => "rust",
+        "js" | "jsx" => "javascript",
+        "ts" | "tsx" => "typescript",
+        "py" => "python",
+        "rb" => "ruby",
+        "sh" | "zsh" => "shell",
+        "yml" => "yaml",
+        "md" => "markdown",
+        "cpp" | "cxx" => "c++",
+        "cs" | "csharp" => "c#",
+        "dockerfile" => "docker",
+        other => other,
+    };
+
+    serde_json::from_value(serde_json::Value::String(language.to_string()))
+        .unwrap_or(CodeLanguage::PlainText)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::import::markdown;
+    use crate::models::block::{CodeLanguage, CreateBlock};
+    use crate::models::text::RichText;
+
+    fn plain_text(text: &[RichText]) -> String {
+        text.iter().map(RichText::plain_text).collect()
+    }
+
+    #[test]
+    fn converts_block_structure() {
+        let blocks = markdown(include_str!("tests/document.md"));
+
+        let kinds: Vec<_> = blocks
+            .iter()
+            .map(|block| {
+                serde_json::to_value(block).unwrap()["type"]
+                    .as_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                "heading_1",
+                "paragraph",
+                "heading_2",
+                "bulleted_list_item",
+                "bulleted_list_item",
+                "numbered_list_item",
+                "numbered_list_item",
+                "to_do",
+                "to_do",
+                "quote",
+                "code",
+                "divider",
+                "heading_3",
+            ]
+        );
+
+        match &blocks[3].children() {
+            [CreateBlock::BulletedListItem { bulleted_list_item }] => {
+                assert_eq!(plain_text(&bulleted_list_item.rich_text), "nested")
+            }
+            children => panic!("unexpected children {:?}", children),
+        }
+        match &blocks[8] {
+            CreateBlock::ToDo { to_do } => {
+                assert!(to_do.checked);
+                assert_eq!(plain_text(&to_do.rich_text), "done");
+            }
+            block => panic!("unexpected block {:?}", block),
+        }
+        match &blocks[10] {
+            CreateBlock::Code { code } => {
+                assert_eq!(code.language, CodeLanguage::Rust);
+                assert_eq!(plain_text(&code.rich_text), "fn main() {}\n// done");
+            }
+            block => panic!("unexpected block {:?}", block),
+        }
+    }
+
+    #[test]
+    fn nests_lists_in_their_items() {
+        let blocks = markdown("1. one\n   - [ ] task\n     - deep\n   - other\n2. two\n");
+
+        let outline: Vec<_> = blocks.iter().flat_map(|block| outline(block, 0)).collect();
+        assert_eq!(
+            outline,
+            vec![
+                "numbered_list_item one",
+                "  to_do task",
+                "    bulleted_list_item deep",
+                "  bulleted_list_item other",
+                "numbered_list_item two",
+            ]
+        );
+    }
+
+    fn outline(
+        block: &CreateBlock,
+        depth: usize,
+    ) -> Vec<String> {
+        let json = serde_json::to_value(block).unwrap();
+        let kind = json["type"].as_str().unwrap();
+        let text = json[kind]["rich_text"][0]["plain_text"].as_str().unwrap();
+        let mut lines = vec![format!("{}{} {}", "  ".repeat(depth), kind, text)];
+        for child in block.children() {
+            lines.extend(outline(child, depth + 1));
+        }
+        lines
+    }
+
+    #[test]
+    fn keeps_inline_formatting_and_links() {
+        let blocks = markdown(
+            "Some **bold _and_ italic** text with `code` and [a link](https://example.com).",
+        );
+
+        let text = match &blocks[..] {
+            [CreateBlock::Paragraph { paragraph }] => &paragraph.rich_text,
+            blocks => panic!("unexpected blocks {:?}", blocks),
+        };
+
+        let segments: Vec<_> = text
+            .iter()
+            .map(|text| match text {
+                RichText::Text { rich_text, .. } => {
+                    let annotations = rich_text.annotations.clone().unwrap();
+                    (
+                        rich_text.plain_text.as_str(),
+                        annotations.bold.unwrap(),
+                        annotations.italic.unwrap(),
+                        annotations.code.unwrap(),
+                        rich_text.href.as_deref(),
+                    )
+                }
+                text => panic!("unexpected text {:?}", text),
+            })
+            .collect();
+
+        assert_eq!(
+            segments,
+            vec![
+                ("Some ", false, false, false, None),
+                ("bold ", true, false, false, None),
+                ("and", true, true, false, None),
+                (" italic", true, false, false, None),
+                (" text with ", false, false, false, None),
+                ("code", false, false, true, None),
+                (" and ", false, false, false, None),
+                ("a link", false, false, false, Some("https://example.com")),
+                (".", false, false, false, None),
+            ]
+        );
+
+        let json = serde_json::to_value(&text[7]).unwrap();
+        assert_eq!(json["text"]["link"]["url"], "https://example.com");
+    }
+}
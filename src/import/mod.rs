@@ -0,0 +1,9 @@
+//! Conversions of content from other tools into Notion databases and blocks.
+
+#[cfg(feature = "markdown")]
+mod markdown;
+mod table;
+
+#[cfg(feature = "markdown")]
+pub use markdown::markdown;
+pub use table::{database_request, import_table, infer_schema, ColumnType, ImportedTable, Table};
@@ -0,0 +1,390 @@
+use crate::batch::BatchReport;
+use crate::ids::{AsIdentifier, PageId};
+use crate::models::properties::{
+    CreatePropertyConfiguration, CreatePropertyValue, DateOrDateTime, DateValue, NumberFormat,
+    PropertyConfiguration,
+};
+use crate::models::text::RichText;
+use crate::models::{Database, DatabaseCreateRequest, Number, Page, PageCreateRequest, Parent};
+use crate::{Error, NotionApi};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::{BTreeSet, HashMap};
+
+/// Columns with more distinct values than this are never turned into selects.
+const MAX_SELECT_OPTIONS: usize = 20;
+
+/// A table of text cells, e.g. parsed from an HTML `<table>` or a CSV file.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct Table {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(
+        headers: Vec<String>,
+        rows: Vec<Vec<String>>,
+    ) -> Self {
+        Self { headers, rows }
+    }
+
+    /// Parses RFC 4180 CSV, using the first record as the headers.
+    /// Quoted fields may contain commas, newlines and `""` escaped quotes.
+    pub fn from_csv(csv: &str) -> Self {
+        let mut records = parse_csv(csv).into_iter();
+        let headers = records.next().unwrap_or_default();
+
+        Self {
+            headers,
+            rows: records.collect(),
+        }
+    }
+
+    fn column(
+        &self,
+        index: usize,
+    ) -> impl Iterator<Item = &str> {
+        self.rows
+            .iter()
+            .map(move |row| cell(row, index))
+            .filter(|cell| !cell.is_empty())
+    }
+}
+
+/// The Notion property type a column is imported as.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ColumnType {
+    Title,
+    Text,
+    Number,
+    Checkbox,
+    Date,
+    /// A select with the given options, in the order they first appear.
+    Select(Vec<String>),
+}
+
+impl ColumnType {
+    /// The property configuration used when creating the database.
+    pub fn configuration(&self) -> CreatePropertyConfiguration {
+        match self {
+            ColumnType::Title => CreatePropertyConfiguration::title(),
+            ColumnType::Text => CreatePropertyConfiguration::text(),
+            ColumnType::Number => CreatePropertyConfiguration::number(NumberFormat::Number),
+            ColumnType::Checkbox => CreatePropertyConfiguration::Checkbox {},
+            ColumnType::Date => CreatePropertyConfiguration::Date {},
+            ColumnType::Select(options) => CreatePropertyConfiguration::select(options.clone()),
+        }
+    }
+
+    /// Converts a cell into a value for the property `configuration` this column created.
+    fn value(
+        &self,
+        configuration: &PropertyConfiguration,
+        cell: &str,
+    ) -> CreatePropertyValue {
+        match self {
+            ColumnType::Title => CreatePropertyValue::Title(vec![RichText::text(cell)]),
+            ColumnType::Text => CreatePropertyValue::Text(if cell.is_empty() {
+                vec![]
+            } else {
+                vec![RichText::text(cell)]
+            }),
+            ColumnType::Number => CreatePropertyValue::Number(parse_number(cell)),
+            ColumnType::Checkbox => {
+                CreatePropertyValue::Checkbox(parse_bool(cell).unwrap_or_default())
+            }
+            ColumnType::Date => {
+                CreatePropertyValue::Date(parse_date(cell).map(|start| DateValue {
+                    start,
+                    end: None,
+                    time_zone: None,
+                }))
+            }
+            ColumnType::Select(_) => CreatePropertyValue::Select(match configuration {
+                PropertyConfiguration::Select { select, .. } => select
+                    .options
+                    .iter()
+                    .find(|option| option.name == cell)
+                    .map(|option| option.id.clone().into()),
+                _ => None,
+            }),
+        }
+    }
+}
+
+/// Infers a property type for each column of `table`.
+///
+/// The first column becomes the title. Other columns become checkboxes, numbers or dates
+/// when every non-empty cell parses as one, and selects when they repeat a small set of
+/// values. Anything else is imported as text.
+pub fn infer_schema(table: &Table) -> Vec<ColumnType> {
+    (0..table.headers.len())
+        .map(|index| {
+            if index == 0 {
+                return ColumnType::Title;
+            }
+
+            let cells: Vec<_> = table.column(index).collect();
+            if cells.is_empty() {
+                ColumnType::Text
+            } else if cells.iter().all(|cell| parse_bool(cell).is_some()) {
+                ColumnType::Checkbox
+            } else if cells.iter().all(|cell| parse_number(cell).is_some()) {
+                ColumnType::Number
+            } else if cells.iter().all(|cell| parse_date(cell).is_some()) {
+                ColumnType::Date
+            } else {
+                select_options(&cells).map_or(ColumnType::Text, ColumnType::Select)
+            }
+        })
+        .collect()
+}
+
+/// The request creating a database with one property per column of `table`.
+pub fn database_request<P: AsIdentifier<PageId>>(
+    parent: P,
+    title: &str,
+    table: &Table,
+    schema: &[ColumnType],
+) -> DatabaseCreateRequest {
+    table.headers.iter().zip(schema).fold(
+        DatabaseCreateRequest::new(parent, vec![RichText::text(title)]),
+        |request, (header, column)| request.property(header.clone(), column.configuration()),
+    )
+}
+
+/// A database created by [import_table], and the pages created for the rows of the table.
+#[derive(Debug)]
+pub struct ImportedTable {
+    pub database: Database,
+    /// The pages created for each row, and the rows that couldn't be created.
+    pub rows: BatchReport<Page>,
+}
+
+/// Creates a database as a child of `parent` with a schema inferred from `table`,
+/// see [infer_schema], and adds a page for every row.
+///
+/// Rows that fail are reported in [ImportedTable::rows] without stopping the import.
+pub async fn import_table<P: AsIdentifier<PageId>>(
+    api: &NotionApi,
+    parent: P,
+    title: &str,
+    table: &Table,
+) -> Result<ImportedTable, Error> {
+    let schema = infer_schema(table);
+    let database = api
+        .create_database(database_request(parent, title, table, &schema))
+        .await?;
+
+    let rows = BatchReport::run(&table.rows, |row| {
+        api.create_page(PageCreateRequest {
+            parent: Parent::Database {
+                database_id: database.id.clone(),
+            },
+            properties: row_properties(&database, table, &schema, row),
+            children: None,
+            icon: None,
+            cover: None,
+        })
+    })
+    .await;
+
+    Ok(ImportedTable { database, rows })
+}
+
+fn row_properties(
+    database: &Database,
+    table: &Table,
+    schema: &[ColumnType],
+    row: &[String],
+) -> HashMap<String, CreatePropertyValue> {
+    table
+        .headers
+        .iter()
+        .zip(schema)
+        .enumerate()
+        .filter_map(|(index, (header, column))| {
+            let configuration = database.properties.get(header)?;
+            Some((
+                header.clone(),
+                column.value(configuration, cell(row, index)),
+            ))
+        })
+        .collect()
+}
+
+fn cell(
+    row: &[String],
+    index: usize,
+) -> &str {
+    row.get(index).map(|cell| cell.trim()).unwrap_or_default()
+}
+
+/// The distinct values of a column, if there are few enough and they repeat.
+fn select_options(cells: &[&str]) -> Option<Vec<String>> {
+    let mut seen = BTreeSet::new();
+    let options: Vec<String> = cells
+        .iter()
+        .filter(|cell| seen.insert(**cell))
+        .map(|cell| cell.to_string())
+        .collect();
+
+    // Notion doesn't allow commas in select options.
+    let valid = options.iter().all(|option| !option.contains(','));
+
+    if valid && options.len() <= MAX_SELECT_OPTIONS && options.len() * 2 <= cells.len() {
+        Some(options)
+    } else {
+        None
+    }
+}
+
+fn parse_bool(cell: &str) -> Option<bool> {
+    match cell.to_lowercase().as_str() {
+        "true" | "yes" => Some(true),
+        "false" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_number(cell: &str) -> Option<Number> {
+    if let Ok(number) = cell.parse::<i64>() {
+        return Some(number.into());
+    }
+
+    cell.parse::<f64>().ok().and_then(Number::from_f64)
+}
+
+fn parse_date(cell: &str) -> Option<DateOrDateTime> {
+    if let Ok(date) = NaiveDate::parse_from_str(cell, "%Y-%m-%d") {
+        return Some(DateOrDateTime::Date(date));
+    }
+
+    DateTime::parse_from_rfc3339(cell)
+        .ok()
+        .map(|date_time| DateOrDateTime::DateTime(date_time.with_timezone(&Utc)))
+}
+
+fn parse_csv(csv: &str) -> Vec<Vec<String>> {
+    let mut records = vec![];
+    let mut record = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = csv.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    quoted = false;
+                }
+            }
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => record.push(std::mem::take(&mut field)),
+            '\r' if !quoted && chars.peek() == Some(&'\n') => {}
+            '\n' if !quoted => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            c => field.push(c),
+        }
+    }
+
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ids::PageId;
+    use crate::import::table::row_properties;
+    use crate::import::{database_request, infer_schema, ColumnType, Table};
+    use crate::models::Database;
+    use serde_json::json;
+
+    const CSV: &str = "Name,Done,Estimate,Due,Status,Notes\r\n\
+        Write docs,yes,3,2022-05-12,Todo,\"First, the README\"\r\n\
+        Ship it,no,0.5,,Done,\r\n\
+        Celebrate,No,,2022-05-14T09:00:00Z,Todo,\"Say \"\"thanks\"\"\"\r\n\
+        Rest,true,1,2022-05-15,Todo,\r\n";
+
+    #[test]
+    fn parses_csv() {
+        let table = Table::from_csv(CSV);
+
+        assert_eq!(
+            table.headers,
+            vec!["Name", "Done", "Estimate", "Due", "Status", "Notes"]
+        );
+        assert_eq!(table.rows.len(), 4);
+        assert_eq!(table.rows[0][5], "First, the README");
+        assert_eq!(table.rows[2][5], "Say \"thanks\"");
+    }
+
+    #[test]
+    fn infers_column_types() {
+        let table = Table::from_csv(CSV);
+
+        assert_eq!(
+            infer_schema(&table),
+            vec![
+                ColumnType::Title,
+                ColumnType::Checkbox,
+                ColumnType::Number,
+                ColumnType::Date,
+                ColumnType::Select(vec!["Todo".to_string(), "Done".to_string()]),
+                ColumnType::Text,
+            ]
+        );
+    }
+
+    #[test]
+    fn converts_rows_to_properties() -> Result<(), Box<dyn std::error::Error>> {
+        let table = Table::from_csv(CSV);
+        let schema = infer_schema(&table);
+
+        let request = serde_json::to_value(database_request(
+            "b55c9c91384d452b81dbd1ef79372b75".parse::<PageId>()?,
+            "Tasks",
+            &table,
+            &schema,
+        ))?;
+        assert_eq!(
+            request["properties"]["Status"],
+            json!({"select": {"options": [{"name": "Todo"}, {"name": "Done"}]}})
+        );
+
+        let database: Database = serde_json::from_value(json!({
+            "id": "database-id",
+            "created_time": "2022-05-12T00:00:00.000Z",
+            "last_edited_time": "2022-05-12T00:00:00.000Z",
+            "title": [],
+            "parent": {"type": "workspace"},
+            "properties": {
+                "Name": {"id": "title", "type": "title", "title": {}},
+                "Estimate": {"id": "est", "type": "number", "number": {"format": "number"}},
+                "Status": {"id": "st", "type": "select", "select": {"options": [
+                    {"id": "todo-id", "name": "Todo", "color": "red"},
+                    {"id": "done-id", "name": "Done", "color": "green"},
+                ]}},
+            },
+        }))?;
+
+        let properties =
+            serde_json::to_value(row_properties(&database, &table, &schema, &table.rows[1]))?;
+
+        assert_eq!(properties["Status"]["select"], json!({"id": "done-id"}));
+        assert_eq!(properties["Estimate"]["number"], json!(0.5));
+        assert_eq!(properties["Name"]["title"][0]["text"]["content"], "Ship it");
+        assert!(properties.get("Done").is_none());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+use reqwest::{Method, StatusCode, Url};
+use std::sync::Arc;
+use std::time::Duration;
+
+pub(crate) type RequestHook = Arc<dyn Fn(&RequestInfo) + Send + Sync>;
+pub(crate) type ResponseHook = Arc<dyn Fn(&ResponseInfo) + Send + Sync>;
+
+/// A request about to be sent, handed to [on_request](crate::NotionApiBuilder::on_request())
+/// hooks. Retries of a request are sent, and reported, again.
+#[derive(Debug, Clone)]
+pub struct RequestInfo {
+    /// The endpoint the request is for, with ids left out, e.g. `GET /pages/{id}`.
+    pub endpoint: String,
+    pub method: Method,
+    pub url: Url,
+    /// The attempt at sending the request, starting at 1.
+    pub attempt: u32,
+}
+
+/// The outcome of sending a request, handed to
+/// [on_response](crate::NotionApiBuilder::on_response()) hooks.
+#[derive(Debug, Clone)]
+pub struct ResponseInfo {
+    /// The endpoint the request was for, with ids left out, e.g. `GET /pages/{id}`.
+    pub endpoint: String,
+    pub method: Method,
+    /// The status of the response, or `None` when no response was received.
+    pub status: Option<StatusCode>,
+    /// The attempt at sending the request, starting at 1.
+    pub attempt: u32,
+    /// The time from sending the request to receiving the whole response.
+    pub latency: Duration,
+}
+
+/// Names the endpoint a request is for by its method and path, with the ids of objects
+/// replaced, so that requests for different objects are counted together.
+pub(crate) fn endpoint(
+    method: &Method,
+    url: &Url,
+) -> String {
+    let segments: Vec<&str> = url.path_segments().into_iter().flatten().collect();
+    // Skip the version, or whatever else the base url starts with.
+    let start = segments
+        .iter()
+        .position(|segment| {
+            matches!(
+                *segment,
+                "pages" | "blocks" | "databases" | "users" | "search" | "comments" | "oauth"
+            )
+        })
+        .unwrap_or(0);
+
+    let mut path = String::new();
+    let mut previous = "";
+    for segment in &segments[start..] {
+        let segment = match previous {
+            "pages" | "blocks" | "databases" | "comments" => "{id}",
+            "users" if *segment != "me" => "{id}",
+            "properties" => "{property_id}",
+            _ => segment,
+        };
+        path.push('/');
+        path.push_str(segment);
+        previous = segment;
+    }
+
+    format!("{} {}", method, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::instrument::endpoint;
+    use reqwest::{Method, Url};
+
+    #[test]
+    fn names_endpoints_without_ids() {
+        let name = |method: Method, url: &str| endpoint(&method, &Url::parse(url).unwrap());
+
+        assert_eq!(
+            name(
+                Method::GET,
+                "https://api.notion.com/v1/pages/b55c9c91384d452b81dbd1ef79372b75"
+            ),
+            "GET /pages/{id}"
+        );
+        assert_eq!(
+            name(
+                Method::PATCH,
+                "http://localhost:8080/notion/v1/blocks/9e891834-6a03-475c-a2b8-421e17f0f3aa/children?page_size=100"
+            ),
+            "PATCH /blocks/{id}/children"
+        );
+        assert_eq!(
+            name(
+                Method::GET,
+                "https://api.notion.com/v1/pages/b55c9c91384d452b81dbd1ef79372b75/properties/title"
+            ),
+            "GET /pages/{id}/properties/{property_id}"
+        );
+        assert_eq!(
+            name(Method::GET, "https://api.notion.com/v1/users/me"),
+            "GET /users/me"
+        );
+        assert_eq!(
+            name(
+                Method::POST,
+                "https://api.notion.com/v1/databases/abc/query"
+            ),
+            "POST /databases/{id}/query"
+        );
+    }
+}
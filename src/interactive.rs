@@ -0,0 +1,99 @@
+//! Fuzzy pickers for choosing a database or page from the terminal, built on [skim].
+//!
+//! The pickers take over the terminal until something is chosen,
+//! and return `None` when the user aborts with `Esc` or `Ctrl-C`.
+
+use crate::ids::{AsIdentifier, PageId};
+use crate::models::search::NotionSearch;
+use crate::models::{Database, Page, Parent, SearchResult};
+use crate::{Error, NotionApi};
+use futures::TryStreamExt;
+use skim::prelude::{bounded, SkimItemReceiver, SkimItemSender, SkimOptions};
+use skim::{Skim, SkimItem};
+use std::borrow::Cow;
+use std::sync::Arc;
+
+/// Picks one of the databases shared with the integration, searching their titles.
+pub async fn pick_database(api: &NotionApi) -> Result<Option<Database>, Error> {
+    let databases: Vec<Database> = api
+        .search_stream(NotionSearch::filter_by_databases())
+        .try_filter_map(|object| async move {
+            Ok(match object {
+                SearchResult::Database { database } => Some(database),
+                _ => None,
+            })
+        })
+        .try_collect()
+        .await?;
+
+    Ok(pick(databases, Database::display_title))
+}
+
+/// Picks one of the pages directly below `root`, searching their titles.
+pub async fn pick_page<T: AsIdentifier<PageId>>(
+    api: &NotionApi,
+    root: T,
+) -> Result<Option<Page>, Error> {
+    let root = root.as_id();
+    let pages: Vec<Page> = api
+        .search_stream(NotionSearch::filter_by_pages())
+        .try_filter_map(|object| async move {
+            Ok(match object {
+                SearchResult::Page { page } => Some(page),
+                _ => None,
+            })
+        })
+        .try_filter(|page| {
+            let below_root = matches!(&page.parent, Parent::Page { page_id } if page_id == root);
+            async move { below_root }
+        })
+        .try_collect()
+        .await?;
+
+    Ok(pick(pages, Page::display_title))
+}
+
+/// Shows the `title` of each item in a fuzzy finder, and returns the item that was chosen.
+pub fn pick<T, F>(
+    items: Vec<T>,
+    title: F,
+) -> Option<T>
+where
+    F: Fn(&T) -> String,
+{
+    let (sender, receiver): (SkimItemSender, SkimItemReceiver) = bounded(items.len().max(1));
+    for (index, item) in items.iter().enumerate() {
+        let choice = Choice {
+            index,
+            title: title(item),
+        };
+        // The receiver is still held, and the channel has room for every item.
+        let _ = sender.send(Arc::new(choice));
+    }
+    drop(sender);
+
+    let output = Skim::run_with(&SkimOptions::default(), Some(receiver))?;
+    if output.is_abort {
+        return None;
+    }
+    let index = output
+        .selected_items
+        .first()?
+        .as_any()
+        .downcast_ref::<Choice>()?
+        .index;
+
+    items.into_iter().nth(index)
+}
+
+/// An item shown in the finder, remembering where it came from.
+struct Choice {
+    index: usize,
+    title: String,
+}
+
+impl SkimItem for Choice {
+    fn text(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.title)
+    }
+}
@@ -1,27 +1,97 @@
-use crate::ids::{BlockId, DatabaseId};
+//! A client for the [Notion API](https://developers.notion.com/reference/intro).
+//!
+//! Most programs only need [prelude], which stays stable across releases.
+//! The other modules are organized by area and may still be reorganized as the crate grows.
+
+#![allow(clippy::result_large_err)]
+
+use crate::ids::{BlockId, DatabaseId, PropertyId};
 use crate::models::error::ErrorResponse;
+use crate::models::properties::PropertyItem;
 use crate::models::search::{DatabaseQuery, SearchRequest};
-use crate::models::{Database, ListResponse, Object, Page};
+use crate::models::{
+    Database, DatabaseCreateRequest, DatabaseUpdateRequest, ListResponse, Object, Page, Parent,
+    SearchResult,
+};
+use append_queue::AppendQueue;
+use builder::RequestSigner;
+use cache::ResponseCache;
+use conflict_pacer::ConflictPacer;
+use futures::{Stream, TryStreamExt};
 use ids::{AsIdentifier, PageId};
-use models::block::Block;
-use models::PageCreateRequest;
-use reqwest::header::{HeaderMap, HeaderValue};
-use reqwest::{header, Client, ClientBuilder, RequestBuilder};
-use tracing::Instrument;
+use instrument::{RequestHook, ResponseHook};
+use models::block::{validate_children, Block, BlockTreeError, CreateBlock};
+use models::paging::{paginate, Paging};
+use models::{PageCreateRequest, PageUpdateRequest};
+use rate_limit::RateLimiter;
+use reqwest::header::HeaderMap;
+use reqwest::{header, Client};
+use serde::de::{DeserializeOwned, IgnoredAny};
+use std::sync::Arc;
+use std::time::Duration;
 
+pub mod analysis;
+mod append_queue;
+mod append_tree;
+pub mod batch;
+mod builder;
+pub mod cache;
+#[cfg(feature = "config")]
+pub mod config;
+mod conflict_pacer;
+pub mod diff;
+pub mod export;
+mod fetch_many;
+pub mod fixture;
 pub mod ids;
+pub mod import;
+mod instrument;
+#[cfg(feature = "interactive")]
+pub mod interactive;
 pub mod models;
+mod move_page;
+pub mod notify;
+pub mod oauth;
+pub mod prelude;
+mod rate_limit;
+mod request;
+pub mod schema;
+pub mod sync;
+mod transport;
+mod trash;
+mod version;
+
+pub use builder::{NotionApiBuilder, RequestParts};
+pub use instrument::{RequestInfo, ResponseInfo};
+pub use move_page::MovedPage;
+pub use request::RetryPolicy;
+pub use transport::{NotionTransport, ReqwestTransport, TransportResponse};
+pub use version::{ApiVersion, UnknownApiVersion};
 
 pub use chrono;
 
 const NOTION_API_VERSION: &str = "2022-02-22";
+const NOTION_API_URL: &str = "https://api.notion.com/v1";
 
 /// An wrapper Error type for all errors produced by the [`NotionApi`](NotionApi) client.
 #[derive(Debug, thiserror::Error)]
+#[allow(clippy::large_enum_variant)]
 pub enum Error {
     #[error("Invalid Notion API Token: {}", source)]
     InvalidApiToken { source: header::InvalidHeaderValue },
 
+    #[error("Invalid value for the {} header: {}", name, source)]
+    InvalidHeaderValue {
+        name: &'static str,
+        source: header::InvalidHeaderValue,
+    },
+
+    #[error("No Notion API token found, checked: {}", .checked.join(", "))]
+    MissingApiToken { checked: Vec<String> },
+
+    #[error("Invalid value for environment variable {}: {:?}", name, value)]
+    InvalidEnvironmentVariable { name: &'static str, value: String },
+
     #[error("Unable to build reqwest HTTP client: {}", source)]
     ErrorBuildingClient { source: reqwest::Error },
 
@@ -42,108 +112,193 @@ pub enum Error {
 
     #[error("API Error {}({}): {}", .error.code, .error.status, .error.message)]
     ApiError { error: ErrorResponse },
+
+    #[error("Invalid block tree: {}", source)]
+    InvalidBlockTree { source: BlockTreeError },
+
+    #[error("OAuth error {} ({}): {}", error, status, description.as_deref().unwrap_or_default())]
+    OAuthError {
+        status: reqwest::StatusCode,
+        error: String,
+        description: Option<String>,
+    },
+
+    #[error("Unable to deserialize the properties of page {}: {}", page_id, source)]
+    PropertyDeserialization {
+        page_id: PageId,
+        source: serde_json::Error,
+    },
+
+    #[error("Notion-Version {} doesn't support {}", version, feature)]
+    UnsupportedByApiVersion {
+        feature: &'static str,
+        version: ApiVersion,
+    },
+
+    #[error(
+        "Pages can only be created under a page or a database, not {:?}",
+        parent
+    )]
+    InvalidPageParent { parent: Parent },
+}
+
+impl Error {
+    /// The error Notion responded with, if the request reached it.
+    pub fn api_error(&self) -> Option<&ErrorResponse> {
+        match self {
+            Error::ApiError { error } => Some(error),
+            _ => None,
+        }
+    }
+
+    /// The HTTP status of the response the error came from, if there was one.
+    pub fn status(&self) -> Option<reqwest::StatusCode> {
+        match self {
+            Error::ApiError { error } => reqwest::StatusCode::from_u16(error.status.code()).ok(),
+            Error::OAuthError { status, .. } => Some(*status),
+            Error::RequestFailed { source } | Error::ResponseIoError { source } => source.status(),
+            _ => None,
+        }
+    }
+
+    /// The id Notion gave the failed request, to include when reporting a problem to Notion.
+    pub fn request_id(&self) -> Option<&str> {
+        self.api_error()?.request_id.as_deref()
+    }
+
+    /// Whether the request may succeed when sent again: it was rate limited,
+    /// failed with a server error, or timed out or couldn't connect.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::ApiError { error } => error.is_retryable(),
+            Error::RequestFailed { source } => source.is_timeout() || source.is_connect(),
+            _ => false,
+        }
+    }
+
+    /// How long Notion asked to wait before sending the request again, if it did.
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.api_error()?.retry_after
+    }
 }
 
 /// An API client for Notion.
-/// Create a client by using [new(api_token: String)](Self::new()).
+/// Create a client by using [new(api_token: String)](Self::new()),
+/// or [builder(api_token: String)](Self::builder()) to configure it further.
 #[derive(Clone)]
 pub struct NotionApi {
     client: Client,
+    base_url: String,
+    /// Authorization, `Notion-Version` and any other headers sent with every request.
+    headers: HeaderMap,
+    timeout: Option<Duration>,
+    request_signer: Option<RequestSigner>,
+    retry_policy: Option<RetryPolicy>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    append_queue: Option<Arc<AppendQueue>>,
+    conflict_pacer: Option<Arc<ConflictPacer>>,
+    transport: Arc<dyn NotionTransport>,
+    on_request: Option<RequestHook>,
+    on_response: Option<ResponseHook>,
+    cache: Option<ResponseCache>,
+    /// The version in the `Notion-Version` header, unless it's one this crate doesn't know.
+    api_version: Option<ApiVersion>,
 }
 
 impl NotionApi {
     /// Creates an instance of NotionApi.
     /// May fail if the provided api_token is an improper value.
     pub fn new(api_token: String) -> Result<Self, Error> {
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            "Notion-Version",
-            HeaderValue::from_static(NOTION_API_VERSION),
-        );
-
-        let mut auth_value = HeaderValue::from_str(&format!("Bearer {}", api_token))
-            .map_err(|source| Error::InvalidApiToken { source })?;
-        auth_value.set_sensitive(true);
-        headers.insert(header::AUTHORIZATION, auth_value);
-
-        let client = ClientBuilder::new()
-            .default_headers(headers)
-            .build()
-            .map_err(|source| Error::ErrorBuildingClient { source })?;
-
-        Ok(Self { client })
-    }
-
-    async fn make_json_request(
-        &self,
-        request: RequestBuilder,
-    ) -> Result<Object, Error> {
-        let request = request.build()?;
-        let url = request.url();
-        tracing::trace!(
-            method = request.method().as_str(),
-            url = url.as_str(),
-            "Sending request"
-        );
-        let json = self
-            .client
-            .execute(request)
-            .instrument(tracing::trace_span!("Sending request"))
-            .await
-            .map_err(|source| Error::RequestFailed { source })?
-            .text()
-            .instrument(tracing::trace_span!("Reading response"))
-            .await
-            .map_err(|source| Error::ResponseIoError { source })?;
+        Self::builder(api_token).build()
+    }
 
-        tracing::debug!("JSON Response: {}", json);
-        #[cfg(test)]
-        {
-            dbg!(serde_json::from_str::<serde_json::Value>(&json)
-                .map_err(|source| Error::JsonParseError { source })?);
-        }
-        let result =
-            serde_json::from_str(&json).map_err(|source| Error::JsonParseError { source })?;
+    /// Creates an instance of NotionApi configured from environment variables,
+    /// see [NotionApiBuilder::from_env()].
+    pub fn from_env() -> Result<Self, Error> {
+        NotionApiBuilder::from_env()?.build()
+    }
 
-        match result {
-            Object::Error { error } => Err(Error::ApiError { error }),
-            response => Ok(response),
-        }
+    /// Creates a [NotionApiBuilder] for configuring the client before it is created.
+    pub fn builder(api_token: String) -> NotionApiBuilder {
+        NotionApiBuilder::new(api_token)
+    }
+
+    /// A client that sends requests with `api_token` instead, e.g. to serve another
+    /// workspace a public integration was authorized for.
+    ///
+    /// The client shares the connection pool and configuration of this one.
+    /// Its requests are [rate limited](NotionApiBuilder::requests_per_second()) separately,
+    /// at the same rate, and it doesn't share the [cache](NotionApiBuilder::cache()),
+    /// whose objects another token may not have access to.
+    /// May fail if `api_token` is an improper value.
+    pub fn with_token<S: AsRef<str>>(
+        &self,
+        api_token: S,
+    ) -> Result<Self, Error> {
+        let mut headers = self.headers.clone();
+        headers.insert(header::AUTHORIZATION, builder::bearer(api_token.as_ref())?);
+
+        Ok(Self {
+            headers,
+            rate_limiter: self
+                .rate_limiter
+                .as_ref()
+                .map(|rate_limiter| Arc::new(rate_limiter.with_same_rate())),
+            cache: None,
+            ..self.clone()
+        })
     }
 
     /// List all the databases shared with the supplied integration token.
     /// > This method is apparently deprecated/"not recommended" and
     /// > [search()](Self::search()) should be used instead.
     pub async fn list_databases(&self) -> Result<ListResponse<Database>, Error> {
-        let builder = self.client.get("https://api.notion.com/v1/databases");
+        let builder = self.client.get(format!("{}/databases", self.base_url));
 
         match self.make_json_request(builder).await? {
-            Object::List { list } => Ok(list.expect_databases()?),
+            Object::List { list } => Ok(list.into_databases()?),
             response => Err(Error::UnexpectedResponse { response }),
         }
     }
 
-    /// Search all pages in notion.
+    /// Search the pages and databases shared with the integration.
     /// `query` can either be a [SearchRequest] or a slightly more convenient
     /// [NotionSearch](models::search::NotionSearch) query.
     pub async fn search<T: Into<SearchRequest>>(
         &self,
         query: T,
-    ) -> Result<ListResponse<Object>, Error> {
+    ) -> Result<ListResponse<SearchResult>, Error> {
         let result = self
             .make_json_request(
                 self.client
-                    .post("https://api.notion.com/v1/search")
+                    .post(format!("{}/search", self.base_url))
                     .json(&query.into()),
             )
             .await?;
 
         match result {
-            Object::List { list } => Ok(list),
+            Object::List { list } => Ok(list.into_search_results()?),
             response => Err(Error::UnexpectedResponse { response }),
         }
     }
 
+    /// Search all pages in notion, following `next_cursor` until every result has been fetched.
+    pub async fn search_all<T: Into<SearchRequest>>(
+        &self,
+        query: T,
+    ) -> Result<Vec<SearchResult>, Error> {
+        self.search_stream(query).try_collect().await
+    }
+
+    /// Search all pages in notion, yielding results as they are fetched
+    /// and requesting the next page of results only when needed.
+    pub fn search_stream<T: Into<SearchRequest>>(
+        &self,
+        query: T,
+    ) -> impl Stream<Item = Result<SearchResult, Error>> + '_ {
+        paginate(query.into(), move |query| self.search(query))
+    }
+
     /// Get a database by [DatabaseId].
     pub async fn get_database<T: AsIdentifier<DatabaseId>>(
         &self,
@@ -151,7 +306,8 @@ impl NotionApi {
     ) -> Result<Database, Error> {
         let result = self
             .make_json_request(self.client.get(format!(
-                "https://api.notion.com/v1/databases/{}",
+                "{}/databases/{}",
+                self.base_url,
                 database_id.as_id()
             )))
             .await?;
@@ -162,6 +318,54 @@ impl NotionApi {
         }
     }
 
+    /// Creates a new database as a child of a page and returns the created database.
+    pub async fn create_database<T: Into<DatabaseCreateRequest>>(
+        &self,
+        database: T,
+    ) -> Result<Database, Error> {
+        let database = database.into();
+        let body = self.database_body(&database, &database.properties)?;
+
+        let result = self
+            .make_json_request(
+                self.client
+                    .post(format!("{}/databases", self.base_url))
+                    .json(&body),
+            )
+            .await?;
+
+        match result {
+            Object::Database { database } => Ok(database),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    /// Updates the title or schema of a database and returns the updated database.
+    pub async fn update_database<T: AsIdentifier<DatabaseId>>(
+        &self,
+        database_id: T,
+        update: DatabaseUpdateRequest,
+    ) -> Result<Database, Error> {
+        let body = self.database_body(&update, &update.properties)?;
+
+        let result = self
+            .make_json_request(
+                self.client
+                    .patch(format!(
+                        "{}/databases/{}",
+                        self.base_url,
+                        database_id.as_id()
+                    ))
+                    .json(&body),
+            )
+            .await?;
+
+        match result {
+            Object::Database { database } => Ok(database),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
     /// Get a page by [PageId].
     pub async fn get_page<T: AsIdentifier<PageId>>(
         &self,
@@ -169,7 +373,8 @@ impl NotionApi {
     ) -> Result<Page, Error> {
         let result = self
             .make_json_request(self.client.get(format!(
-                "https://api.notion.com/v1/pages/{}",
+                "{}/pages/{}",
+                self.base_url,
                 page_id.as_id()
             )))
             .await?;
@@ -180,16 +385,117 @@ impl NotionApi {
         }
     }
 
-    /// Creates a new page and return the created page
+    /// Get a page's property by [PropertyId], one page of items at a time.
+    ///
+    /// Title, rich text, relation and people properties are paginated, with one
+    /// [PropertyItem] per element. Any other property is returned as a single item.
+    pub async fn get_page_property_item<P, T>(
+        &self,
+        page_id: P,
+        property_id: T,
+        paging: Paging,
+    ) -> Result<ListResponse<PropertyItem>, Error>
+    where
+        P: AsIdentifier<PageId>,
+        T: AsIdentifier<PropertyId>,
+    {
+        let result = self
+            .make_json_request(
+                self.client
+                    .get(format!(
+                        "{base_url}/pages/{page_id}/properties/{property_id}",
+                        base_url = self.base_url,
+                        page_id = page_id.as_id(),
+                        property_id = property_id.as_id()
+                    ))
+                    .query(&paging),
+            )
+            .await?;
+
+        match result {
+            Object::List { list } => Ok(list.into_property_items()?),
+            Object::PropertyItem { item } => Ok(ListResponse {
+                results: vec![item],
+                next_cursor: None,
+                has_more: false,
+            }),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    /// Get every item of a page's property, following `next_cursor` until all have been fetched.
+    pub async fn get_page_property_item_all<P, T>(
+        &self,
+        page_id: P,
+        property_id: T,
+    ) -> Result<Vec<PropertyItem>, Error>
+    where
+        P: AsIdentifier<PageId>,
+        T: AsIdentifier<PropertyId>,
+    {
+        let (page_id, property_id) = (page_id.as_id(), property_id.as_id());
+        paginate(Paging::default(), |paging| {
+            self.get_page_property_item(page_id, property_id, paging)
+        })
+        .try_collect()
+        .await
+    }
+
+    /// Creates a new page and return the created page.
+    /// The page's children are [validated](models::block::validate_children()) before sending.
     pub async fn create_page<T: Into<PageCreateRequest>>(
         &self,
         page: T,
+    ) -> Result<Page, Error> {
+        let page = page.into();
+        if let Some(children) = &page.children {
+            validate_children(children).map_err(|source| Error::InvalidBlockTree { source })?;
+        }
+
+        let result = self
+            .make_json_request(
+                self.client
+                    .post(format!("{}/pages", self.base_url))
+                    .json(&page),
+            )
+            .await?;
+
+        match result {
+            Object::Page { page } => Ok(page),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    /// Changes the properties, icon or cover of a page, and returns the updated page.
+    pub async fn update_page<T: AsIdentifier<PageId>>(
+        &self,
+        page_id: T,
+        update: PageUpdateRequest,
     ) -> Result<Page, Error> {
         let result = self
             .make_json_request(
                 self.client
-                    .post("https://api.notion.com/v1/pages")
-                    .json(&page.into()),
+                    .patch(format!("{}/pages/{}", self.base_url, page_id.as_id()))
+                    .json(&update),
+            )
+            .await?;
+
+        match result {
+            Object::Page { page } => Ok(page),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    /// Archives a page, moving it to the trash, and returns the archived page.
+    pub async fn archive_page<T: AsIdentifier<PageId>>(
+        &self,
+        page_id: T,
+    ) -> Result<Page, Error> {
+        let result = self
+            .make_json_request(
+                self.client
+                    .patch(format!("{}/pages/{}", self.base_url, page_id.as_id()))
+                    .json(&serde_json::json!({ "archived": true })),
             )
             .await?;
 
@@ -212,33 +518,251 @@ impl NotionApi {
         let result = self
             .make_json_request(
                 self.client
-                    .post(&format!(
-                        "https://api.notion.com/v1/databases/{database_id}/query",
+                    .post(format!(
+                        "{base_url}/databases/{database_id}/query",
+                        base_url = self.base_url,
                         database_id = database.as_id()
                     ))
                     .json(&query.into()),
             )
             .await?;
         match result {
-            Object::List { list } => Ok(list.expect_pages()?),
+            Object::List { list } => Ok(list.into_pages()?),
             response => Err(Error::UnexpectedResponse { response }),
         }
     }
 
+    /// Query a database, following `next_cursor` until every matching page has been fetched.
+    pub async fn query_database_all<D, T>(
+        &self,
+        database: D,
+        query: T,
+    ) -> Result<Vec<Page>, Error>
+    where
+        T: Into<DatabaseQuery>,
+        D: AsIdentifier<DatabaseId>,
+    {
+        self.query_database_stream(database, query)
+            .try_collect()
+            .await
+    }
+
+    /// Query a database, deserializing the properties of every matching page
+    /// into `T` with [Properties::deserialize_into](models::Properties::deserialize_into()).
+    pub async fn query_database_as<R, D, T>(
+        &self,
+        database: D,
+        query: T,
+    ) -> Result<Vec<R>, Error>
+    where
+        R: DeserializeOwned,
+        T: Into<DatabaseQuery>,
+        D: AsIdentifier<DatabaseId>,
+    {
+        self.query_database_stream(database, query)
+            .and_then(|page| async move {
+                page.properties.deserialize_into().map_err(|source| {
+                    Error::PropertyDeserialization {
+                        page_id: page.id,
+                        source,
+                    }
+                })
+            })
+            .try_collect()
+            .await
+    }
+
+    /// Query a database, yielding matching pages as they are fetched
+    /// and requesting the next page of results only when needed.
+    pub fn query_database_stream<D, T>(
+        &self,
+        database: D,
+        query: T,
+    ) -> impl Stream<Item = Result<Page, Error>> + '_
+    where
+        T: Into<DatabaseQuery>,
+        D: AsIdentifier<DatabaseId>,
+    {
+        let database_id = database.as_id().clone();
+        paginate(query.into(), move |query| {
+            self.query_database(database_id.clone(), query)
+        })
+    }
+
+    /// Get the first page of the children of a block, at most 100 of them.
+    /// Use [get_block_children_all](NotionApi::get_block_children_all()) for all the children.
     pub async fn get_block_children<T: AsIdentifier<BlockId>>(
         &self,
         block_id: T,
     ) -> Result<ListResponse<Block>, Error> {
+        self.get_block_children_page(block_id.as_id(), Paging::default())
+            .await
+    }
+
+    /// Get a page of the children of a block, starting at the cursor of `paging`,
+    /// with as many children as its page size.
+    pub async fn get_block_children_with_paging<T: AsIdentifier<BlockId>>(
+        &self,
+        block_id: T,
+        paging: Paging,
+    ) -> Result<ListResponse<Block>, Error> {
+        self.get_block_children_page(block_id.as_id(), paging).await
+    }
+
+    /// Get all the children of a block, following `next_cursor` until every child has been fetched.
+    pub async fn get_block_children_all<T: AsIdentifier<BlockId>>(
+        &self,
+        block_id: T,
+    ) -> Result<Vec<Block>, Error> {
+        self.get_block_children_stream(block_id).try_collect().await
+    }
+
+    /// Get the children of a block, yielding them as they are fetched
+    /// and requesting the next page of children only when needed.
+    pub fn get_block_children_stream<T: AsIdentifier<BlockId>>(
+        &self,
+        block_id: T,
+    ) -> impl Stream<Item = Result<Block, Error>> + '_ {
+        let block_id = block_id.as_id().clone();
+        paginate(Paging::default(), move |paging| {
+            let block_id = block_id.clone();
+            async move { self.get_block_children_page(&block_id, paging).await }
+        })
+    }
+
+    /// Counts the children of a block, requesting as many pages of children as needed
+    /// but skipping over the blocks instead of parsing them.
+    /// Only direct children are counted.
+    pub async fn count_block_children<T: AsIdentifier<BlockId>>(
+        &self,
+        block_id: T,
+    ) -> Result<usize, Error> {
+        let block_id = block_id.as_id();
+        paginate(Paging::default().with_page_size(100), |paging| {
+            self.skip_block_children_page(block_id, paging)
+        })
+        .try_fold(0, |count, _| async move { Ok(count + 1) })
+        .await
+    }
+
+    /// Whether a block has any children, requesting at most one of them.
+    pub async fn has_any_children<T: AsIdentifier<BlockId>>(
+        &self,
+        block_id: T,
+    ) -> Result<bool, Error> {
+        let children = self
+            .skip_block_children_page(block_id.as_id(), Paging::default().with_page_size(1))
+            .await?;
+        Ok(!children.results.is_empty())
+    }
+
+    /// Appends blocks to the children of a block or page and returns the appended blocks.
+    ///
+    /// When [ordered appends](NotionApiBuilder::ordered_appends()) are enabled,
+    /// concurrent appends to the same block are sent one at a time in the order they were made.
+    /// The blocks are [validated](models::block::validate_children()) before sending.
+    pub async fn append_block_children<T: AsIdentifier<BlockId>>(
+        &self,
+        block_id: T,
+        children: Vec<CreateBlock>,
+    ) -> Result<ListResponse<Block>, Error> {
+        validate_children(&children).map_err(|source| Error::InvalidBlockTree { source })?;
+
+        let block_id = block_id.as_id();
+        let _turn = match &self.append_queue {
+            Some(queue) => Some(queue.turn(block_id).await),
+            None => None,
+        };
+
         let result = self
-            .make_json_request(self.client.get(&format!(
-                "https://api.notion.com/v1/blocks/{block_id}/children",
-                block_id = block_id.as_id()
-            )))
+            .make_json_request(
+                self.client
+                    .patch(format!(
+                        "{base_url}/blocks/{block_id}/children",
+                        base_url = self.base_url,
+                        block_id = block_id
+                    ))
+                    .json(&serde_json::json!({ "children": children })),
+            )
+            .await?;
+
+        match result {
+            Object::List { list } => Ok(list.into_blocks()?),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    /// Replaces the content of a block, keeping its id and position, and returns the updated block.
+    ///
+    /// The children of `block` are ignored: Notion doesn't change children in an update,
+    /// [append](NotionApi::append_block_children()) or [trash](NotionApi::trash_block()) those.
+    pub async fn update_block<T: AsIdentifier<BlockId>>(
+        &self,
+        block_id: T,
+        mut block: CreateBlock,
+    ) -> Result<Block, Error> {
+        block.take_children();
+
+        let result = self
+            .make_json_request(
+                self.client
+                    .patch(format!("{}/blocks/{}", self.base_url, block_id.as_id()))
+                    .json(&block),
+            )
             .await?;
 
         match result {
-            Object::List { list } => Ok(list.expect_blocks()?),
+            Object::Block { block } => Ok(block),
             response => Err(Error::UnexpectedResponse { response }),
         }
     }
+
+    async fn get_block_children_page(
+        &self,
+        block_id: &BlockId,
+        paging: Paging,
+    ) -> Result<ListResponse<Block>, Error> {
+        let result = self
+            .make_json_request(
+                self.client
+                    .get(format!(
+                        "{base_url}/blocks/{block_id}/children",
+                        base_url = self.base_url,
+                        block_id = block_id
+                    ))
+                    .query(&paging),
+            )
+            .await?;
+
+        match result {
+            Object::List { list } => Ok(list.into_blocks()?),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    /// Gets a page of the children of a block, skipping over the blocks.
+    async fn skip_block_children_page(
+        &self,
+        block_id: &BlockId,
+        paging: Paging,
+    ) -> Result<ListResponse<IgnoredAny>, Error> {
+        let response = self
+            .send_request(
+                self.client
+                    .get(format!(
+                        "{base_url}/blocks/{block_id}/children",
+                        base_url = self.base_url,
+                        block_id = block_id
+                    ))
+                    .query(&paging),
+            )
+            .await?;
+
+        serde_json::from_str(&response.body).map_err(|source| {
+            match serde_json::from_str(&response.body) {
+                Ok(Object::Error { error }) => request::api_error(error, &response),
+                _ => Error::JsonParseError { source },
+            }
+        })
+    }
 }
@@ -1,11 +1,20 @@
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use serde::de::Error as _;
+use serde::ser::{Error as _, SerializeMap};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::ids::{AsIdentifier, BlockId, DatabaseId, PageId};
-use crate::models::text::{RichText, TextColor};
+use crate::models::text::{plain_text, RichText, TextColor};
 use crate::models::users::UserCommon;
+use crate::models::Parent;
 
+mod table;
+#[cfg(test)]
 mod tests;
+mod validate;
+
+pub use table::TableBuilder;
+pub use validate::{validate_children, BlockTreeError, BlockTreeProblem};
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 pub struct BlockCommon {
@@ -15,13 +24,24 @@ pub struct BlockCommon {
     pub has_children: bool,
     pub created_by: UserCommon,
     pub last_edited_by: UserCommon,
+    #[serde(default)]
+    pub archived: bool,
+    /// Whether the block is in the trash. Responses from before Notion added the field leave it `false`.
+    #[serde(default)]
+    pub in_trash: bool,
+    /// The page, block or database the block is in.
+    /// Notion includes it from API version `2022-06-28`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Parent>,
 }
 
+/// Fields of blocks that can have children: [Block]s when read from Notion,
+/// or [CreateBlock]s to create along with the block.
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
-pub struct TextAndChildren {
+pub struct TextAndChildren<B = Block> {
     pub rich_text: Vec<RichText>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub children: Option<Vec<Block>>,
+    pub children: Option<Vec<B>>,
     pub color: TextColor,
 }
 
@@ -41,6 +61,14 @@ pub struct ExternalFileObject {
     url: String,
 }
 
+/// An emoji uploaded to the workspace.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct CustomEmoji {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 #[serde(tag = "type")]
 #[serde(rename_all = "snake_case")]
@@ -48,6 +76,7 @@ pub enum FileOrEmojiObject {
     Emoji { emoji: String },
     File { file: InternalFileObject },
     External { external: ExternalFileObject },
+    CustomEmoji { custom_emoji: CustomEmoji },
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
@@ -58,6 +87,24 @@ pub enum FileObject {
     External { external: ExternalFileObject },
 }
 
+impl FileObject {
+    /// A file hosted outside of Notion.
+    pub fn external<U: Into<String>>(url: U) -> Self {
+        FileObject::External {
+            external: ExternalFileObject { url: url.into() },
+        }
+    }
+}
+
+/// A file block's file, along with its caption.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct FileFields {
+    #[serde(flatten)]
+    pub file: FileObject,
+    #[serde(default)]
+    pub caption: Vec<RichText>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 pub struct Callout {
     pub rich_text: Vec<RichText>,
@@ -66,11 +113,11 @@ pub struct Callout {
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
-pub struct ToDoFields {
+pub struct ToDoFields<B = Block> {
     pub rich_text: Vec<RichText>,
     pub checked: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub children: Option<Vec<Block>>,
+    pub children: Option<Vec<B>>,
     pub color: TextColor,
 }
 
@@ -198,13 +245,17 @@ pub struct TableOfContents {
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
-pub struct ColumnListFields {
-    pub children: Vec<Block>,
+pub struct ColumnListFields<B = Block> {
+    /// Responses leave the children out.
+    #[serde(default = "Vec::new")]
+    pub children: Vec<B>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
-pub struct ColumnFields {
-    pub children: Vec<Block>,
+pub struct ColumnFields<B = Block> {
+    /// Responses leave the children out.
+    #[serde(default = "Vec::new")]
+    pub children: Vec<B>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
@@ -213,9 +264,9 @@ pub struct LinkPreviewFields {
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
-pub struct TemplateFields {
+pub struct TemplateFields<B = Block> {
     pub rich_text: Vec<RichText>,
-    pub children: Vec<Block>,
+    pub children: Vec<B>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
@@ -232,25 +283,52 @@ pub struct SyncedFromObject {
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
-pub struct SyncedBlockFields {
+pub struct SyncedBlockFields<B = Block> {
+    /// The original synced block this block duplicates, or `None` for an original.
     pub synced_from: Option<SyncedFromObject>,
-    pub children: Vec<Block>,
+    /// The content of an original synced block, created along with it.
+    /// Duplicates show the content of their original, and responses leave it out.
+    #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<B>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
-pub struct TableFields {
+pub struct TableFields<B = Block> {
+    /// The number of cells in every row.
     pub table_width: u64,
     pub has_column_header: bool,
     pub has_row_header: bool,
-    pub children: Vec<Block>,
+    /// The [table rows](Block::TableRow). Tables are created with at least one row,
+    /// and responses leave the rows out.
+    #[serde(default = "Vec::new")]
+    pub children: Vec<B>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 pub struct TableRowFields {
-    pub cells: Vec<RichText>,
+    /// The text of each cell, from left to right.
+    pub cells: Vec<Vec<RichText>>,
 }
 
+impl TableRowFields {
+    /// The plain text of the cells, separated by tabs.
+    pub fn plain_text(&self) -> String {
+        self.cells
+            .iter()
+            .map(|cell| plain_text(cell))
+            .collect::<Vec<_>>()
+            .join("\t")
+    }
+}
+
+/// A block read from Notion.
+///
+/// Blocks of types this crate doesn't know yet become [Block::Unknown], which keeps
+/// their id and content so they can still be identified, skipped or written back out.
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+// Serialize and Deserialize are implemented below, on top of these derived ones,
+// to handle unknown block types.
+#[serde(remote = "Self")]
 #[serde(tag = "type")]
 #[serde(rename_all = "snake_case")]
 pub enum Block {
@@ -320,7 +398,7 @@ pub enum Block {
     ChildDatabase {
         #[serde(flatten)]
         common: BlockCommon,
-        child_page: ChildDatabaseFields,
+        child_database: ChildDatabaseFields,
     },
     Embed {
         #[serde(flatten)]
@@ -337,11 +415,15 @@ pub enum Block {
         common: BlockCommon,
         video: FileObject,
     },
+    Audio {
+        #[serde(flatten)]
+        common: BlockCommon,
+        audio: FileObject,
+    },
     File {
         #[serde(flatten)]
         common: BlockCommon,
-        file: FileObject,
-        caption: Text,
+        file: FileFields,
     },
     Pdf {
         #[serde(flatten)]
@@ -415,12 +497,83 @@ pub enum Block {
         #[serde(flatten)]
         common: BlockCommon,
     },
-    #[serde(other)]
-    Unknown,
+    #[serde(skip)]
+    Unknown {
+        common: BlockCommon,
+        /// The type of the block, e.g. `ai_block`.
+        kind: String,
+        /// The type specific content of the block, stored under its type.
+        data: serde_json::Value,
+    },
+}
+
+impl<'de> Deserialize<'de> for Block {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut value = serde_json::Value::deserialize(deserializer)?;
+        let error = match Block::deserialize(&value) {
+            Ok(block) => return Ok(block),
+            Err(error) => error,
+        };
+
+        // Only blocks of unknown types fall back to Unknown, other errors are reported.
+        // A block with nothing but its type can only fail on the type when it is unknown,
+        // unlike the block itself, whose content may hold unknown variants of its own.
+        let kind = match value.get("type").and_then(|kind| kind.as_str()) {
+            Some(kind) if !Block::is_known_type(kind) => kind.to_string(),
+            _ => return Err(D::Error::custom(error)),
+        };
+        let data = value
+            .as_object_mut()
+            .and_then(|block| block.remove(&kind))
+            .unwrap_or_default();
+        let common = BlockCommon::deserialize(value).map_err(D::Error::custom)?;
+
+        Ok(Block::Unknown { common, kind, data })
+    }
+}
+
+impl Block {
+    fn is_known_type(kind: &str) -> bool {
+        match Block::deserialize(serde_json::json!({ "type": kind })) {
+            Ok(_) => true,
+            Err(error) => !error.to_string().starts_with("unknown variant"),
+        }
+    }
+}
+
+impl Serialize for Block {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match self {
+            Block::Unknown { common, kind, data } => {
+                let mut block = serde_json::to_value(common).map_err(S::Error::custom)?;
+                if let Some(block) = block.as_object_mut() {
+                    block.insert("type".to_string(), kind.clone().into());
+                    block.insert(kind.clone(), data.clone());
+                }
+                block.serialize(serializer)
+            }
+            block => Block::serialize(block, serializer),
+        }
+    }
 }
 
 impl AsIdentifier<BlockId> for Block {
     fn as_id(&self) -> &BlockId {
+        &self.common().id
+    }
+}
+
+impl Block {
+    /// The page, block or database the block is in, when Notion included it.
+    pub fn parent(&self) -> Option<&Parent> {
+        self.common().parent.as_ref()
+    }
+
+    /// The fields every block has.
+    pub fn common(&self) -> &BlockCommon {
         use Block::*;
         match self {
             Paragraph { common, .. }
@@ -439,6 +592,7 @@ impl AsIdentifier<BlockId> for Block {
             | Embed { common, .. }
             | Image { common, .. }
             | Video { common, .. }
+            | Audio { common, .. }
             | File { common, .. }
             | Pdf { common, .. }
             | Bookmark { common, .. }
@@ -454,38 +608,149 @@ impl AsIdentifier<BlockId> for Block {
             | SyncedBlock { common, .. }
             | Table { common, .. }
             | TableRow { common, .. }
-            | Unsupported { common, .. } => &common.id,
-            Unknown => {
-                panic!("Trying to reference identifier for unknown block!")
+            | Unsupported { common, .. }
+            | Unknown { common, .. } => common,
+        }
+    }
+
+    /// The rich text of paragraphs, headings, list items and other blocks of text,
+    /// empty for other blocks.
+    pub fn rich_text(&self) -> &[RichText] {
+        match self {
+            Block::Paragraph {
+                paragraph: text, ..
+            }
+            | Block::Quote { quote: text, .. }
+            | Block::BulletedListItem {
+                bulleted_list_item: text,
+                ..
+            }
+            | Block::NumberedListItem {
+                numbered_list_item: text,
+                ..
+            }
+            | Block::Toggle { toggle: text, .. } => &text.rich_text,
+            Block::Heading1 {
+                heading_1: text, ..
             }
+            | Block::Heading2 {
+                heading_2: text, ..
+            }
+            | Block::Heading3 {
+                heading_3: text, ..
+            } => &text.rich_text,
+            Block::Callout { callout, .. } => &callout.rich_text,
+            Block::ToDo { to_do, .. } => &to_do.rich_text,
+            Block::Code { code, .. } => &code.rich_text,
+            Block::Template { template, .. } => &template.rich_text,
+            _ => &[],
         }
     }
-}
 
-impl Into<CreateBlock> for Block {
-    fn into(self) -> CreateBlock {
+    /// The text of the block, without its children: its [rich text](Block::rich_text()),
+    /// the title of child pages and databases, bookmark captions, equations and table cells.
+    pub fn plain_text(&self) -> String {
         match self {
-            Block::Paragraph { paragraph, .. } => CreateBlock::Paragraph { paragraph },
+            Block::ChildPage { child_page, .. } => child_page.title.clone(),
+            Block::ChildDatabase { child_database, .. } => child_database.title.clone(),
+            Block::Bookmark { bookmark, .. } => plain_text(&bookmark.caption),
+            Block::Equation { equation, .. } => equation.expression.clone(),
+            Block::TableRow { table_row, .. } => table_row.plain_text(),
+            block => plain_text(block.rich_text()),
+        }
+    }
+
+    /// The text of the block and the [children](Block::children()) it includes,
+    /// a line per block with text.
+    pub fn plain_text_with_children(&self) -> String {
+        let mut lines = vec![];
+        self.push_lines(&mut lines);
+        lines.join("\n")
+    }
+
+    fn push_lines(
+        &self,
+        lines: &mut Vec<String>,
+    ) {
+        let text = self.plain_text();
+        if !text.is_empty() {
+            lines.push(text);
+        }
+        for child in self.children() {
+            child.push_lines(lines);
+        }
+    }
+
+    /// The child blocks included with this block, if any.
+    ///
+    /// Responses usually leave these out; use [has_children](BlockCommon::has_children)
+    /// to tell whether the block has children to fetch.
+    pub fn children(&self) -> &[Block] {
+        let children = match self {
+            Block::Paragraph {
+                paragraph: text, ..
+            }
+            | Block::Quote { quote: text, .. }
+            | Block::BulletedListItem {
+                bulleted_list_item: text,
+                ..
+            }
+            | Block::NumberedListItem {
+                numbered_list_item: text,
+                ..
+            }
+            | Block::Toggle { toggle: text, .. } => text.children.as_deref(),
+            Block::ToDo { to_do, .. } => to_do.children.as_deref(),
+            Block::ColumnList { column_list, .. } => Some(column_list.children.as_slice()),
+            Block::Column { column, .. } => Some(column.children.as_slice()),
+            Block::Template { template, .. } => Some(template.children.as_slice()),
+            Block::SyncedBlock { synced_block, .. } => Some(synced_block.children.as_slice()),
+            Block::Table { table, .. } => Some(table.children.as_slice()),
+            _ => None,
+        };
+        children.unwrap_or_default()
+    }
+}
+
+impl From<Block> for CreateBlock {
+    fn from(block: Block) -> Self {
+        match block {
+            Block::Paragraph { paragraph, .. } => CreateBlock::Paragraph {
+                paragraph: paragraph.into(),
+            },
             Block::Heading1 { heading_1, .. } => CreateBlock::Heading1 { heading_1 },
             Block::Heading2 { heading_2, .. } => CreateBlock::Heading2 { heading_2 },
             Block::Heading3 { heading_3, .. } => CreateBlock::Heading3 { heading_3 },
             Block::Callout { callout, .. } => CreateBlock::Callout { callout },
-            Block::Quote { quote, .. } => CreateBlock::Quote { quote },
+            Block::Quote { quote, .. } => CreateBlock::Quote {
+                quote: quote.into(),
+            },
             Block::BulletedListItem {
                 bulleted_list_item, ..
-            } => CreateBlock::BulletedListItem { bulleted_list_item },
+            } => CreateBlock::BulletedListItem {
+                bulleted_list_item: bulleted_list_item.into(),
+            },
             Block::NumberedListItem {
                 numbered_list_item, ..
-            } => CreateBlock::NumberedListItem { numbered_list_item },
-            Block::ToDo { to_do, .. } => CreateBlock::ToDo { to_do },
-            Block::Toggle { toggle, .. } => CreateBlock::Toggle { toggle },
+            } => CreateBlock::NumberedListItem {
+                numbered_list_item: numbered_list_item.into(),
+            },
+            Block::ToDo { to_do, .. } => CreateBlock::ToDo {
+                to_do: to_do.into(),
+            },
+            Block::Toggle { toggle, .. } => CreateBlock::Toggle {
+                toggle: toggle.into(),
+            },
             Block::Code { code, .. } => CreateBlock::Code { code },
             Block::ChildPage { child_page, .. } => CreateBlock::ChildPage { child_page },
-            Block::ChildDatabase { child_page, .. } => CreateBlock::ChildDatabase { child_page },
+            Block::ChildDatabase { child_database, .. } => {
+                CreateBlock::ChildDatabase { child_database }
+            }
             Block::Embed { embed, .. } => CreateBlock::Embed { embed },
             Block::Image { image, .. } => CreateBlock::Image { image },
             Block::Video { video, .. } => CreateBlock::Video { video },
-            Block::File { file, caption, .. } => CreateBlock::File { file, caption },
+            Block::Audio { audio, .. } => CreateBlock::Audio { audio },
+            Block::File { file, .. } => CreateBlock::File { file },
             Block::Pdf { pdf, .. } => CreateBlock::Pdf { pdf },
             Block::Bookmark { bookmark, .. } => CreateBlock::Bookmark { bookmark },
             Block::Equation { equation, .. } => CreateBlock::Equation { equation },
@@ -494,17 +759,98 @@ impl Into<CreateBlock> for Block {
                 table_of_contents, ..
             } => CreateBlock::TableOfContents { table_of_contents },
             Block::Breadcrumb { .. } => CreateBlock::Breadcrumb {},
-            Block::ColumnList { column_list, .. } => CreateBlock::ColumnList { column_list },
-            Block::Column { column, .. } => CreateBlock::Column { column },
+            Block::ColumnList { column_list, .. } => CreateBlock::ColumnList {
+                column_list: column_list.into(),
+            },
+            Block::Column { column, .. } => CreateBlock::Column {
+                column: column.into(),
+            },
 
             Block::LinkPreview { link_preview, .. } => CreateBlock::LinkPreview { link_preview },
-            Block::Template { template, .. } => CreateBlock::Template { template },
+            Block::Template { template, .. } => CreateBlock::Template {
+                template: template.into(),
+            },
             Block::LinkToPage { link_to_page, .. } => CreateBlock::LinkToPage { link_to_page },
-            Block::Table { table, .. } => CreateBlock::Table { table },
-            Block::SyncedBlock { synced_block, .. } => CreateBlock::SyncedBlock { synced_block },
+            Block::Table { table, .. } => CreateBlock::Table {
+                table: table.into(),
+            },
+            Block::SyncedBlock { synced_block, .. } => CreateBlock::SyncedBlock {
+                synced_block: synced_block.into(),
+            },
             Block::TableRow { table_row, .. } => CreateBlock::TableRow { table_row },
             Block::Unsupported { .. } => CreateBlock::Unsupported,
-            Block::Unknown => CreateBlock::Unknown,
+            Block::Unknown { .. } => CreateBlock::Unknown,
+        }
+    }
+}
+
+/// Blocks read from Notion, as blocks to create.
+fn create_all(blocks: Vec<Block>) -> Vec<CreateBlock> {
+    blocks.into_iter().map(CreateBlock::from).collect()
+}
+
+impl From<TextAndChildren> for TextAndChildren<CreateBlock> {
+    fn from(text: TextAndChildren) -> Self {
+        TextAndChildren {
+            rich_text: text.rich_text,
+            children: text.children.map(create_all),
+            color: text.color,
+        }
+    }
+}
+
+impl From<ToDoFields> for ToDoFields<CreateBlock> {
+    fn from(to_do: ToDoFields) -> Self {
+        ToDoFields {
+            rich_text: to_do.rich_text,
+            checked: to_do.checked,
+            children: to_do.children.map(create_all),
+            color: to_do.color,
+        }
+    }
+}
+
+impl From<ColumnListFields> for ColumnListFields<CreateBlock> {
+    fn from(column_list: ColumnListFields) -> Self {
+        ColumnListFields {
+            children: create_all(column_list.children),
+        }
+    }
+}
+
+impl From<ColumnFields> for ColumnFields<CreateBlock> {
+    fn from(column: ColumnFields) -> Self {
+        ColumnFields {
+            children: create_all(column.children),
+        }
+    }
+}
+
+impl From<TemplateFields> for TemplateFields<CreateBlock> {
+    fn from(template: TemplateFields) -> Self {
+        TemplateFields {
+            rich_text: template.rich_text,
+            children: create_all(template.children),
+        }
+    }
+}
+
+impl From<SyncedBlockFields> for SyncedBlockFields<CreateBlock> {
+    fn from(synced_block: SyncedBlockFields) -> Self {
+        SyncedBlockFields {
+            synced_from: synced_block.synced_from,
+            children: create_all(synced_block.children),
+        }
+    }
+}
+
+impl From<TableFields> for TableFields<CreateBlock> {
+    fn from(table: TableFields) -> Self {
+        TableFields {
+            table_width: table.table_width,
+            has_column_header: table.has_column_header,
+            has_row_header: table.has_row_header,
+            children: create_all(table.children),
         }
     }
 }
@@ -514,7 +860,7 @@ impl Into<CreateBlock> for Block {
 #[serde(rename_all = "snake_case")]
 pub enum CreateBlock {
     Paragraph {
-        paragraph: TextAndChildren,
+        paragraph: TextAndChildren<CreateBlock>,
     },
     #[serde(rename = "heading_1")]
     Heading1 {
@@ -532,19 +878,19 @@ pub enum CreateBlock {
         callout: Callout,
     },
     Quote {
-        quote: TextAndChildren,
+        quote: TextAndChildren<CreateBlock>,
     },
     BulletedListItem {
-        bulleted_list_item: TextAndChildren,
+        bulleted_list_item: TextAndChildren<CreateBlock>,
     },
     NumberedListItem {
-        numbered_list_item: TextAndChildren,
+        numbered_list_item: TextAndChildren<CreateBlock>,
     },
     ToDo {
-        to_do: ToDoFields,
+        to_do: ToDoFields<CreateBlock>,
     },
     Toggle {
-        toggle: TextAndChildren,
+        toggle: TextAndChildren<CreateBlock>,
     },
     Code {
         code: CodeFields,
@@ -553,7 +899,7 @@ pub enum CreateBlock {
         child_page: ChildPageFields,
     },
     ChildDatabase {
-        child_page: ChildDatabaseFields,
+        child_database: ChildDatabaseFields,
     },
     Embed {
         embed: EmbedFields,
@@ -564,9 +910,11 @@ pub enum CreateBlock {
     Video {
         video: FileObject,
     },
+    Audio {
+        audio: FileObject,
+    },
     File {
-        file: FileObject,
-        caption: Text,
+        file: FileFields,
     },
     Pdf {
         pdf: FileObject,
@@ -577,31 +925,33 @@ pub enum CreateBlock {
     Equation {
         equation: Equation,
     },
+    #[serde(serialize_with = "serialize_divider")]
     Divider,
     TableOfContents {
         table_of_contents: TableOfContents,
     },
+    #[serde(serialize_with = "serialize_breadcrumb")]
     Breadcrumb,
     ColumnList {
-        column_list: ColumnListFields,
+        column_list: ColumnListFields<CreateBlock>,
     },
     Column {
-        column: ColumnFields,
+        column: ColumnFields<CreateBlock>,
     },
     LinkPreview {
         link_preview: LinkPreviewFields,
     },
     Template {
-        template: TemplateFields,
+        template: TemplateFields<CreateBlock>,
     },
     LinkToPage {
         link_to_page: LinkToPageFields,
     },
     Table {
-        table: TableFields,
+        table: TableFields<CreateBlock>,
     },
     SyncedBlock {
-        synced_block: SyncedBlockFields,
+        synced_block: SyncedBlockFields<CreateBlock>,
     },
     TableRow {
         table_row: TableRowFields,
@@ -610,3 +960,123 @@ pub enum CreateBlock {
     #[serde(other)]
     Unknown,
 }
+
+/// Notion needs the empty fields of blocks without content, e.g. `"divider": {}`.
+#[derive(Serialize)]
+struct Empty {}
+
+fn serialize_divider<S: Serializer>(serializer: S) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(Some(1))?;
+    map.serialize_entry("divider", &Empty {})?;
+    map.end()
+}
+
+fn serialize_breadcrumb<S: Serializer>(serializer: S) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(Some(1))?;
+    map.serialize_entry("breadcrumb", &Empty {})?;
+    map.end()
+}
+
+impl CreateBlock {
+    /// A block showing a LaTeX expression.
+    pub fn equation<S: Into<String>>(expression: S) -> Self {
+        CreateBlock::Equation {
+            equation: Equation {
+                expression: expression.into(),
+            },
+        }
+    }
+
+    /// An original synced block with its content, which duplicates then show.
+    pub fn synced_block(children: Vec<CreateBlock>) -> Self {
+        CreateBlock::SyncedBlock {
+            synced_block: SyncedBlockFields {
+                synced_from: None,
+                children,
+            },
+        }
+    }
+
+    /// A duplicate of the original synced block `original`, which shows its content.
+    pub fn synced_duplicate<T: AsIdentifier<BlockId>>(original: T) -> Self {
+        CreateBlock::SyncedBlock {
+            synced_block: SyncedBlockFields {
+                synced_from: Some(SyncedFromObject {
+                    block_id: original.as_id().clone(),
+                }),
+                children: vec![],
+            },
+        }
+    }
+
+    /// The child blocks created along with this block.
+    pub fn children(&self) -> &[CreateBlock] {
+        let children = match self {
+            CreateBlock::Paragraph { paragraph: text }
+            | CreateBlock::Quote { quote: text }
+            | CreateBlock::BulletedListItem {
+                bulleted_list_item: text,
+            }
+            | CreateBlock::NumberedListItem {
+                numbered_list_item: text,
+            }
+            | CreateBlock::Toggle { toggle: text } => text.children.as_deref(),
+            CreateBlock::ToDo { to_do } => to_do.children.as_deref(),
+            CreateBlock::ColumnList { column_list } => Some(column_list.children.as_slice()),
+            CreateBlock::Column { column } => Some(column.children.as_slice()),
+            CreateBlock::Template { template } => Some(template.children.as_slice()),
+            CreateBlock::SyncedBlock { synced_block } => Some(synced_block.children.as_slice()),
+            CreateBlock::Table { table } => Some(table.children.as_slice()),
+            _ => None,
+        };
+        children.unwrap_or_default()
+    }
+
+    /// Removes the child blocks created along with this block and returns them.
+    pub(crate) fn take_children(&mut self) -> Vec<CreateBlock> {
+        match self {
+            CreateBlock::Paragraph { paragraph: text }
+            | CreateBlock::Quote { quote: text }
+            | CreateBlock::BulletedListItem {
+                bulleted_list_item: text,
+            }
+            | CreateBlock::NumberedListItem {
+                numbered_list_item: text,
+            }
+            | CreateBlock::Toggle { toggle: text } => text.children.take().unwrap_or_default(),
+            CreateBlock::ToDo { to_do } => to_do.children.take().unwrap_or_default(),
+            CreateBlock::ColumnList { column_list } => std::mem::take(&mut column_list.children),
+            CreateBlock::Column { column } => std::mem::take(&mut column.children),
+            CreateBlock::Template { template } => std::mem::take(&mut template.children),
+            CreateBlock::SyncedBlock { synced_block } => std::mem::take(&mut synced_block.children),
+            CreateBlock::Table { table } => std::mem::take(&mut table.children),
+            _ => vec![],
+        }
+    }
+
+    /// Creates `children` along with this block, for blocks that can have children.
+    pub(crate) fn set_children(
+        &mut self,
+        children: Vec<CreateBlock>,
+    ) {
+        let optional = (!children.is_empty()).then_some(children.clone());
+        match self {
+            CreateBlock::Paragraph { paragraph: text }
+            | CreateBlock::Quote { quote: text }
+            | CreateBlock::BulletedListItem {
+                bulleted_list_item: text,
+            }
+            | CreateBlock::NumberedListItem {
+                numbered_list_item: text,
+            }
+            | CreateBlock::Toggle { toggle: text } => text.children = optional,
+            CreateBlock::ToDo { to_do } => to_do.children = optional,
+            CreateBlock::ColumnList { column_list } => column_list.children = children,
+            CreateBlock::Column { column } => column.children = children,
+            CreateBlock::Template { template } => template.children = children,
+            CreateBlock::SyncedBlock { synced_block } => synced_block.children = children,
+            CreateBlock::Table { table } => table.children = children,
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use crate::models::block::{CreateBlock, TableFields, TableRowFields};
+use crate::models::text::RichText;
+
+/// Builds a [table](CreateBlock::Table) along with its rows, to create in a single append.
+///
+/// ```
+/// use notion::models::block::{CreateBlock, TableBuilder};
+///
+/// let table: CreateBlock = TableBuilder::new(3)
+///     .header(["Region", "Q1", "Q2"])
+///     .row(["North", "12", "15"])
+///     .row(["South", "9"])
+///     .build();
+/// # assert_eq!(table.children().len(), 3);
+/// ```
+///
+/// Rows with fewer cells than the table is wide get empty cells at the end,
+/// and rows with more cells widen the table.
+/// Notion creates at most 100 rows along with a table, so tables with more rows are
+/// created with [append_block_tree](crate::NotionApi::append_block_tree()),
+/// which appends the rest of the rows once the table exists.
+#[derive(Debug, Clone, Default)]
+pub struct TableBuilder {
+    width: usize,
+    header: Option<Vec<Vec<RichText>>>,
+    has_row_header: bool,
+    rows: Vec<Vec<Vec<RichText>>>,
+}
+
+impl TableBuilder {
+    /// Starts a table with `width` cells in every row.
+    pub fn new(width: usize) -> Self {
+        Self {
+            width,
+            ..Self::default()
+        }
+    }
+
+    /// The column headers, shown as the first row.
+    pub fn header<I>(
+        self,
+        cells: I,
+    ) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        Self {
+            header: Some(text_cells(cells)),
+            ..self
+        }
+    }
+
+    /// Whether the first cell of every row is shown as a header.
+    pub fn row_header(
+        self,
+        has_row_header: bool,
+    ) -> Self {
+        Self {
+            has_row_header,
+            ..self
+        }
+    }
+
+    /// Adds a row of plain text cells.
+    pub fn row<I>(
+        self,
+        cells: I,
+    ) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.rich_text_row(text_cells(cells))
+    }
+
+    /// Adds a row of cells with formatting, links or mentions.
+    pub fn rich_text_row(
+        mut self,
+        cells: Vec<Vec<RichText>>,
+    ) -> Self {
+        self.rows.push(cells);
+        self
+    }
+
+    pub fn build(self) -> CreateBlock {
+        let has_column_header = self.header.is_some();
+        let rows: Vec<_> = self.header.into_iter().chain(self.rows).collect();
+        let width = rows.iter().map(Vec::len).fold(self.width, usize::max);
+
+        let children = rows
+            .into_iter()
+            .map(|mut cells| {
+                cells.resize(width, vec![]);
+                CreateBlock::TableRow {
+                    table_row: TableRowFields { cells },
+                }
+            })
+            .collect();
+
+        CreateBlock::Table {
+            table: TableFields {
+                table_width: width as u64,
+                has_column_header,
+                has_row_header: self.has_row_header,
+                children,
+            },
+        }
+    }
+}
+
+impl From<TableBuilder> for CreateBlock {
+    fn from(builder: TableBuilder) -> Self {
+        builder.build()
+    }
+}
+
+fn text_cells<I>(cells: I) -> Vec<Vec<RichText>>
+where
+    I: IntoIterator,
+    I::Item: Into<String>,
+{
+    cells
+        .into_iter()
+        .map(|cell| {
+            let text = cell.into();
+            match text.is_empty() {
+                true => vec![],
+                false => vec![RichText::text(text)],
+            }
+        })
+        .collect()
+}
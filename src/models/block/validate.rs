@@ -0,0 +1,226 @@
+use crate::models::block::CreateBlock;
+use std::fmt;
+
+/// How deep blocks can be nested below the blocks of a single request.
+const MAX_NESTING: usize = 2;
+
+/// A structural rule Notion enforces when creating blocks.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum BlockTreeProblem {
+    /// A `table_row` whose parent isn't a `table`.
+    TableRowOutsideTable,
+    /// A `column` whose parent isn't a `column_list`.
+    ColumnOutsideColumnList,
+    /// A block nested more than two levels below the blocks of the request.
+    TooDeep,
+    /// A synced block that duplicates another synced block, but has children of its own.
+    SyncedDuplicateWithChildren,
+}
+
+impl fmt::Display for BlockTreeProblem {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.write_str(match self {
+            BlockTreeProblem::TableRowOutsideTable => "table rows can only be children of a table",
+            BlockTreeProblem::ColumnOutsideColumnList => {
+                "columns can only be children of a column list"
+            }
+            BlockTreeProblem::TooDeep => {
+                "blocks can only be nested two levels deep in a single request"
+            }
+            BlockTreeProblem::SyncedDuplicateWithChildren => {
+                "synced blocks that duplicate another block can't have children"
+            }
+        })
+    }
+}
+
+/// A block of a tree being created breaks one of Notion's structural rules.
+#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
+#[error("{} at {}", problem, path_display(path))]
+pub struct BlockTreeError {
+    /// Indexes of the offending block, starting with its index in the request's children.
+    pub path: Vec<usize>,
+    pub problem: BlockTreeProblem,
+}
+
+fn path_display(path: &[usize]) -> String {
+    path.iter()
+        .map(|index| format!("children[{}]", index))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// The parts of a block the rules look at.
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum Kind {
+    Table,
+    TableRow,
+    ColumnList,
+    Column,
+    SyncedDuplicate,
+    Other,
+}
+
+impl From<&CreateBlock> for Kind {
+    fn from(block: &CreateBlock) -> Self {
+        match block {
+            CreateBlock::Table { .. } => Kind::Table,
+            CreateBlock::TableRow { .. } => Kind::TableRow,
+            CreateBlock::ColumnList { .. } => Kind::ColumnList,
+            CreateBlock::Column { .. } => Kind::Column,
+            CreateBlock::SyncedBlock { synced_block } if synced_block.synced_from.is_some() => {
+                Kind::SyncedDuplicate
+            }
+            _ => Kind::Other,
+        }
+    }
+}
+
+/// Checks blocks about to be created against the structural rules Notion enforces,
+/// reporting the first block that breaks one.
+///
+/// The parent of `children` isn't known, so table rows and columns are allowed at
+/// the top level, for appending to an existing table or column list.
+pub fn validate_children(children: &[CreateBlock]) -> Result<(), BlockTreeError> {
+    let mut path = vec![];
+    for (index, block) in children.iter().enumerate() {
+        path.push(index);
+        check(&mut path, None, block.into(), block.children())?;
+        path.pop();
+    }
+    Ok(())
+}
+
+fn check(
+    path: &mut Vec<usize>,
+    parent: Option<Kind>,
+    kind: Kind,
+    children: &[CreateBlock],
+) -> Result<(), BlockTreeError> {
+    let error = |problem| {
+        Err(BlockTreeError {
+            path: path.clone(),
+            problem,
+        })
+    };
+
+    match (parent, kind) {
+        (Some(parent), Kind::TableRow) if parent != Kind::Table => {
+            return error(BlockTreeProblem::TableRowOutsideTable)
+        }
+        (Some(parent), Kind::Column) if parent != Kind::ColumnList => {
+            return error(BlockTreeProblem::ColumnOutsideColumnList)
+        }
+        _ => {}
+    }
+    if path.len() > MAX_NESTING + 1 {
+        return error(BlockTreeProblem::TooDeep);
+    }
+    if kind == Kind::SyncedDuplicate && !children.is_empty() {
+        return error(BlockTreeProblem::SyncedDuplicateWithChildren);
+    }
+
+    for (index, child) in children.iter().enumerate() {
+        path.push(index);
+        check(path, Some(kind), child.into(), child.children())?;
+        path.pop();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::block::{validate_children, Block, BlockTreeProblem, CreateBlock};
+    use serde_json::{json, Value};
+
+    fn block(
+        kind: &str,
+        fields: Value,
+    ) -> Block {
+        let user = json!({"object": "user", "id": "6419f912-5293-4ea8-b2c8-9c3ce44f90e3"});
+        serde_json::from_value(json!({
+            "object": "block",
+            "id": "9e891834-6a03-475c-a2b8-421e17f0f3aa",
+            "created_time": "2022-05-12T21:15:00.000Z",
+            "last_edited_time": "2022-05-12T22:10:00.000Z",
+            "created_by": user,
+            "last_edited_by": user,
+            "has_children": false,
+            "type": kind,
+            kind: fields,
+        }))
+        .unwrap()
+    }
+
+    fn paragraph(children: Vec<Block>) -> Value {
+        json!({"rich_text": [], "color": "default", "children": children})
+    }
+
+    fn create(block: Block) -> CreateBlock {
+        block.into()
+    }
+
+    #[test]
+    fn accepts_valid_trees() {
+        let row = block("table_row", json!({"cells": []}));
+        let table = block(
+            "table",
+            json!({"table_width": 1, "has_column_header": false, "has_row_header": false, "children": [row.clone()]}),
+        );
+        let column = block(
+            "column",
+            json!({"children": [block("paragraph", paragraph(vec![]))]}),
+        );
+        let columns = block("column_list", json!({"children": [column.clone()]}));
+
+        assert_eq!(
+            validate_children(&[create(table), create(columns), create(row), create(column)]),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn points_at_the_offending_block() {
+        let row = block("table_row", json!({"cells": []}));
+        let nested_row = create(block(
+            "paragraph",
+            paragraph(vec![block("paragraph", paragraph(vec![])), row]),
+        ));
+
+        let error =
+            validate_children(&[create(block("divider", json!({}))), nested_row]).unwrap_err();
+
+        assert_eq!(error.path, vec![1, 1]);
+        assert_eq!(error.problem, BlockTreeProblem::TableRowOutsideTable);
+        assert_eq!(
+            error.to_string(),
+            "table rows can only be children of a table at children[1].children[1]"
+        );
+    }
+
+    #[test]
+    fn rejects_deep_nesting_and_synced_duplicates_with_children() {
+        let deep = block("paragraph", paragraph(vec![]));
+        let deep = block("paragraph", paragraph(vec![deep]));
+        let deep = block("paragraph", paragraph(vec![deep]));
+        let deep = create(block("paragraph", paragraph(vec![deep])));
+        let error = validate_children(&[deep]).unwrap_err();
+        assert_eq!(error.path, vec![0, 0, 0, 0]);
+        assert_eq!(error.problem, BlockTreeProblem::TooDeep);
+
+        let synced = create(block(
+            "synced_block",
+            json!({
+                "synced_from": {"block_id": "c2a7b6d0-4d8e-4f1a-9b3c-5e6f7a8b9c0d"},
+                "children": [block("paragraph", paragraph(vec![]))],
+            }),
+        ));
+        assert_eq!(
+            validate_children(&[synced]).unwrap_err().problem,
+            BlockTreeProblem::SyncedDuplicateWithChildren
+        );
+    }
+}
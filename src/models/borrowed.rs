@@ -0,0 +1,95 @@
+//! Borrowed views of query and search responses, for reading large crawls
+//! without copying every string out of the response.
+//!
+//! Ids, cursors and property names borrow from the JSON text when they contain no
+//! escapes, and property values are kept as raw JSON until they're asked for, so
+//! properties that are never read are never parsed.
+//!
+//! ```
+//! use notion::models::borrowed::{ListResponse, Page};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let json = r#"{"results": [{
+//!     "id": "b55c9c91-384d-452b-81db-d1ef79372b75",
+//!     "created_time": "2021-05-13T21:21:00.000Z",
+//!     "last_edited_time": "2021-05-13T21:21:00.000Z",
+//!     "archived": false,
+//!     "properties": {"Name": {"id": "title", "type": "title", "title": []}}
+//! }], "next_cursor": null, "has_more": false}"#;
+//!
+//! let pages: ListResponse<Page> = serde_json::from_str(json)?;
+//! assert_eq!(pages.results[0].title()?, Some(String::new()));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::models::properties::PropertyValue;
+use crate::models::{DateTime, Utc};
+use crate::Error;
+use serde::Deserialize;
+use serde_json::value::RawValue;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// <https://developers.notion.com/reference/pagination#responses>
+#[derive(Deserialize, Debug, Clone)]
+pub struct ListResponse<'a, T> {
+    pub results: Vec<T>,
+    #[serde(borrow)]
+    pub next_cursor: Option<Cow<'a, str>>,
+    pub has_more: bool,
+}
+
+/// A page whose properties are parsed on demand.
+/// Deserialize the response as [Page](crate::models::Page) instead when everything is needed.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Page<'a> {
+    #[serde(borrow)]
+    pub id: Cow<'a, str>,
+    pub created_time: DateTime<Utc>,
+    pub last_edited_time: DateTime<Utc>,
+    pub archived: bool,
+    /// Property values keyed by name, as they appear in the response.
+    #[serde(borrow)]
+    pub properties: HashMap<Cow<'a, str>, &'a RawValue>,
+}
+
+/// Just enough of a property value to tell its type.
+#[derive(Deserialize)]
+struct PropertyType<'a> {
+    #[serde(rename = "type", borrow)]
+    kind: Cow<'a, str>,
+}
+
+impl<'a> Page<'a> {
+    /// Parses the value of the property called `name`, if the page has it.
+    pub fn property(
+        &self,
+        name: &str,
+    ) -> Result<Option<PropertyValue>, Error> {
+        self.properties
+            .get(name)
+            .map(|value| parse(value.get()))
+            .transpose()
+    }
+
+    /// The plain text of the title property, parsing only that property.
+    pub fn title(&self) -> Result<Option<String>, Error> {
+        for value in self.properties.values() {
+            let property: PropertyType = parse(value.get())?;
+            if property.kind == "title" {
+                return match parse(value.get())? {
+                    PropertyValue::Title { title, .. } => {
+                        Ok(Some(title.iter().map(|t| t.plain_text()).collect()))
+                    }
+                    _ => Ok(None),
+                };
+            }
+        }
+        Ok(None)
+    }
+}
+
+fn parse<'a, T: Deserialize<'a>>(json: &'a str) -> Result<T, Error> {
+    serde_json::from_str(json).map_err(|source| Error::JsonParseError { source })
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
+use std::time::Duration;
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Debug, Clone, Hash)]
 #[serde(transparent)]
@@ -9,6 +10,10 @@ impl StatusCode {
     pub fn code(&self) -> u16 {
         self.0
     }
+
+    pub fn is_server_error(&self) -> bool {
+        (500..600).contains(&self.0)
+    }
 }
 
 impl Display for StatusCode {
@@ -26,26 +31,103 @@ pub struct ErrorResponse {
     pub status: StatusCode,
     pub code: ErrorCode,
     pub message: String,
+    /// Identifies the request when reporting a problem to Notion.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// How long Notion asked to wait before retrying, from the `Retry-After` header.
+    #[serde(skip)]
+    pub retry_after: Option<Duration>,
+}
+
+impl ErrorResponse {
+    /// Whether the request may succeed when sent again: it was rate limited or failed
+    /// with a server error, which are the responses a [RetryPolicy](crate::RetryPolicy) retries.
+    pub fn is_retryable(&self) -> bool {
+        self.code == ErrorCode::RateLimited || self.status.is_server_error()
+    }
 }
 
+/// The codes Notion gives errors.
+/// Codes that aren't documented yet are kept as [Unknown](ErrorCode::Unknown).
 /// <https://developers.notion.com/reference/errors>
-#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
-#[serde(rename_all = "snake_case")]
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Hash)]
+#[serde(from = "String", into = "String")]
 pub enum ErrorCode {
     InvalidJson,
     InvalidRequestUrl,
     InvalidRequest,
+    InvalidGrant,
     ValidationError,
-    MissionVersion,
+    MissingVersion,
     Unauthorized,
     RestrictedResource,
     ObjectNotFound,
     ConflictError,
     RateLimited,
     InternalServerError,
+    BadGateway,
     ServiceUnavailable,
-    #[serde(other)] // serde issue #912
-    Unknown,
+    DatabaseConnectionUnavailable,
+    GatewayTimeout,
+    Unknown(String),
+}
+
+impl ErrorCode {
+    /// The code as Notion writes it, e.g. `validation_error`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            ErrorCode::InvalidJson => "invalid_json",
+            ErrorCode::InvalidRequestUrl => "invalid_request_url",
+            ErrorCode::InvalidRequest => "invalid_request",
+            ErrorCode::InvalidGrant => "invalid_grant",
+            ErrorCode::ValidationError => "validation_error",
+            ErrorCode::MissingVersion => "missing_version",
+            ErrorCode::Unauthorized => "unauthorized",
+            ErrorCode::RestrictedResource => "restricted_resource",
+            ErrorCode::ObjectNotFound => "object_not_found",
+            ErrorCode::ConflictError => "conflict_error",
+            ErrorCode::RateLimited => "rate_limited",
+            ErrorCode::InternalServerError => "internal_server_error",
+            ErrorCode::BadGateway => "bad_gateway",
+            ErrorCode::ServiceUnavailable => "service_unavailable",
+            ErrorCode::DatabaseConnectionUnavailable => "database_connection_unavailable",
+            ErrorCode::GatewayTimeout => "gateway_timeout",
+            ErrorCode::Unknown(code) => code,
+        }
+    }
+}
+
+impl From<String> for ErrorCode {
+    fn from(code: String) -> Self {
+        match code.as_str() {
+            "invalid_json" => ErrorCode::InvalidJson,
+            "invalid_request_url" => ErrorCode::InvalidRequestUrl,
+            "invalid_request" => ErrorCode::InvalidRequest,
+            "invalid_grant" => ErrorCode::InvalidGrant,
+            "validation_error" => ErrorCode::ValidationError,
+            "missing_version" => ErrorCode::MissingVersion,
+            "unauthorized" => ErrorCode::Unauthorized,
+            "restricted_resource" => ErrorCode::RestrictedResource,
+            "object_not_found" => ErrorCode::ObjectNotFound,
+            "conflict_error" => ErrorCode::ConflictError,
+            "rate_limited" => ErrorCode::RateLimited,
+            "internal_server_error" => ErrorCode::InternalServerError,
+            "bad_gateway" => ErrorCode::BadGateway,
+            "service_unavailable" => ErrorCode::ServiceUnavailable,
+            "database_connection_unavailable" => ErrorCode::DatabaseConnectionUnavailable,
+            "gateway_timeout" => ErrorCode::GatewayTimeout,
+            _ => ErrorCode::Unknown(code),
+        }
+    }
+}
+
+impl From<ErrorCode> for String {
+    fn from(code: ErrorCode) -> Self {
+        match code {
+            ErrorCode::Unknown(code) => code,
+            code => code.as_str().to_string(),
+        }
+    }
 }
 
 impl Display for ErrorCode {
@@ -53,7 +135,7 @@ impl Display for ErrorCode {
         &self,
         f: &mut Formatter<'_>,
     ) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        f.write_str(self.as_str())
     }
 }
 
@@ -64,13 +146,33 @@ mod tests {
     #[test]
     fn deserialize_error() {
         let error: ErrorResponse = serde_json::from_str(include_str!("tests/error.json")).unwrap();
-        assert_eq!(error.code, ErrorCode::ValidationError)
+        assert_eq!(error.code, ErrorCode::ValidationError);
+        assert_eq!(
+            error.request_id.as_deref(),
+            Some("a0cbe6a3-9d4c-4e0a-a1cb-2c6f0a7e7b61")
+        );
+        assert!(!error.is_retryable());
     }
 
     #[test]
     fn deserialize_unknown_error() {
         let error: ErrorResponse =
             serde_json::from_str(include_str!("tests/unknown_error.json")).unwrap();
-        assert_eq!(error.code, ErrorCode::Unknown)
+        assert_eq!(error.code, ErrorCode::Unknown("asadfsdfasd".to_string()));
+        assert_eq!(serde_json::to_value(&error).unwrap()["code"], "asadfsdfasd");
+    }
+
+    #[test]
+    fn error_codes_round_trip() {
+        for code in [
+            ErrorCode::MissingVersion,
+            ErrorCode::DatabaseConnectionUnavailable,
+            ErrorCode::GatewayTimeout,
+        ] {
+            let json = serde_json::to_value(&code).unwrap();
+            assert_eq!(json, code.as_str());
+            assert_eq!(serde_json::from_value::<ErrorCode>(json).unwrap(), code);
+        }
+        assert_eq!(ErrorCode::GatewayTimeout.to_string(), "gateway_timeout");
     }
 }
@@ -0,0 +1,320 @@
+//! A shorthand for building database query filters.
+//!
+//! ```
+//! use notion::chrono::NaiveDate;
+//! use notion::models::filter;
+//! use notion::models::search::DatabaseQuery;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let today = NaiveDate::from_ymd_opt(2022, 6, 1).unwrap();
+//! let filter = filter::property("Status")
+//!     .select_equals("Done")
+//!     .and(filter::property("Due").date_before(today))
+//!     .or(filter::property("Pinned").checkbox(true))
+//!     .build()?;
+//!
+//! let query = DatabaseQuery {
+//!     filter: Some(filter),
+//!     ..Default::default()
+//! };
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::ids::{PageId, UserId};
+use crate::models::properties::DateOrDateTime;
+use crate::models::search::{
+    CheckboxCondition, DateCondition, FilterCondition, MultiSelectCondition, NumberCondition,
+    PeopleCondition, PropertyCondition, RelationCondition, SelectCondition, TextCondition,
+    TimestampCondition,
+};
+use crate::models::Number;
+
+/// How many compound filters Notion allows below the top level one.
+const MAX_NESTING: usize = 2;
+
+/// Compound filters are nested deeper than Notion allows.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, thiserror::Error)]
+#[error(
+    "Compound filters can only be nested two levels deep, found {} levels",
+    nesting
+)]
+pub struct FilterTooDeep {
+    /// How many levels of compound filters are below the top level one.
+    pub nesting: usize,
+}
+
+/// Starts a filter on the property called `name`.
+pub fn property<S: Into<String>>(name: S) -> PropertyFilter {
+    PropertyFilter {
+        property: name.into(),
+    }
+}
+
+/// Filters on when pages were created.
+pub fn created_time(condition: DateCondition) -> Filter {
+    Filter(FilterCondition::Timestamp {
+        condition: TimestampCondition::CreatedTime {
+            created_time: condition,
+        },
+    })
+}
+
+/// Filters on when pages were last edited.
+pub fn last_edited_time(condition: DateCondition) -> Filter {
+    Filter(FilterCondition::Timestamp {
+        condition: TimestampCondition::LastEditedTime {
+            last_edited_time: condition,
+        },
+    })
+}
+
+/// A property to filter on, waiting for its condition.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PropertyFilter {
+    property: String,
+}
+
+impl PropertyFilter {
+    /// Filters on any condition of the property.
+    pub fn condition(
+        self,
+        condition: PropertyCondition,
+    ) -> Filter {
+        Filter(FilterCondition::Property {
+            property: self.property,
+            condition,
+        })
+    }
+
+    pub fn title_equals<S: Into<String>>(
+        self,
+        value: S,
+    ) -> Filter {
+        self.condition(PropertyCondition::Title(TextCondition::Equals(
+            value.into(),
+        )))
+    }
+
+    pub fn title_contains<S: Into<String>>(
+        self,
+        value: S,
+    ) -> Filter {
+        self.condition(PropertyCondition::Title(TextCondition::Contains(
+            value.into(),
+        )))
+    }
+
+    pub fn text_equals<S: Into<String>>(
+        self,
+        value: S,
+    ) -> Filter {
+        self.condition(PropertyCondition::RichText(TextCondition::Equals(
+            value.into(),
+        )))
+    }
+
+    pub fn text_contains<S: Into<String>>(
+        self,
+        value: S,
+    ) -> Filter {
+        self.condition(PropertyCondition::RichText(TextCondition::Contains(
+            value.into(),
+        )))
+    }
+
+    pub fn number_equals<N: Into<Number>>(
+        self,
+        value: N,
+    ) -> Filter {
+        self.condition(PropertyCondition::Number(NumberCondition::Equals(
+            value.into(),
+        )))
+    }
+
+    pub fn number_greater_than<N: Into<Number>>(
+        self,
+        value: N,
+    ) -> Filter {
+        self.condition(PropertyCondition::Number(NumberCondition::GreaterThan(
+            value.into(),
+        )))
+    }
+
+    pub fn number_less_than<N: Into<Number>>(
+        self,
+        value: N,
+    ) -> Filter {
+        self.condition(PropertyCondition::Number(NumberCondition::LessThan(
+            value.into(),
+        )))
+    }
+
+    pub fn checkbox(
+        self,
+        checked: bool,
+    ) -> Filter {
+        self.condition(PropertyCondition::Checkbox(CheckboxCondition::Equals(
+            checked,
+        )))
+    }
+
+    pub fn select_equals<S: Into<String>>(
+        self,
+        option: S,
+    ) -> Filter {
+        self.condition(PropertyCondition::Select(SelectCondition::Equals(
+            option.into(),
+        )))
+    }
+
+    pub fn multi_select_contains<S: Into<String>>(
+        self,
+        option: S,
+    ) -> Filter {
+        self.condition(PropertyCondition::MultiSelect(
+            MultiSelectCondition::Contains(option.into()),
+        ))
+    }
+
+    pub fn date_before<D: Into<DateOrDateTime>>(
+        self,
+        date: D,
+    ) -> Filter {
+        self.condition(PropertyCondition::Date(DateCondition::Before(date.into())))
+    }
+
+    pub fn date_after<D: Into<DateOrDateTime>>(
+        self,
+        date: D,
+    ) -> Filter {
+        self.condition(PropertyCondition::Date(DateCondition::After(date.into())))
+    }
+
+    pub fn people_contains(
+        self,
+        user: UserId,
+    ) -> Filter {
+        self.condition(PropertyCondition::People(PeopleCondition::Contains(user)))
+    }
+
+    pub fn relation_contains(
+        self,
+        page: PageId,
+    ) -> Filter {
+        self.condition(PropertyCondition::Relation(RelationCondition::Contains(
+            page,
+        )))
+    }
+}
+
+/// A filter that can be combined with others before it's [built](Filter::build).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Filter(FilterCondition);
+
+impl Filter {
+    /// Matches pages matching both this filter and `other`.
+    ///
+    /// Chained `and`s add to the same compound filter rather than nesting.
+    pub fn and(
+        self,
+        other: Filter,
+    ) -> Self {
+        match self.0 {
+            FilterCondition::And { mut and } => {
+                and.push(other.0);
+                Filter(FilterCondition::And { and })
+            }
+            condition => Filter(FilterCondition::And {
+                and: vec![condition, other.0],
+            }),
+        }
+    }
+
+    /// Matches pages matching either this filter or `other`.
+    ///
+    /// Chained `or`s add to the same compound filter rather than nesting.
+    pub fn or(
+        self,
+        other: Filter,
+    ) -> Self {
+        match self.0 {
+            FilterCondition::Or { mut or } => {
+                or.push(other.0);
+                Filter(FilterCondition::Or { or })
+            }
+            condition => Filter(FilterCondition::Or {
+                or: vec![condition, other.0],
+            }),
+        }
+    }
+
+    /// The filter condition, as long as its compound filters aren't nested too deep.
+    pub fn build(self) -> Result<FilterCondition, FilterTooDeep> {
+        match compound_depth(&self.0) {
+            depth if depth > MAX_NESTING + 1 => Err(FilterTooDeep { nesting: depth - 1 }),
+            _ => Ok(self.0),
+        }
+    }
+}
+
+impl From<FilterCondition> for Filter {
+    fn from(condition: FilterCondition) -> Self {
+        Filter(condition)
+    }
+}
+
+/// How many compound filters deep the condition goes.
+fn compound_depth(condition: &FilterCondition) -> usize {
+    match condition {
+        FilterCondition::And { and: conditions } | FilterCondition::Or { or: conditions } => {
+            1 + conditions.iter().map(compound_depth).max().unwrap_or(0)
+        }
+        FilterCondition::Property { .. } | FilterCondition::Timestamp { .. } => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::filter::{self, FilterTooDeep};
+    use crate::models::search::DateCondition;
+    use serde_json::json;
+
+    #[test]
+    fn builds_compound_filters() -> Result<(), Box<dyn std::error::Error>> {
+        let filter = filter::property("Status")
+            .select_equals("Done")
+            .and(filter::property("Price").number_less_than(5))
+            .and(filter::last_edited_time(DateCondition::PastWeek))
+            .or(filter::property("Tags").multi_select_contains("Pinned"))
+            .build()?;
+
+        assert_eq!(
+            serde_json::to_value(&filter)?,
+            json!({"or": [
+                {"and": [
+                    {"property": "Status", "select": {"equals": "Done"}},
+                    {"property": "Price", "number": {"less_than": 5}},
+                    {"timestamp": "last_edited_time", "last_edited_time": {"past_week": {}}},
+                ]},
+                {"property": "Tags", "multi_select": {"contains": "Pinned"}},
+            ]})
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn limits_nesting() {
+        let leaf = || filter::property("Done").checkbox(true);
+        // or > and > or: two levels below the top one.
+        let nested = leaf()
+            .or(leaf())
+            .and(leaf())
+            .or(leaf().and(leaf()).or(leaf()));
+        assert!(nested.clone().build().is_ok());
+
+        let too_deep = leaf().and(nested);
+        assert_eq!(too_deep.build(), Err(FilterTooDeep { nesting: 3 }));
+    }
+}
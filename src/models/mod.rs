@@ -1,36 +1,46 @@
+//! The objects the Notion API reads and writes.
+//!
+//! Read models, like [Page] and [Block](block::Block), deserialize everything Notion responds
+//! with, and serialize back to JSON they can be read from again, e.g. for caching.
+//! Write models, like [PageCreateRequest] and [CreateBlock](block::CreateBlock), serialize to
+//! exactly the request bodies Notion documents, without the ids, timestamps and other fields
+//! Notion computes itself. The one exception is the `plain_text` of rich text,
+//! which Notion ignores in requests.
+//! The tests compare write models to request bodies in `src/models/tests`.
+
 pub mod block;
+pub mod borrowed;
 pub mod error;
+pub mod filter;
 pub mod paging;
 pub mod properties;
 pub mod search;
 #[cfg(test)]
 mod tests;
 pub mod text;
+pub mod unfurl;
 pub mod users;
 
-use crate::models::properties::{PropertyConfiguration, PropertyValue};
+use crate::models::properties::{
+    CreatePropertyConfiguration, CreatePropertyValue, CreateSelectedValue, DateOrDateTime,
+    DateValue, PropertyConfiguration, PropertyItem, PropertyValue, RelationValue,
+};
 use crate::models::text::RichText;
 use crate::Error;
 use block::ExternalFileObject;
-use serde::{Deserialize, Serialize};
-use serde_json::Value;
-use std::collections::HashMap;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
 
-use crate::ids::{AsIdentifier, DatabaseId, PageId};
-use crate::models::block::{Block, CreateBlock, FileObject};
+use crate::ids::{AsIdentifier, BlockId, DatabaseId, PageId, UserId};
+use crate::models::block::{Block, CreateBlock, CustomEmoji, FileObject};
 use crate::models::error::ErrorResponse;
 use crate::models::paging::PagingCursor;
-use crate::models::users::User;
+use crate::models::users::{User, UserCommon};
 pub use chrono::{DateTime, Utc};
 pub use serde_json::value::Number;
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Copy, Clone)]
-#[serde(rename_all = "snake_case")]
-enum ObjectType {
-    Database,
-    List,
-}
-
 /// Represents a Notion Database
 /// See <https://developers.notion.com/reference/database>
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
@@ -41,8 +51,19 @@ pub struct Database {
     pub created_time: DateTime<Utc>,
     /// Date and time when this database was updated.
     pub last_edited_time: DateTime<Utc>,
+    /// User who created the database.
+    pub created_by: Option<UserCommon>,
+    /// User who last edited the database.
+    pub last_edited_by: Option<UserCommon>,
     /// Name of the database as it appears in Notion.
     pub title: Vec<RichText>,
+    /// Description of the database as it appears in Notion.
+    #[serde(default)]
+    pub description: Vec<RichText>,
+    pub icon: Option<IconObject>,
+    pub cover: Option<FileObject>,
+    /// The page, block or workspace the database is in.
+    pub parent: Parent,
     /// Schema of properties for the database as they appear in Notion.
     //
     // key string
@@ -50,8 +71,18 @@ pub struct Database {
     //
     // value object
     // A Property object.
-    pub icon: Option<IconObject>,
+    #[serde(serialize_with = "serialize_sorted")]
     pub properties: HashMap<String, PropertyConfiguration>,
+    /// Whether the database is shown inline in its parent page, rather than as a full page.
+    #[serde(default)]
+    pub is_inline: bool,
+    /// The archived status of the database.
+    #[serde(default)]
+    pub archived: bool,
+    /// The URL of the database in Notion.
+    pub url: Option<String>,
+    /// The URL of the database on the web, if it's been published.
+    pub public_url: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
@@ -68,6 +99,19 @@ pub enum IconObject {
     Emoji {
         emoji: String,
     },
+    CustomEmoji {
+        custom_emoji: CustomEmoji,
+    },
+}
+
+impl IconObject {
+    /// The emoji, when the icon is one.
+    pub fn emoji(&self) -> Option<&str> {
+        match self {
+            IconObject::Emoji { emoji } => Some(emoji),
+            _ => None,
+        }
+    }
 }
 
 impl AsIdentifier<DatabaseId> for Database {
@@ -83,6 +127,21 @@ impl Database {
             .flat_map(|rich_text| rich_text.plain_text().chars())
             .collect()
     }
+
+    /// The title prefixed with the icon emoji, if the icon is an emoji, e.g. `📝 Tasks`.
+    pub fn display_title(&self) -> String {
+        display_title(self.icon.as_ref(), self.title_plain_text())
+    }
+}
+
+fn display_title(
+    icon: Option<&IconObject>,
+    title: String,
+) -> String {
+    match icon.and_then(IconObject::emoji) {
+        Some(emoji) => format!("{} {}", emoji, title),
+        None => title,
+    }
 }
 
 /// <https://developers.notion.com/reference/pagination#responses>
@@ -97,6 +156,53 @@ impl<T> ListResponse<T> {
     pub fn results(&self) -> &[T] {
         &self.results
     }
+
+    /// Transforms each result, keeping the paging information.
+    pub fn map<U, F: FnMut(T) -> U>(
+        self,
+        f: F,
+    ) -> ListResponse<U> {
+        ListResponse {
+            results: self.results.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+            has_more: self.has_more,
+        }
+    }
+
+    /// Transforms each result, keeping the paging information,
+    /// or fails with the first error.
+    pub fn try_map<U, E, F: FnMut(T) -> Result<U, E>>(
+        self,
+        f: F,
+    ) -> Result<ListResponse<U>, E> {
+        Ok(ListResponse {
+            results: self.results.into_iter().map(f).collect::<Result<_, _>>()?,
+            next_cursor: self.next_cursor,
+            has_more: self.has_more,
+        })
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.results.iter()
+    }
+}
+
+impl<T> IntoIterator for ListResponse<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.results.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ListResponse<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.results.iter()
+    }
 }
 
 impl ListResponse<Object> {
@@ -117,54 +223,49 @@ impl ListResponse<Object> {
         }
     }
 
-    pub(crate) fn expect_databases(self) -> Result<ListResponse<Database>, crate::Error> {
-        let databases: Result<Vec<_>, _> = self
-            .results
-            .into_iter()
-            .map(|object| match object {
-                Object::Database { database } => Ok(database),
-                response => Err(Error::UnexpectedResponse { response }),
-            })
-            .collect();
-
-        Ok(ListResponse {
-            results: databases?,
-            has_more: self.has_more,
-            next_cursor: self.next_cursor,
+    /// The databases of the list, failing with [UnexpectedResponse](Error::UnexpectedResponse)
+    /// on any other object.
+    pub fn into_databases(self) -> Result<ListResponse<Database>, Error> {
+        self.try_map(|object| match object {
+            Object::Database { database } => Ok(database),
+            response => Err(Error::UnexpectedResponse { response }),
         })
     }
 
-    pub(crate) fn expect_pages(self) -> Result<ListResponse<Page>, crate::Error> {
-        let items: Result<Vec<_>, _> = self
-            .results
-            .into_iter()
-            .map(|object| match object {
-                Object::Page { page } => Ok(page),
-                response => Err(Error::UnexpectedResponse { response }),
-            })
-            .collect();
+    /// The pages of the list, failing with [UnexpectedResponse](Error::UnexpectedResponse)
+    /// on any other object.
+    pub fn into_pages(self) -> Result<ListResponse<Page>, Error> {
+        self.try_map(|object| match object {
+            Object::Page { page } => Ok(page),
+            response => Err(Error::UnexpectedResponse { response }),
+        })
+    }
 
-        Ok(ListResponse {
-            results: items?,
-            has_more: self.has_more,
-            next_cursor: self.next_cursor,
+    /// The pages and databases of the list, failing with
+    /// [UnexpectedResponse](Error::UnexpectedResponse) on any other object.
+    pub fn into_search_results(self) -> Result<ListResponse<SearchResult>, Error> {
+        self.try_map(|object| match object {
+            Object::Page { page } => Ok(SearchResult::Page { page }),
+            Object::Database { database } => Ok(SearchResult::Database { database }),
+            response => Err(Error::UnexpectedResponse { response }),
         })
     }
 
-    pub(crate) fn expect_blocks(self) -> Result<ListResponse<Block>, crate::Error> {
-        let items: Result<Vec<_>, _> = self
-            .results
-            .into_iter()
-            .map(|object| match object {
-                Object::Block { block } => Ok(block),
-                response => Err(Error::UnexpectedResponse { response }),
-            })
-            .collect();
+    /// The property items of the list, failing with
+    /// [UnexpectedResponse](Error::UnexpectedResponse) on any other object.
+    pub fn into_property_items(self) -> Result<ListResponse<PropertyItem>, Error> {
+        self.try_map(|object| match object {
+            Object::PropertyItem { item } => Ok(item),
+            response => Err(Error::UnexpectedResponse { response }),
+        })
+    }
 
-        Ok(ListResponse {
-            results: items?,
-            has_more: self.has_more,
-            next_cursor: self.next_cursor,
+    /// The blocks of the list, failing with [UnexpectedResponse](Error::UnexpectedResponse)
+    /// on any other object.
+    pub fn into_blocks(self) -> Result<ListResponse<Block>, Error> {
+        self.try_map(|object| match object {
+            Object::Block { block } => Ok(block),
+            response => Err(Error::UnexpectedResponse { response }),
         })
     }
 }
@@ -181,15 +282,33 @@ pub enum Parent {
     Page {
         page_id: PageId,
     },
+    /// Blocks, and pages nested in blocks like toggles and columns, can have a block parent.
+    #[serde(rename = "block_id")]
+    Block {
+        block_id: BlockId,
+    },
     Workspace,
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 pub struct Properties {
-    #[serde(flatten)]
+    #[serde(flatten, serialize_with = "serialize_sorted")]
     pub properties: HashMap<String, PropertyValue>,
 }
 
+/// Serializes a map keyed by property name in name order,
+/// so serialized pages and databases can be compared byte for byte.
+fn serialize_sorted<V, S>(
+    map: &HashMap<String, V>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    V: Serialize,
+    S: Serializer,
+{
+    serializer.collect_map(map.iter().collect::<BTreeMap<_, _>>())
+}
+
 impl Properties {
     pub fn title(&self) -> Option<String> {
         self.properties.values().find_map(|p| match p {
@@ -199,14 +318,488 @@ impl Properties {
             _ => None,
         })
     }
+
+    /// The plain text of a title or rich text property.
+    pub fn get_text(
+        &self,
+        name: &str,
+    ) -> Option<String> {
+        match self.properties.get(name)? {
+            PropertyValue::Title { title: text, .. }
+            | PropertyValue::Text {
+                rich_text: text, ..
+            } => Some(text::plain_text(text)),
+            _ => None,
+        }
+    }
+
+    pub fn get_number(
+        &self,
+        name: &str,
+    ) -> Option<&Number> {
+        match self.properties.get(name)? {
+            PropertyValue::Number { number, .. } => number.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn get_date(
+        &self,
+        name: &str,
+    ) -> Option<&DateValue> {
+        match self.properties.get(name)? {
+            PropertyValue::Date { date, .. } => date.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// The name of the option selected in a select or status property.
+    pub fn get_select(
+        &self,
+        name: &str,
+    ) -> Option<&str> {
+        match self.properties.get(name)? {
+            PropertyValue::Select { select: option, .. }
+            | PropertyValue::Status { status: option, .. } => option.as_ref()?.name.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// The ids of the pages a relation property links to.
+    pub fn get_relation_ids(
+        &self,
+        name: &str,
+    ) -> Option<Vec<&PageId>> {
+        match self.properties.get(name)? {
+            PropertyValue::Relation { relation, .. } => Some(
+                relation
+                    .iter()
+                    .flatten()
+                    .map(|relation| &relation.id)
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+
+    pub fn get_checkbox(
+        &self,
+        name: &str,
+    ) -> Option<bool> {
+        match self.properties.get(name)? {
+            PropertyValue::Checkbox { checkbox, .. } => Some(*checkbox),
+            _ => None,
+        }
+    }
+
+    /// The [plain values](PropertyValue::plain_value()) of the properties, keyed by name.
+    pub fn plain_values(&self) -> serde_json::Map<String, serde_json::Value> {
+        self.properties
+            .iter()
+            .map(|(name, value)| (name.clone(), value.plain_value()))
+            .collect()
+    }
+
+    /// Deserializes the [plain values](PropertyValue::plain_value()) of the properties
+    /// into a type of the app's own, with a field per property.
+    ///
+    /// ```
+    /// # use notion::models::Properties;
+    /// use chrono::NaiveDate;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Task {
+    ///     #[serde(rename = "Name")]
+    ///     name: String,
+    ///     #[serde(rename = "Done")]
+    ///     done: bool,
+    ///     #[serde(rename = "Due")]
+    ///     due: Option<NaiveDate>,
+    /// }
+    ///
+    /// # let properties: Properties = serde_json::from_value(serde_json::json!({
+    /// #     "Name": {"id": "title", "type": "title", "title": [{
+    /// #         "type": "text", "text": {"content": "Ship it"}, "plain_text": "Ship it",
+    /// #         "annotations": {"bold": false, "italic": false, "strikethrough": false,
+    /// #             "underline": false, "code": false, "color": "default"}
+    /// #     }]},
+    /// #     "Done": {"id": "a", "type": "checkbox", "checkbox": false},
+    /// #     "Due": {"id": "b", "type": "date", "date": {"start": "2021-05-01"}},
+    /// # }))?;
+    /// let task: Task = properties.deserialize_into()?;
+    /// assert_eq!(task.name, "Ship it");
+    /// assert!(!task.done);
+    /// assert_eq!(task.due, NaiveDate::from_ymd_opt(2021, 5, 1));
+    /// # Ok::<(), serde_json::Error>(())
+    /// ```
+    pub fn deserialize_into<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_value(serde_json::Value::Object(self.plain_values()))
+    }
 }
 
 #[derive(Serialize, Debug, Eq, PartialEq)]
 pub struct PageCreateRequest {
     pub parent: Parent,
-    pub properties: Properties,
+    /// Property values keyed by name.
+    #[serde(serialize_with = "serialize_sorted")]
+    pub properties: HashMap<String, CreatePropertyValue>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub children: Option<Vec<CreateBlock>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<IconObject>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cover: Option<FileObject>,
+}
+
+/// Builds a [PageCreateRequest] one property at a time.
+///
+/// Properties are keyed by name, and replace any earlier value for the same name.
+///
+/// ```
+/// # use notion::ids::DatabaseId;
+/// # use notion::models::PageCreateRequestBuilder;
+/// # use notion::chrono::NaiveDate;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let database: DatabaseId = "b1a8c3e0-7d2f-4c5b-9e1a-0f6d3c2b1a00".parse()?;
+/// let request = PageCreateRequestBuilder::in_database(&database)
+///     .title("Write the release notes")
+///     .select("Status", "Doing")
+///     .date("Due", NaiveDate::from_ymd_opt(2022, 6, 1).unwrap())
+///     .checkbox("Urgent", true)
+///     .icon("📝")
+///     .build();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PageCreateRequestBuilder {
+    parent: Parent,
+    properties: HashMap<String, CreatePropertyValue>,
+    children: Option<Vec<CreateBlock>>,
+    icon: Option<IconObject>,
+    cover: Option<FileObject>,
+}
+
+impl PageCreateRequestBuilder {
+    pub fn new(parent: Parent) -> Self {
+        Self {
+            parent,
+            properties: HashMap::new(),
+            children: None,
+            icon: None,
+            cover: None,
+        }
+    }
+
+    /// Starts a request for a new row of `database`.
+    pub fn in_database<D: AsIdentifier<DatabaseId>>(database: D) -> Self {
+        Self::new(Parent::Database {
+            database_id: database.as_id().clone(),
+        })
+    }
+
+    /// Starts a request for a new sub-page of `page`.
+    /// Sub-pages only have a [title](Self::title()).
+    pub fn in_page<P: AsIdentifier<PageId>>(page: P) -> Self {
+        Self::new(Parent::Page {
+            page_id: page.as_id().clone(),
+        })
+    }
+
+    /// Sets any property value, replacing any earlier value for `name`.
+    pub fn property<S: Into<String>>(
+        mut self,
+        name: S,
+        value: CreatePropertyValue,
+    ) -> Self {
+        self.properties.insert(name.into(), value);
+        self
+    }
+
+    /// Sets the title of the page, whatever the title property of its database is called.
+    pub fn title<T: Into<String>>(
+        self,
+        title: T,
+    ) -> Self {
+        // The title property always has the id `title`, which Notion accepts in place of its name.
+        self.property(
+            "title",
+            CreatePropertyValue::Title(vec![RichText::text(title)]),
+        )
+    }
+
+    pub fn text<S: Into<String>, T: Into<String>>(
+        self,
+        name: S,
+        text: T,
+    ) -> Self {
+        self.property(name, CreatePropertyValue::Text(vec![RichText::text(text)]))
+    }
+
+    pub fn number<S: Into<String>, N: Into<Number>>(
+        self,
+        name: S,
+        number: N,
+    ) -> Self {
+        self.property(name, CreatePropertyValue::Number(Some(number.into())))
+    }
+
+    /// Selects the option named `option`. Notion adds the option if the property doesn't have it yet.
+    pub fn select<S: Into<String>, O: Into<CreateSelectedValue>>(
+        self,
+        name: S,
+        option: O,
+    ) -> Self {
+        self.property(name, CreatePropertyValue::Select(Some(option.into())))
+    }
+
+    pub fn multi_select<S, I>(
+        self,
+        name: S,
+        options: I,
+    ) -> Self
+    where
+        S: Into<String>,
+        I: IntoIterator,
+        I::Item: Into<CreateSelectedValue>,
+    {
+        self.property(
+            name,
+            CreatePropertyValue::MultiSelect(options.into_iter().map(Into::into).collect()),
+        )
+    }
+
+    /// Sets a status property to one of its existing statuses.
+    pub fn status<S: Into<String>, O: Into<CreateSelectedValue>>(
+        self,
+        name: S,
+        status: O,
+    ) -> Self {
+        self.property(name, CreatePropertyValue::Status(Some(status.into())))
+    }
+
+    /// Sets a date property to a single date, or a date and time.
+    pub fn date<S: Into<String>, D: Into<DateOrDateTime>>(
+        self,
+        name: S,
+        date: D,
+    ) -> Self {
+        self.date_value(name, DateValue::new(date))
+    }
+
+    /// Sets a date property to any date value, e.g. a range or a date and time in a time zone.
+    pub fn date_value<S: Into<String>>(
+        self,
+        name: S,
+        date: DateValue,
+    ) -> Self {
+        self.property(name, CreatePropertyValue::Date(Some(date)))
+    }
+
+    /// Links `page` in a relation property.
+    /// Calling it again with the same property name links more pages.
+    pub fn relation<S: Into<String>, P: AsIdentifier<PageId>>(
+        mut self,
+        name: S,
+        page: P,
+    ) -> Self {
+        let related = RelationValue {
+            id: page.as_id().clone(),
+        };
+        match self.properties.entry(name.into()) {
+            Entry::Occupied(mut entry) => match entry.get_mut() {
+                CreatePropertyValue::Relation(relation) => relation.push(related),
+                value => *value = CreatePropertyValue::Relation(vec![related]),
+            },
+            Entry::Vacant(entry) => {
+                entry.insert(CreatePropertyValue::Relation(vec![related]));
+            }
+        }
+        self
+    }
+
+    /// Assigns users to a people property.
+    pub fn people<S, I>(
+        self,
+        name: S,
+        users: I,
+    ) -> Self
+    where
+        S: Into<String>,
+        I: IntoIterator,
+        I::Item: AsIdentifier<UserId>,
+    {
+        self.property(name, CreatePropertyValue::people(users))
+    }
+
+    pub fn checkbox<S: Into<String>>(
+        self,
+        name: S,
+        checked: bool,
+    ) -> Self {
+        self.property(name, CreatePropertyValue::Checkbox(checked))
+    }
+
+    pub fn url<S: Into<String>, U: Into<String>>(
+        self,
+        name: S,
+        url: U,
+    ) -> Self {
+        self.property(name, CreatePropertyValue::Url(Some(url.into())))
+    }
+
+    pub fn email<S: Into<String>, E: Into<String>>(
+        self,
+        name: S,
+        email: E,
+    ) -> Self {
+        self.property(name, CreatePropertyValue::Email(Some(email.into())))
+    }
+
+    /// The content of the page.
+    pub fn children(
+        self,
+        children: Vec<CreateBlock>,
+    ) -> Self {
+        Self {
+            children: Some(children),
+            ..self
+        }
+    }
+
+    /// Uses an emoji as the page icon.
+    pub fn icon<E: Into<String>>(
+        self,
+        emoji: E,
+    ) -> Self {
+        Self {
+            icon: Some(IconObject::Emoji {
+                emoji: emoji.into(),
+            }),
+            ..self
+        }
+    }
+
+    /// The image shown at the top of the page.
+    pub fn cover(
+        self,
+        cover: FileObject,
+    ) -> Self {
+        Self {
+            cover: Some(cover),
+            ..self
+        }
+    }
+
+    pub fn build(self) -> PageCreateRequest {
+        PageCreateRequest {
+            parent: self.parent,
+            properties: self.properties,
+            children: self.children,
+            icon: self.icon,
+            cover: self.cover,
+        }
+    }
+}
+
+impl From<PageCreateRequestBuilder> for PageCreateRequest {
+    fn from(builder: PageCreateRequestBuilder) -> Self {
+        builder.build()
+    }
+}
+
+/// Changes to the properties, icon or cover of a page.
+/// See <https://developers.notion.com/reference/patch-page>
+#[derive(Serialize, Debug, Eq, PartialEq, Clone, Default)]
+pub struct PageUpdateRequest {
+    /// Property values to change, keyed by name.
+    /// Properties that aren't included are left as they are.
+    #[serde(
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "serialize_sorted"
+    )]
+    pub properties: HashMap<String, CreatePropertyValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<IconObject>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cover: Option<FileObject>,
+}
+
+impl PageUpdateRequest {
+    /// Sets a property value, replacing any earlier value for `name`.
+    pub fn property<S: Into<String>>(
+        mut self,
+        name: S,
+        value: CreatePropertyValue,
+    ) -> Self {
+        self.properties.insert(name.into(), value);
+        self
+    }
+}
+
+/// See <https://developers.notion.com/reference/create-a-database>
+#[derive(Serialize, Debug, Eq, PartialEq, Clone)]
+pub struct DatabaseCreateRequest {
+    /// Databases can only be created as children of a page.
+    pub parent: Parent,
+    pub title: Vec<RichText>,
+    /// Schema of the database, keyed by property name.
+    /// Every database needs exactly one [title](CreatePropertyConfiguration::Title) property.
+    #[serde(serialize_with = "serialize_sorted")]
+    pub properties: HashMap<String, CreatePropertyConfiguration>,
+}
+
+impl DatabaseCreateRequest {
+    pub fn new<P: AsIdentifier<PageId>>(
+        parent: P,
+        title: Vec<RichText>,
+    ) -> Self {
+        Self {
+            parent: Parent::Page {
+                page_id: parent.as_id().clone(),
+            },
+            title,
+            properties: HashMap::new(),
+        }
+    }
+
+    /// Adds a property to the schema, replacing any property with the same name.
+    pub fn property<S: Into<String>>(
+        mut self,
+        name: S,
+        configuration: CreatePropertyConfiguration,
+    ) -> Self {
+        self.properties.insert(name.into(), configuration);
+        self
+    }
+}
+
+/// See <https://developers.notion.com/reference/update-a-database>
+#[derive(Serialize, Debug, Eq, PartialEq, Clone, Default)]
+pub struct DatabaseUpdateRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<Vec<RichText>>,
+    /// Properties to add or change, keyed by name.
+    /// Properties that aren't included are left as they are.
+    #[serde(
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "serialize_sorted"
+    )]
+    pub properties: HashMap<String, CreatePropertyConfiguration>,
+}
+
+impl DatabaseUpdateRequest {
+    /// Adds or changes a property of the schema.
+    /// For selects, the options given replace the existing ones.
+    pub fn property<S: Into<String>>(
+        mut self,
+        name: S,
+        configuration: CreatePropertyConfiguration,
+    ) -> Self {
+        self.properties.insert(name.into(), configuration);
+        self
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
@@ -216,17 +809,34 @@ pub struct Page {
     pub created_time: DateTime<Utc>,
     /// Date and time when this page was updated.
     pub last_edited_time: DateTime<Utc>,
+    /// User who created the page. Notion only includes the user's id.
+    pub created_by: Option<UserCommon>,
+    /// User who last edited the page. Notion only includes the user's id.
+    pub last_edited_by: Option<UserCommon>,
     /// The archived status of the page.
     pub archived: bool,
+    /// Whether the page is in the trash. Responses from before Notion added the field leave it `false`.
+    #[serde(default)]
+    pub in_trash: bool,
     pub properties: Properties,
     pub icon: Option<IconObject>,
+    pub cover: Option<FileObject>,
     pub parent: Parent,
+    /// The URL of the page in Notion.
+    pub url: Option<String>,
+    /// The URL of the page on the web, if it's been published.
+    pub public_url: Option<String>,
 }
 
 impl Page {
     pub fn title(&self) -> Option<String> {
         self.properties.title()
     }
+
+    /// The title prefixed with the icon emoji, if the icon is an emoji, e.g. `🥬 Tuscan kale`.
+    pub fn display_title(&self) -> String {
+        display_title(self.icon.as_ref(), self.title().unwrap_or_default())
+    }
 }
 
 impl AsIdentifier<PageId> for Page {
@@ -259,6 +869,10 @@ pub enum Object {
         #[serde(flatten)]
         user: User,
     },
+    PropertyItem {
+        #[serde(flatten)]
+        item: PropertyItem,
+    },
     Error {
         #[serde(flatten)]
         error: ErrorResponse,
@@ -270,3 +884,97 @@ impl Object {
         matches!(self, Object::Database { .. })
     }
 }
+
+/// A page or database found by [searching](crate::NotionApi::search()).
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(tag = "object")]
+#[serde(rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
+pub enum SearchResult {
+    Page {
+        #[serde(flatten)]
+        page: Page,
+    },
+    Database {
+        #[serde(flatten)]
+        database: Database,
+    },
+}
+
+impl SearchResult {
+    pub fn is_page(&self) -> bool {
+        matches!(self, SearchResult::Page { .. })
+    }
+
+    pub fn is_database(&self) -> bool {
+        matches!(self, SearchResult::Database { .. })
+    }
+
+    /// The page or database title, prefixed with its emoji icon.
+    pub fn display_title(&self) -> String {
+        match self {
+            SearchResult::Page { page } => page.display_title(),
+            SearchResult::Database { database } => database.display_title(),
+        }
+    }
+
+    pub fn last_edited_time(&self) -> DateTime<Utc> {
+        match self {
+            SearchResult::Page { page } => page.last_edited_time,
+            SearchResult::Database { database } => database.last_edited_time,
+        }
+    }
+
+    /// Where the page or database is, to rebuild the hierarchy of a workspace from search results.
+    pub fn parent(&self) -> &Parent {
+        match self {
+            SearchResult::Page { page } => &page.parent,
+            SearchResult::Database { database } => &database.parent,
+        }
+    }
+}
+
+impl From<SearchResult> for Object {
+    fn from(result: SearchResult) -> Self {
+        match result {
+            SearchResult::Page { page } => Object::Page { page },
+            SearchResult::Database { database } => Object::Database { database },
+        }
+    }
+}
+
+impl ListResponse<SearchResult> {
+    pub fn only_databases(self) -> ListResponse<Database> {
+        let databases = self
+            .results
+            .into_iter()
+            .filter_map(|result| match result {
+                SearchResult::Database { database } => Some(database),
+                SearchResult::Page { .. } => None,
+            })
+            .collect();
+
+        ListResponse {
+            results: databases,
+            has_more: self.has_more,
+            next_cursor: self.next_cursor,
+        }
+    }
+
+    pub fn only_pages(self) -> ListResponse<Page> {
+        let pages = self
+            .results
+            .into_iter()
+            .filter_map(|result| match result {
+                SearchResult::Page { page } => Some(page),
+                SearchResult::Database { .. } => None,
+            })
+            .collect();
+
+        ListResponse {
+            results: pages,
+            has_more: self.has_more,
+            next_cursor: self.next_cursor,
+        }
+    }
+}
@@ -1,20 +1,197 @@
+use crate::models::ListResponse;
+use crate::Error;
+use futures::{stream, Stream, TryStreamExt};
 use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::future::Future;
 
+/// An opaque cursor returned as the `next_cursor` of a [ListResponse],
+/// used to resume listing from where that response ended.
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Clone)]
 #[serde(transparent)]
 pub struct PagingCursor(String);
 
+impl PagingCursor {
+    pub fn new(cursor: String) -> Self {
+        Self(cursor)
+    }
+
+    pub fn value(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for PagingCursor {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// <https://developers.notion.com/reference/pagination#parameters-for-paginated-requests>
 #[derive(Serialize, Debug, Eq, PartialEq, Default, Clone)]
 pub struct Paging {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub start_cursor: Option<PagingCursor>,
+    /// The number of items to return, at most 100.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub page_size: Option<u8>,
 }
 
+impl Paging {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_page_size(
+        self,
+        page_size: u8,
+    ) -> Self {
+        Paging {
+            page_size: Some(page_size),
+            ..self
+        }
+    }
+
+    pub fn with_start_cursor(
+        self,
+        start_cursor: PagingCursor,
+    ) -> Self {
+        Paging {
+            start_cursor: Some(start_cursor),
+            ..self
+        }
+    }
+}
+
 pub trait Pageable {
     fn start_from(
         self,
         starting_point: Option<PagingCursor>,
     ) -> Self;
 }
+
+impl Pageable for Paging {
+    fn start_from(
+        self,
+        starting_point: Option<PagingCursor>,
+    ) -> Self {
+        Paging {
+            start_cursor: starting_point,
+            ..self
+        }
+    }
+}
+
+/// Calls `fetch` with `query` and then with the `next_cursor` of each response,
+/// until a response reports there is nothing more, yielding the results one by one.
+pub(crate) fn paginate<Q, T, F, Fut>(
+    query: Q,
+    fetch: F,
+) -> impl Stream<Item = Result<T, Error>>
+where
+    Q: Pageable + Clone,
+    F: FnMut(Q) -> Fut,
+    Fut: Future<Output = Result<ListResponse<T>, Error>>,
+{
+    stream::try_unfold((fetch, Some(query)), |(mut fetch, query)| async move {
+        let query = match query {
+            Some(query) => query,
+            None => return Ok::<_, Error>(None),
+        };
+
+        let response = fetch(query.clone()).await?;
+        let next_query = match response.next_cursor {
+            Some(cursor) if response.has_more => Some(query.start_from(Some(cursor))),
+            _ => None,
+        };
+
+        Ok(Some((response.results, (fetch, next_query))))
+    })
+    .map_ok(|results| stream::iter(results.into_iter().map(Ok)))
+    .try_flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ids::BlockId;
+    use crate::models::paging::{paginate, Paging, PagingCursor};
+    use crate::models::ListResponse;
+    use crate::{NotionApi, TransportResponse};
+    use futures::TryStreamExt;
+    use reqwest::StatusCode;
+
+    #[tokio::test]
+    async fn paginate_follows_cursors() -> Result<(), Box<dyn std::error::Error>> {
+        let mut requests = vec![];
+
+        let results: Vec<_> = paginate(
+            Paging {
+                start_cursor: None,
+                page_size: Some(2),
+            },
+            |paging: Paging| {
+                requests.push(paging.clone());
+                let page = match paging.start_cursor {
+                    None => ListResponse {
+                        results: vec![1, 2],
+                        next_cursor: Some(PagingCursor("second".to_string())),
+                        has_more: true,
+                    },
+                    Some(_) => ListResponse {
+                        results: vec![3],
+                        next_cursor: None,
+                        has_more: false,
+                    },
+                };
+                async { Ok(page) }
+            },
+        )
+        .try_collect()
+        .await?;
+
+        assert_eq!(results, vec![1, 2, 3]);
+        assert_eq!(
+            requests[1],
+            Paging {
+                start_cursor: Some(PagingCursor("second".to_string())),
+                page_size: Some(2),
+            }
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fetches_block_children_from_a_cursor() -> Result<(), Box<dyn std::error::Error>> {
+        let api = NotionApi::builder("secret".to_string())
+            .transport(|request: reqwest::Request| {
+                assert_eq!(
+                    request.url().query(),
+                    Some("start_cursor=second&page_size=10")
+                );
+                Ok(TransportResponse::new(
+                    StatusCode::OK,
+                    r#"{"object": "list", "results": [], "next_cursor": "third", "has_more": true}"#,
+                ))
+            })
+            .build()?;
+        let block_id: BlockId = "9e891834-6a03-475c-a2b8-421e17f0f3aa".parse()?;
+
+        let children = api
+            .get_block_children_with_paging(
+                &block_id,
+                Paging::new()
+                    .with_start_cursor(PagingCursor::new("second".to_string()))
+                    .with_page_size(10),
+            )
+            .await?;
+
+        assert!(children.has_more);
+        assert_eq!(children.next_cursor.unwrap().value(), "third");
+
+        Ok(())
+    }
+}
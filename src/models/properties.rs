@@ -1,12 +1,13 @@
 use crate::models::text::RichText;
 use crate::models::users::User;
 
-use crate::ids::{DatabaseId, PageId, PropertyId};
+use crate::ids::{AsIdentifier, DatabaseId, PageId, PropertyId, UserId};
 use crate::models::{DateTime, Number, Utc};
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 
 pub mod formulas;
+mod plain;
 
 #[cfg(test)]
 mod tests;
@@ -88,21 +89,79 @@ pub struct Formula {
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(from = "RelationRepr")]
 pub struct Relation {
     /// The database this relation refers to.
     /// New linked pages must belong to this database in order to be valid.
     pub database_id: DatabaseId,
-    /// By default, relations are formed as two synced properties across databases:
-    ///     if you make a change to one property, it updates the synced property at the same time.
-    /// `synced_property_name` refers to the name of the property in the related database.
+    #[serde(flatten)]
+    pub kind: RelationKind,
+}
+
+/// Whether a relation only shows in its own database, or also as a synced property
+/// of the related database.
+/// <https://developers.notion.com/reference/property-object#relation>
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RelationKind {
+    SingleProperty { single_property: SingleProperty },
+    DualProperty { dual_property: DualProperty },
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Eq, PartialEq, Clone)]
+pub struct SingleProperty {}
+
+/// The property a relation is synced with in the related database:
+/// a change to either property updates the other at the same time.
+#[derive(Serialize, Deserialize, Debug, Default, Eq, PartialEq, Clone)]
+pub struct DualProperty {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub synced_property_name: Option<String>,
-    /// By default, relations are formed as two synced properties across databases:
-    ///     if you make a change to one property, it updates the synced property at the same time.
-    /// `synced_property_id` refers to the id of the property in the related database.
     /// This is usually a short string of random letters and symbols.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub synced_property_id: Option<PropertyId>,
 }
 
+/// Relations as Notion-Version `2022-06-28` and later describe them, or as earlier versions do,
+/// where every relation is synced.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RelationRepr {
+    Current {
+        database_id: DatabaseId,
+        #[serde(flatten)]
+        kind: RelationKind,
+    },
+    Synced {
+        database_id: DatabaseId,
+        #[serde(default)]
+        synced_property_name: Option<String>,
+        #[serde(default)]
+        synced_property_id: Option<PropertyId>,
+    },
+}
+
+impl From<RelationRepr> for Relation {
+    fn from(repr: RelationRepr) -> Self {
+        match repr {
+            RelationRepr::Current { database_id, kind } => Relation { database_id, kind },
+            RelationRepr::Synced {
+                database_id,
+                synced_property_name,
+                synced_property_id,
+            } => Relation {
+                database_id,
+                kind: RelationKind::DualProperty {
+                    dual_property: DualProperty {
+                        synced_property_name,
+                        synced_property_id,
+                    },
+                },
+            },
+        }
+    }
+}
+
 /// The function used to roll up the values of the relation property.
 /// <https://developers.notion.com/reference/page-property-values#rollup>
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Copy, Clone)]
@@ -265,6 +324,233 @@ pub enum PropertyConfiguration {
     },
 }
 
+impl PropertyConfiguration {
+    pub fn id(&self) -> &PropertyId {
+        use PropertyConfiguration::*;
+        match self {
+            Title { id }
+            | Text { id }
+            | Number { id, .. }
+            | Select { id, .. }
+            | Status { id, .. }
+            | MultiSelect { id, .. }
+            | Date { id }
+            | People { id }
+            | Files { id }
+            | Checkbox { id }
+            | Url { id }
+            | Email { id }
+            | PhoneNumber { id }
+            | Formula { id, .. }
+            | Relation { id, .. }
+            | Rollup { id, .. }
+            | CreatedTime { id }
+            | CreatedBy { id }
+            | LastEditedTime { id }
+            | LastEditBy { id }
+            | UniqueId { id }
+            | Button { id } => id,
+        }
+    }
+    /// The type of the property as Notion names it, e.g. `rich_text` or `multi_select`.
+    pub fn type_name(&self) -> &'static str {
+        use PropertyConfiguration::*;
+        match self {
+            Title { .. } => "title",
+            Text { .. } => "rich_text",
+            Number { .. } => "number",
+            Select { .. } => "select",
+            Status { .. } => "status",
+            MultiSelect { .. } => "multi_select",
+            Date { .. } => "date",
+            People { .. } => "people",
+            Files { .. } => "files",
+            Checkbox { .. } => "checkbox",
+            Url { .. } => "url",
+            Email { .. } => "email",
+            PhoneNumber { .. } => "phone_number",
+            Formula { .. } => "formula",
+            Relation { .. } => "relation",
+            Rollup { .. } => "rollup",
+            CreatedTime { .. } => "created_time",
+            CreatedBy { .. } => "created_by",
+            LastEditedTime { .. } => "last_edited_time",
+            LastEditBy { .. } => "last_edited_by",
+            UniqueId { .. } => "unique_id",
+            Button { .. } => "button",
+        }
+    }
+}
+
+/// An option for a select or multi-select property being created.
+/// Notion assigns the id, and picks a color when none is given.
+#[derive(Serialize, Debug, Eq, PartialEq, Clone)]
+pub struct CreateSelectOption {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<Color>,
+}
+
+impl CreateSelectOption {
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Self {
+            name: name.into(),
+            color: None,
+        }
+    }
+
+    pub fn with_color(
+        self,
+        color: Color,
+    ) -> Self {
+        Self {
+            color: Some(color),
+            ..self
+        }
+    }
+}
+
+impl From<&str> for CreateSelectOption {
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<String> for CreateSelectOption {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
+
+#[derive(Serialize, Debug, Eq, PartialEq, Clone)]
+pub struct CreateSelect {
+    pub options: Vec<CreateSelectOption>,
+}
+
+#[derive(Serialize, Debug, Eq, PartialEq, Clone)]
+pub struct CreateRelation {
+    /// The database the relation refers to.
+    pub database_id: DatabaseId,
+    /// Single property relations need Notion-Version `2022-06-28` or later,
+    /// see [api_version](crate::NotionApiBuilder::api_version()).
+    /// Earlier versions receive dual property relations without their `type` tag.
+    #[serde(flatten)]
+    pub kind: RelationKind,
+}
+
+#[derive(Serialize, Debug, Eq, PartialEq, Clone)]
+pub struct CreateRollup {
+    /// The name of the relation property to roll up.
+    pub relation_property_name: String,
+    /// The name of the property of the related pages used as an input to `function`.
+    pub rollup_property_name: String,
+    pub function: RollupFunction,
+}
+
+/// The schema of a property when creating a database.
+/// Unlike [PropertyConfiguration] there is no id, Notion assigns one on creation.
+/// See <https://developers.notion.com/reference/create-a-database>
+#[derive(Serialize, Debug, Eq, PartialEq, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum CreatePropertyConfiguration {
+    Title {},
+    #[serde(rename = "rich_text")]
+    Text {},
+    Number(NumberDetails),
+    Select(CreateSelect),
+    MultiSelect(CreateSelect),
+    Date {},
+    People {},
+    Files {},
+    Checkbox {},
+    Url {},
+    Email {},
+    PhoneNumber {},
+    Formula(Formula),
+    Relation(CreateRelation),
+    Rollup(CreateRollup),
+    CreatedTime {},
+    CreatedBy {},
+    LastEditedTime {},
+    LastEditedBy {},
+}
+
+impl CreatePropertyConfiguration {
+    pub fn title() -> Self {
+        Self::Title {}
+    }
+
+    pub fn text() -> Self {
+        Self::Text {}
+    }
+
+    pub fn number(format: NumberFormat) -> Self {
+        Self::Number(NumberDetails { format })
+    }
+
+    pub fn select<I, O>(options: I) -> Self
+    where
+        I: IntoIterator<Item = O>,
+        O: Into<CreateSelectOption>,
+    {
+        Self::Select(CreateSelect {
+            options: options.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    pub fn multi_select<I, O>(options: I) -> Self
+    where
+        I: IntoIterator<Item = O>,
+        O: Into<CreateSelectOption>,
+    {
+        Self::MultiSelect(CreateSelect {
+            options: options.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    pub fn formula<S: Into<String>>(expression: S) -> Self {
+        Self::Formula(Formula {
+            expression: expression.into(),
+        })
+    }
+
+    /// A relation synced with a new property of the related database.
+    pub fn relation(database_id: DatabaseId) -> Self {
+        Self::Relation(CreateRelation {
+            database_id,
+            kind: RelationKind::DualProperty {
+                dual_property: DualProperty::default(),
+            },
+        })
+    }
+
+    /// A relation that leaves the related database as it is.
+    pub fn single_property_relation(database_id: DatabaseId) -> Self {
+        Self::Relation(CreateRelation {
+            database_id,
+            kind: RelationKind::SingleProperty {
+                single_property: SingleProperty::default(),
+            },
+        })
+    }
+
+    pub fn rollup<R, P>(
+        relation_property_name: R,
+        rollup_property_name: P,
+        function: RollupFunction,
+    ) -> Self
+    where
+        R: Into<String>,
+        P: Into<String>,
+    {
+        Self::Rollup(CreateRollup {
+            relation_property_name: relation_property_name.into(),
+            rollup_property_name: rollup_property_name.into(),
+            function,
+        })
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 pub struct SelectedValue {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -279,15 +565,73 @@ pub struct SelectedValue {
 pub enum DateOrDateTime {
     Date(NaiveDate),
     DateTime(DateTime<Utc>),
+    /// A date and time without an offset, in the [time zone](DateValue::time_zone)
+    /// of the date value.
+    LocalDateTime(NaiveDateTime),
+}
+
+impl From<NaiveDate> for DateOrDateTime {
+    fn from(date: NaiveDate) -> Self {
+        DateOrDateTime::Date(date)
+    }
+}
+
+impl From<DateTime<Utc>> for DateOrDateTime {
+    fn from(date_time: DateTime<Utc>) -> Self {
+        DateOrDateTime::DateTime(date_time)
+    }
+}
+
+impl From<NaiveDateTime> for DateOrDateTime {
+    fn from(date_time: NaiveDateTime) -> Self {
+        DateOrDateTime::LocalDateTime(date_time)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 pub struct DateValue {
     pub start: DateOrDateTime,
     pub end: Option<DateOrDateTime>,
+    /// The IANA time zone `start` and `end` are in, e.g. `Europe/Berlin`.
+    /// Notion only accepts a time zone with [local](DateOrDateTime::LocalDateTime) dates and times,
+    /// and returns them converted to UTC.
     pub time_zone: Option<String>,
 }
 
+impl DateValue {
+    /// A single date, or date and time.
+    pub fn new<D: Into<DateOrDateTime>>(start: D) -> Self {
+        Self {
+            start: start.into(),
+            end: None,
+            time_zone: None,
+        }
+    }
+
+    /// A date and time in `time_zone`, e.g. `America/New_York`.
+    pub fn in_time_zone<T: Into<String>>(
+        start: NaiveDateTime,
+        time_zone: T,
+    ) -> Self {
+        Self {
+            start: DateOrDateTime::LocalDateTime(start),
+            end: None,
+            time_zone: Some(time_zone.into()),
+        }
+    }
+
+    /// Makes the date a range ending at `end`.
+    pub fn with_end<D: Into<DateOrDateTime>>(
+        self,
+        end: D,
+    ) -> Self {
+        Self {
+            end: Some(end.into()),
+            ..self
+        }
+    }
+}
+
 /// Formula property value objects represent the result of evaluating a formula
 /// described in the database's properties.
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
@@ -311,9 +655,19 @@ pub struct RelationValue {
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum RollupValue {
-    Number { number: Option<Number> },
-    Date { date: Option<DateTime<Utc>> },
-    Array { array: Vec<RollupPropertyValue> },
+    Number {
+        number: Option<Number>,
+    },
+    Date {
+        date: Option<DateValue>,
+    },
+    Array {
+        array: Vec<RollupPropertyValue>,
+    },
+    /// Notion can't compute this rollup through the API, e.g. for some functions of formulas.
+    Unsupported {},
+    /// The rollup covers more related pages than Notion computes it for.
+    Incomplete {},
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
@@ -438,6 +792,178 @@ pub enum PropertyValue {
     },
 }
 
+/// A property value written when creating a page.
+///
+/// Only covers the property types Notion allows setting: formulas, rollups and the
+/// created/edited metadata are computed by Notion. Values serialize to the request
+/// shape, e.g. `{"select": {"name": "Done"}}`, without the type and id responses include.
+/// <https://developers.notion.com/reference/page-property-values>
+#[derive(Serialize, Debug, Eq, PartialEq, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum CreatePropertyValue {
+    Title(Vec<RichText>),
+    #[serde(rename = "rich_text")]
+    Text(Vec<RichText>),
+    Number(Option<Number>),
+    Select(Option<CreateSelectedValue>),
+    Status(Option<CreateSelectedValue>),
+    MultiSelect(Vec<CreateSelectedValue>),
+    Date(Option<DateValue>),
+    Relation(Vec<RelationValue>),
+    People(Vec<CreatePerson>),
+    Checkbox(bool),
+    Url(Option<String>),
+    Email(Option<String>),
+    PhoneNumber(Option<String>),
+}
+
+impl CreatePropertyValue {
+    /// Links the pages in a relation property, replacing the pages it linked before.
+    pub fn relation<I>(pages: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsIdentifier<PageId>,
+    {
+        Self::Relation(
+            pages
+                .into_iter()
+                .map(|page| RelationValue {
+                    id: page.as_id().clone(),
+                })
+                .collect(),
+        )
+    }
+
+    /// The value that sets a property to `value`, or `None` for the property types
+    /// Notion computes itself, like formulas, and for files.
+    /// Options are picked by id when `value` has one.
+    pub fn from_value(value: &PropertyValue) -> Option<Self> {
+        let selected = |value: &SelectedValue| match (&value.id, &value.name) {
+            (Some(id), _) => Some(CreateSelectedValue::Id { id: id.clone() }),
+            (None, Some(name)) => Some(CreateSelectedValue::Name { name: name.clone() }),
+            (None, None) => None,
+        };
+
+        let value = match value {
+            PropertyValue::Title { title, .. } => Self::Title(title.clone()),
+            PropertyValue::Text { rich_text, .. } => Self::Text(rich_text.clone()),
+            PropertyValue::Number { number, .. } => Self::Number(number.clone()),
+            PropertyValue::Select { select, .. } => {
+                Self::Select(select.as_ref().and_then(selected))
+            }
+            PropertyValue::Status { status, .. } => {
+                Self::Status(status.as_ref().and_then(selected))
+            }
+            PropertyValue::MultiSelect { multi_select, .. } => {
+                Self::MultiSelect(multi_select.iter().flatten().filter_map(selected).collect())
+            }
+            PropertyValue::Date { date, .. } => Self::Date(date.clone()),
+            PropertyValue::Relation { relation, .. } => {
+                Self::Relation(relation.clone().unwrap_or_default())
+            }
+            PropertyValue::People { people, .. } => Self::people(people.iter().map(User::id)),
+            PropertyValue::Checkbox { checkbox, .. } => Self::Checkbox(*checkbox),
+            PropertyValue::Url { url, .. } => Self::Url(url.clone()),
+            PropertyValue::Email { email, .. } => Self::Email(email.clone()),
+            PropertyValue::PhoneNumber { phone_number, .. } => Self::PhoneNumber(
+                Some(phone_number.clone()).filter(|phone_number| !phone_number.is_empty()),
+            ),
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /// Assigns the users to a people property, replacing the users it had before.
+    /// Only the ids of the users are sent.
+    pub fn people<I>(users: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsIdentifier<UserId>,
+    {
+        Self::People(
+            users
+                .into_iter()
+                .map(|user| CreatePerson {
+                    id: user.as_id().clone(),
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Picks an option of a select, multi-select or status property.
+/// Selects and multi-selects gain a new option when no option has the name given.
+#[derive(Serialize, Debug, Eq, PartialEq, Clone)]
+#[serde(untagged)]
+pub enum CreateSelectedValue {
+    Name { name: String },
+    Id { id: SelectOptionId },
+}
+
+impl From<&str> for CreateSelectedValue {
+    fn from(name: &str) -> Self {
+        CreateSelectedValue::Name {
+            name: name.to_string(),
+        }
+    }
+}
+
+impl From<String> for CreateSelectedValue {
+    fn from(name: String) -> Self {
+        CreateSelectedValue::Name { name }
+    }
+}
+
+impl From<SelectOptionId> for CreateSelectedValue {
+    fn from(id: SelectOptionId) -> Self {
+        CreateSelectedValue::Id { id }
+    }
+}
+
+/// A user assigned to a people property.
+#[derive(Serialize, Debug, Eq, PartialEq, Clone)]
+pub struct CreatePerson {
+    pub id: UserId,
+}
+
+/// An item of a page property, as returned by the retrieve a page property item endpoint.
+/// <https://developers.notion.com/reference/property-item-object>
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
+pub enum PropertyItem {
+    /// One element of a paginated property value.
+    Paginated(PaginatedPropertyItem),
+    /// The complete value of a property that isn't paginated.
+    Value(PropertyValue),
+}
+
+/// Title, rich text, relation and people properties are returned one element per item,
+/// since they can hold more values than fit in a page object.
+/// <https://developers.notion.com/reference/property-item-object#paginated-property-values>
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(tag = "type")]
+#[serde(rename_all = "snake_case")]
+pub enum PaginatedPropertyItem {
+    Title {
+        id: PropertyId,
+        title: RichText,
+    },
+    #[serde(rename = "rich_text")]
+    Text {
+        id: PropertyId,
+        rich_text: RichText,
+    },
+    Relation {
+        id: PropertyId,
+        relation: RelationValue,
+    },
+    People {
+        id: PropertyId,
+        people: User,
+    },
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct UniqueidValue {
     pub prefix: Option<String>,
@@ -449,6 +975,10 @@ pub struct UniqueidValue {
 #[serde(tag = "type")]
 #[serde(rename_all = "snake_case")]
 pub enum RollupPropertyValue {
+    /// <https://developers.notion.com/reference/page#title-property-values>
+    Title {
+        title: Vec<RichText>,
+    },
     /// <https://developers.notion.com/reference/page#rich-text-property-values>
     #[serde(rename = "rich_text")]
     Text {
@@ -514,4 +1044,7 @@ pub enum RollupPropertyValue {
     LastEditedBy {
         last_edited_by: User,
     },
+    UniqueId {
+        unique_id: UniqueidValue,
+    },
 }
@@ -1,6 +1,134 @@
+//! Parsers for the text formulas and rollups return, e.g. `"3 days"` or `"$1,200"`.
+//!
+//! Formulas often format durations and amounts as strings, which the API returns as is.
+//! These parsers recognize the common formats and return `None` for anything else,
+//! leaving the fallback to the caller, e.g. `parse_duration(text).unwrap_or_else(Duration::zero)`.
+
+use crate::models::properties::FormulaResultValue;
+use chrono::Duration;
+
+/// Parses a duration written as amounts of units, e.g. `"3 days"`, `"1.5 hours"`,
+/// `"2h 30m"` or `"1 week, 2 days and 4 hours"`.
+///
+/// Units may be weeks, days, hours, minutes or seconds, spelled out, singular or plural,
+/// or abbreviated (`w`, `d`, `h`/`hr`/`hrs`, `m`/`min`/`mins`, `s`/`sec`/`secs`).
+/// A leading `-` negates the whole duration.
+///
+/// ```
+/// use chrono::Duration;
+/// use notion::models::properties::formulas::parse_duration;
+///
+/// assert_eq!(parse_duration("3 days"), Some(Duration::days(3)));
+/// assert_eq!(parse_duration("2h 30m"), Some(Duration::minutes(150)));
+/// assert_eq!(parse_duration("soon"), None);
+/// ```
+pub fn parse_duration(text: &str) -> Option<Duration> {
+    let text = text.trim().to_lowercase();
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest.to_string()),
+        None => (false, text),
+    };
+
+    let mut tokens = tokens(&text).into_iter().peekable();
+    let mut milliseconds = 0f64;
+    let mut any = false;
+    while let Some(token) = tokens.next() {
+        if token == "and" {
+            continue;
+        }
+        // Amounts may be written apart from their units, "3 days", or attached, "3d".
+        let split = token
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(token.len());
+        let (amount, unit) = token.split_at(split);
+        let amount: f64 = amount.parse().ok()?;
+        let unit = match unit {
+            "" => tokens.next()?,
+            unit => unit.to_string(),
+        };
+        milliseconds += amount * unit_milliseconds(&unit)?;
+        any = true;
+    }
+
+    if !any || !milliseconds.is_finite() || milliseconds.abs() > i64::MAX as f64 {
+        return None;
+    }
+    let duration = Duration::milliseconds(milliseconds.round() as i64);
+    Some(if negative { -duration } else { duration })
+}
+
+/// Parses a number formatted for display, e.g. `"1,234.5"`, `"$12"`, `"€ 3.50"` or `"45%"`.
+///
+/// Thousands separators and currency symbols are ignored. Percentages are divided by 100,
+/// the way Notion stores numbers formatted as percentages.
+///
+/// ```
+/// use notion::models::properties::formulas::parse_number;
+///
+/// assert_eq!(parse_number("$1,200"), Some(1200.0));
+/// assert_eq!(parse_number("45%"), Some(0.45));
+/// assert_eq!(parse_number("n/a"), None);
+/// ```
+pub fn parse_number(text: &str) -> Option<f64> {
+    let text = text.trim();
+    let (text, scale) = match text.strip_suffix('%') {
+        Some(rest) => (rest, 0.01),
+        None => (text, 1.0),
+    };
+    let digits: String = text
+        .chars()
+        .filter(|c| {
+            !matches!(c, ',' | '_' | '$' | '€' | '£' | '¥' | '₹' | '₩' | '₽') && !c.is_whitespace()
+        })
+        .collect();
+    let number: f64 = digits.parse().ok()?;
+    number.is_finite().then_some(number * scale)
+}
+
+impl FormulaResultValue {
+    /// The result as a number, [parsing](parse_number) string results.
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            FormulaResultValue::Number { number } => number.as_ref()?.as_f64(),
+            FormulaResultValue::String { string } => parse_number(string.as_deref()?),
+            _ => None,
+        }
+    }
+
+    /// The result of a formula that formats a duration, see [parse_duration].
+    pub fn as_duration(&self) -> Option<Duration> {
+        match self {
+            FormulaResultValue::String { string } => parse_duration(string.as_deref()?),
+            _ => None,
+        }
+    }
+}
+
+/// Words and attached amounts, e.g. `["1", "week", "2d"]` for `"1 week, 2d"`.
+fn tokens(text: &str) -> Vec<String> {
+    text.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|token| !token.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn unit_milliseconds(unit: &str) -> Option<f64> {
+    let seconds = match unit {
+        "w" | "wk" | "wks" | "week" | "weeks" => 7. * 24. * 60. * 60.,
+        "d" | "day" | "days" => 24. * 60. * 60.,
+        "h" | "hr" | "hrs" | "hour" | "hours" => 60. * 60.,
+        "m" | "min" | "mins" | "minute" | "minutes" => 60.,
+        "s" | "sec" | "secs" | "second" | "seconds" => 1.,
+        _ => return None,
+    };
+    Some(seconds * 1000.)
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::models::properties::formulas::{parse_duration, parse_number};
     use crate::models::properties::{FormulaResultValue, PropertyValue};
+    use chrono::Duration;
 
     #[test]
     fn parse_number_formula_prop() {
@@ -24,4 +152,44 @@ mod tests {
         )
         .unwrap();
     }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(
+            parse_duration("1 week, 2 days and 4 hours"),
+            Some(Duration::hours(7 * 24 + 2 * 24 + 4))
+        );
+        assert_eq!(parse_duration("1.5 Hours"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration("-3d"), Some(Duration::days(-3)));
+        assert_eq!(parse_duration("45 secs"), Some(Duration::seconds(45)));
+        assert_eq!(parse_duration("3"), None);
+        assert_eq!(parse_duration("3 fortnights"), None);
+        assert_eq!(parse_duration(""), None);
+    }
+
+    #[test]
+    fn parses_numbers_and_formula_results() {
+        assert_eq!(parse_number("€ 3.50"), Some(3.5));
+        assert_eq!(parse_number("-1,234"), Some(-1234.0));
+
+        let string = |text: &str| FormulaResultValue::String {
+            string: Some(text.to_string()),
+        };
+        assert_eq!(string("12.5%").as_number(), Some(0.125));
+        assert_eq!(string("2 days").as_duration(), Some(Duration::days(2)));
+        assert_eq!(
+            FormulaResultValue::Number {
+                number: Some(4.into())
+            }
+            .as_number(),
+            Some(4.0)
+        );
+        assert_eq!(
+            FormulaResultValue::Boolean {
+                boolean: Some(true)
+            }
+            .as_number(),
+            None
+        );
+    }
 }
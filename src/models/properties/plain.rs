@@ -0,0 +1,174 @@
+use crate::models::properties::{
+    DateValue, FileReference, FormulaResultValue, PropertyValue, RelationValue,
+    RollupPropertyValue, RollupValue, SelectedValue, UniqueidValue,
+};
+use crate::models::text::{self, RichText};
+use crate::models::users::User;
+use serde_json::Value;
+
+impl PropertyValue {
+    /// The value as plain JSON, the way an app would model it rather than the way Notion does.
+    ///
+    /// | Property | Value |
+    /// |---|---|
+    /// | title, rich text | the plain text |
+    /// | number, checkbox | the number or boolean |
+    /// | select, status | the option name |
+    /// | multi-select | the option names |
+    /// | date | the start date, e.g. `"2021-05-01"`, or date and time |
+    /// | relation | the related page ids |
+    /// | people, created by, last edited by | the user names |
+    /// | files | the file urls |
+    /// | url, email, phone number | the text |
+    /// | created time, last edited time | the timestamp |
+    /// | formula, rollup | their result |
+    /// | unique id | the id with its prefix, e.g. `"TASK-12"`, or the number without one |
+    ///
+    /// Empty values are `null`, or an empty array for the properties listed as plural.
+    pub fn plain_value(&self) -> Value {
+        match self {
+            PropertyValue::Title { title: text, .. }
+            | PropertyValue::Text {
+                rich_text: text, ..
+            } => plain_text(text),
+            PropertyValue::Number { number, .. } => number.clone().into(),
+            PropertyValue::Select { select: option, .. }
+            | PropertyValue::Status { status: option, .. } => option_name(option.as_ref()),
+            PropertyValue::MultiSelect { multi_select, .. } => {
+                option_names(multi_select.as_deref())
+            }
+            PropertyValue::Date { date, .. } => start_date(date.as_ref()),
+            PropertyValue::Formula { formula, .. } => formula_result(formula),
+            PropertyValue::Relation { relation, .. } => page_ids(relation.as_deref()),
+            PropertyValue::Rollup { rollup, .. } => rollup_result(rollup.as_ref()),
+            PropertyValue::People { people, .. } => people.iter().map(user_name).collect(),
+            PropertyValue::Files { files, .. } => file_urls(files.as_deref()),
+            PropertyValue::Checkbox { checkbox, .. } => Value::Bool(*checkbox),
+            PropertyValue::Url { url: text, .. } | PropertyValue::Email { email: text, .. } => {
+                text.clone().into()
+            }
+            PropertyValue::PhoneNumber { phone_number, .. } => phone_number.clone().into(),
+            PropertyValue::CreatedTime {
+                created_time: time, ..
+            }
+            | PropertyValue::LastEditedTime {
+                last_edited_time: time,
+                ..
+            } => time.to_rfc3339().into(),
+            PropertyValue::CreatedBy {
+                created_by: user, ..
+            }
+            | PropertyValue::LastEditedBy {
+                last_edited_by: user,
+                ..
+            } => user_name(user),
+            PropertyValue::UniqueId { unique_id, .. } => unique_id_value(unique_id),
+            PropertyValue::Button { .. } => Value::Null,
+        }
+    }
+}
+
+impl RollupPropertyValue {
+    /// The value as plain JSON, see [PropertyValue::plain_value].
+    pub fn plain_value(&self) -> Value {
+        match self {
+            RollupPropertyValue::Title { title: text }
+            | RollupPropertyValue::Text { rich_text: text } => plain_text(text),
+            RollupPropertyValue::Number { number } => number.clone().into(),
+            RollupPropertyValue::Select { select: option }
+            | RollupPropertyValue::Status { status: option } => option_name(option.as_ref()),
+            RollupPropertyValue::MultiSelect { multi_select } => {
+                option_names(multi_select.as_deref())
+            }
+            RollupPropertyValue::Date { date } => start_date(date.as_ref()),
+            RollupPropertyValue::Formula { formula } => formula_result(formula),
+            RollupPropertyValue::Relation { relation } => page_ids(relation.as_deref()),
+            RollupPropertyValue::Rollup { rollup } => rollup_result(rollup.as_ref()),
+            RollupPropertyValue::People { people } => people.iter().map(user_name).collect(),
+            RollupPropertyValue::Files { files } => file_urls(files.as_deref()),
+            RollupPropertyValue::Checkbox { checkbox } => Value::Bool(*checkbox),
+            RollupPropertyValue::Url { url: text } | RollupPropertyValue::Email { email: text } => {
+                text.clone().into()
+            }
+            RollupPropertyValue::PhoneNumber { phone_number } => phone_number.clone().into(),
+            RollupPropertyValue::CreatedTime { created_time: time }
+            | RollupPropertyValue::LastEditedTime {
+                last_edited_time: time,
+            } => time.to_rfc3339().into(),
+            RollupPropertyValue::CreatedBy { created_by: user }
+            | RollupPropertyValue::LastEditedBy {
+                last_edited_by: user,
+            } => user_name(user),
+            RollupPropertyValue::UniqueId { unique_id } => unique_id_value(unique_id),
+        }
+    }
+}
+
+fn plain_text(text: &[RichText]) -> Value {
+    Value::String(text::plain_text(text))
+}
+
+fn option_name(option: Option<&SelectedValue>) -> Value {
+    option.and_then(|option| option.name.clone()).into()
+}
+
+fn option_names(options: Option<&[SelectedValue]>) -> Value {
+    options
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|option| option.name.clone())
+        .collect()
+}
+
+/// Dates serialize to ISO 8601 strings, which `chrono` types deserialize from.
+fn start_date(date: Option<&DateValue>) -> Value {
+    date.and_then(|date| serde_json::to_value(&date.start).ok())
+        .unwrap_or(Value::Null)
+}
+
+fn formula_result(formula: &FormulaResultValue) -> Value {
+    match formula {
+        FormulaResultValue::String { string } => string.clone().into(),
+        FormulaResultValue::Number { number } => number.clone().into(),
+        FormulaResultValue::Boolean { boolean } => (*boolean).into(),
+        FormulaResultValue::Date { date } => start_date(date.as_ref()),
+    }
+}
+
+fn rollup_result(rollup: Option<&RollupValue>) -> Value {
+    match rollup {
+        Some(RollupValue::Number { number }) => number.clone().into(),
+        Some(RollupValue::Date { date }) => start_date(date.as_ref()),
+        Some(RollupValue::Array { array }) => {
+            array.iter().map(RollupPropertyValue::plain_value).collect()
+        }
+        Some(RollupValue::Unsupported {} | RollupValue::Incomplete {}) | None => Value::Null,
+    }
+}
+
+fn page_ids(relation: Option<&[RelationValue]>) -> Value {
+    relation
+        .unwrap_or_default()
+        .iter()
+        .map(|page| Value::String(page.id.to_string()))
+        .collect()
+}
+
+fn user_name(user: &User) -> Value {
+    user.name().into()
+}
+
+fn file_urls(files: Option<&[FileReference]>) -> Value {
+    files
+        .unwrap_or_default()
+        .iter()
+        .map(|file| Value::String(file.url.clone()))
+        .collect()
+}
+
+fn unique_id_value(unique_id: &UniqueidValue) -> Value {
+    match &unique_id.prefix {
+        Some(prefix) => format!("{}-{}", prefix, unique_id.number).into(),
+        None => unique_id.number.into(),
+    }
+}
@@ -1,17 +1,37 @@
 use crate::models::{
-    properties::{DateOrDateTime, RollupPropertyValue, RollupValue},
-    PropertyValue,
+    properties::{
+        DateOrDateTime, DateValue, DualProperty, PropertyConfiguration, Relation, RelationKind,
+        RollupPropertyValue, RollupValue, SingleProperty,
+    },
+    Properties, PropertyValue,
 };
 use chrono::NaiveDate;
+use serde_json::json;
 
 #[test]
 fn verify_date_parsing() {
-    let date = NaiveDate::from_ymd_opt(2021, 01, 02).unwrap();
+    let date = NaiveDate::from_ymd_opt(2021, 1, 2).unwrap();
     let result = serde_json::to_string(&DateOrDateTime::Date(date)).unwrap();
     let parsed: DateOrDateTime = serde_json::from_str(&result).unwrap();
     println!("{:?}", parsed);
 }
 
+#[test]
+fn date_in_time_zone() {
+    let start = NaiveDate::from_ymd_opt(2022, 5, 12)
+        .unwrap()
+        .and_hms_opt(9, 30, 0)
+        .unwrap();
+    let date = DateValue::in_time_zone(start, "America/New_York").with_end(start.date());
+
+    let json = serde_json::to_value(&date).unwrap();
+    assert_eq!(
+        json,
+        json!({"start": "2022-05-12T09:30:00", "end": "2022-05-12", "time_zone": "America/New_York"})
+    );
+    assert_eq!(serde_json::from_value::<DateValue>(json).unwrap(), date);
+}
+
 #[test]
 fn parse_date_property() {
     let _property: PropertyValue =
@@ -57,3 +77,157 @@ fn parse_rollup_property() {
         assert!(matches!(array[0], RollupPropertyValue::Text { .. }))
     }
 }
+
+#[test]
+fn parse_rollup_date_property() {
+    let property: PropertyValue =
+        serde_json::from_str(include_str!("tests/rollup_date_property.json")).unwrap();
+
+    match property {
+        PropertyValue::Rollup {
+            rollup: Some(RollupValue::Date { date: Some(date) }),
+            ..
+        } => assert!(matches!(date.end, Some(DateOrDateTime::DateTime(_)))),
+        property => panic!("Expected a date rollup, got {:?}", property),
+    }
+}
+
+#[test]
+fn parse_rollup_of_titles_and_unique_ids() {
+    let property: PropertyValue =
+        serde_json::from_str(include_str!("tests/rollup_title_property.json")).unwrap();
+
+    match property {
+        PropertyValue::Rollup {
+            rollup: Some(RollupValue::Array { array }),
+            ..
+        } => {
+            assert!(matches!(array[0], RollupPropertyValue::Title { .. }));
+            assert!(matches!(array[1], RollupPropertyValue::UniqueId { .. }));
+        }
+        property => panic!("Expected an array rollup, got {:?}", property),
+    }
+}
+
+#[test]
+fn deserialize_plain_values() {
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    struct Row {
+        #[serde(rename = "Status")]
+        status: Option<String>,
+        #[serde(rename = "Due")]
+        due: Option<NaiveDate>,
+        #[serde(rename = "Tasks")]
+        tasks: Vec<String>,
+        #[serde(rename = "Done")]
+        done: bool,
+        #[serde(rename = "Estimate")]
+        estimate: Option<f64>,
+    }
+
+    let fixture = |json: &str| serde_json::from_str::<serde_json::Value>(json).unwrap();
+    let properties: Properties = serde_json::from_value(json!({
+        "Status": fixture(include_str!("tests/select_property.json")),
+        "Due": fixture(include_str!("tests/date_property.json")),
+        "Tasks": fixture(include_str!("tests/rollup_title_property.json")),
+        "Done": {"id": "a", "type": "checkbox", "checkbox": true},
+        "Estimate": {"id": "b", "type": "number", "number": null},
+    }))
+    .unwrap();
+
+    assert_eq!(
+        properties.plain_values()["Tasks"],
+        json!(["Launch", "TASK-42"])
+    );
+    assert_eq!(
+        properties.deserialize_into::<Row>().unwrap(),
+        Row {
+            status: Some("Reserved".to_string()),
+            due: NaiveDate::from_ymd_opt(2021, 9, 30),
+            tasks: vec!["Launch".to_string(), "TASK-42".to_string()],
+            done: true,
+            estimate: None,
+        }
+    );
+}
+
+#[test]
+fn typed_property_getters() {
+    let fixture = |json: &str| serde_json::from_str::<serde_json::Value>(json).unwrap();
+    let properties: Properties = serde_json::from_value(json!({
+        "Status": fixture(include_str!("tests/select_property.json")),
+        "Due": fixture(include_str!("tests/date_property.json")),
+        "Done": {"id": "a", "type": "checkbox", "checkbox": true},
+        "Score": {"id": "b", "type": "number", "number": 7},
+        "Project": {"id": "c", "type": "relation", "relation": [
+            {"id": "b55c9c91-384d-452b-81db-d1ef79372b75"}
+        ]},
+    }))
+    .unwrap();
+
+    assert_eq!(properties.get_select("Status"), Some("Reserved"));
+    assert_eq!(
+        properties.get_date("Due").map(|date| &date.start),
+        Some(&DateOrDateTime::Date(
+            NaiveDate::from_ymd_opt(2021, 9, 30).unwrap()
+        ))
+    );
+    assert_eq!(properties.get_checkbox("Done"), Some(true));
+    assert_eq!(properties.get_number("Score"), Some(&7.into()));
+    assert_eq!(
+        properties
+            .get_relation_ids("Project")
+            .unwrap()
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>(),
+        vec!["b55c9c91-384d-452b-81db-d1ef79372b75"]
+    );
+    assert_eq!(properties.get_checkbox("Status"), None);
+    assert_eq!(properties.get_text("Missing"), None);
+}
+
+#[test]
+fn parse_relation_configurations() {
+    let relation = |json: serde_json::Value| match serde_json::from_value(json).unwrap() {
+        PropertyConfiguration::Relation { relation, .. } => relation,
+        configuration => panic!("not a relation: {:?}", configuration),
+    };
+    let database_id = "668d797c-76fa-4934-9b05-ad288df2d136";
+
+    let single = relation(json!({"id": "r", "type": "relation", "relation": {
+        "database_id": database_id,
+        "type": "single_property",
+        "single_property": {}
+    }}));
+    assert_eq!(
+        single.kind,
+        RelationKind::SingleProperty {
+            single_property: SingleProperty {}
+        }
+    );
+
+    let dual = relation(json!({"id": "r", "type": "relation", "relation": {
+        "database_id": database_id,
+        "type": "dual_property",
+        "dual_property": {"synced_property_name": "Tasks", "synced_property_id": "abcd"}
+    }}));
+    let synced = RelationKind::DualProperty {
+        dual_property: DualProperty {
+            synced_property_name: Some("Tasks".to_string()),
+            synced_property_id: Some("abcd".parse().unwrap()),
+        },
+    };
+    assert_eq!(dual.kind, synced);
+
+    // Before Notion-Version 2022-06-28 every relation was synced.
+    let legacy = relation(json!({"id": "r", "type": "relation", "relation": {
+        "database_id": database_id,
+        "synced_property_name": "Tasks",
+        "synced_property_id": "abcd"
+    }}));
+    assert_eq!(legacy, dual);
+
+    let json = serde_json::to_value(&dual).unwrap();
+    assert_eq!(serde_json::from_value::<Relation>(json).unwrap(), dual);
+}
@@ -1,7 +1,7 @@
 use crate::ids::{PageId, UserId};
 use crate::models::paging::{Pageable, Paging, PagingCursor};
+use crate::models::properties::DateOrDateTime;
 use crate::models::Number;
-use chrono::{DateTime, Utc};
 use serde::ser::SerializeMap;
 use serde::{Serialize, Serializer};
 
@@ -44,7 +44,7 @@ pub struct Filter {
     value: FilterValue,
 }
 
-#[derive(Serialize, Debug, Eq, PartialEq, Default)]
+#[derive(Serialize, Debug, Eq, PartialEq, Default, Clone)]
 pub struct SearchRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     query: Option<String>,
@@ -144,23 +144,23 @@ pub enum DateCondition {
     /// Only return pages where the page property value matches the provided date exactly.
     /// Note that the comparison is done against the date.
     /// Any time information sent will be ignored.
-    Equals(DateTime<Utc>),
+    Equals(DateOrDateTime),
     /// Only return pages where the page property value is before the provided date.
     /// Note that the comparison is done against the date.
     /// Any time information sent will be ignored.
-    Before(DateTime<Utc>),
+    Before(DateOrDateTime),
     /// Only return pages where the page property value is after the provided date.
     /// Note that the comparison is done against the date.
     /// Any time information sent will be ignored.
-    After(DateTime<Utc>),
+    After(DateOrDateTime),
     /// Only return pages where the page property value is on or before the provided date.
     /// Note that the comparison is done against the date.
     /// Any time information sent will be ignored.
-    OnOrBefore(DateTime<Utc>),
+    OnOrBefore(DateOrDateTime),
     /// Only return pages where the page property value is on or after the provided date.
     /// Note that the comparison is done against the date.
     /// Any time information sent will be ignored.
-    OnOrAfter(DateTime<Utc>),
+    OnOrAfter(DateOrDateTime),
     /// Only return pages where the page property value is empty.
     #[serde(serialize_with = "serialize_to_true")]
     IsEmpty,
@@ -176,6 +176,9 @@ pub enum DateCondition {
     /// Only return pages where the page property value is within the past year.
     #[serde(serialize_with = "serialize_to_empty_object")]
     PastYear,
+    /// Only return pages where the page property value is within the current week.
+    #[serde(serialize_with = "serialize_to_empty_object")]
+    ThisWeek,
     /// Only return pages where the page property value is within the next week.
     #[serde(serialize_with = "serialize_to_empty_object")]
     NextWeek,
@@ -247,7 +250,11 @@ pub enum FormulaCondition {
 #[derive(Serialize, Debug, Eq, PartialEq, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum PropertyCondition {
+    Title(TextCondition),
     RichText(TextCondition),
+    Url(TextCondition),
+    Email(TextCondition),
+    PhoneNumber(TextCondition),
     Number(NumberCondition),
     Checkbox(CheckboxCondition),
     Select(SelectCondition),
@@ -259,6 +266,14 @@ pub enum PropertyCondition {
     Formula(FormulaCondition),
 }
 
+/// Filters pages by when they were created or last edited, rather than by a property.
+#[derive(Serialize, Debug, Eq, PartialEq, Clone)]
+#[serde(tag = "timestamp", rename_all = "snake_case")]
+pub enum TimestampCondition {
+    CreatedTime { created_time: DateCondition },
+    LastEditedTime { last_edited_time: DateCondition },
+}
+
 #[derive(Serialize, Debug, Eq, PartialEq, Clone)]
 #[serde(untagged)]
 pub enum FilterCondition {
@@ -267,6 +282,10 @@ pub enum FilterCondition {
         #[serde(flatten)]
         condition: PropertyCondition,
     },
+    Timestamp {
+        #[serde(flatten)]
+        condition: TimestampCondition,
+    },
     /// Returns pages when **all** of the filters inside the provided vector match.
     And { and: Vec<FilterCondition> },
     /// Returns pages when **any** of the filters inside the provided vector match.
@@ -303,6 +322,139 @@ pub struct DatabaseQuery {
     pub paging: Option<Paging>,
 }
 
+impl SearchRequest {
+    /// Sets the page size and starting cursor of the search.
+    pub fn with_paging(
+        self,
+        paging: Paging,
+    ) -> Self {
+        SearchRequest {
+            paging: Some(paging),
+            ..self
+        }
+    }
+
+    /// Only returns pages, leaving out databases.
+    pub fn filter_by_pages(self) -> Self {
+        self.filter_by(FilterValue::Page)
+    }
+
+    /// Only returns databases, leaving out pages.
+    pub fn filter_by_databases(self) -> Self {
+        self.filter_by(FilterValue::Database)
+    }
+
+    /// Returns the least recently edited results first.
+    pub fn sort_ascending(self) -> Self {
+        self.sort_by_last_edited_time(SortDirection::Ascending)
+    }
+
+    /// Returns the most recently edited results first.
+    pub fn sort_descending(self) -> Self {
+        self.sort_by_last_edited_time(SortDirection::Descending)
+    }
+
+    fn filter_by(
+        self,
+        value: FilterValue,
+    ) -> Self {
+        SearchRequest {
+            filter: Some(Filter {
+                property: FilterProperty::Object,
+                value,
+            }),
+            ..self
+        }
+    }
+
+    fn sort_by_last_edited_time(
+        self,
+        direction: SortDirection,
+    ) -> Self {
+        SearchRequest {
+            sort: Some(Sort {
+                timestamp: SortTimestamp::LastEditedTime,
+                direction,
+            }),
+            ..self
+        }
+    }
+}
+
+impl Pageable for SearchRequest {
+    fn start_from(
+        self,
+        starting_point: Option<PagingCursor>,
+    ) -> Self {
+        SearchRequest {
+            paging: Some(Paging {
+                start_cursor: starting_point,
+                page_size: self.paging.and_then(|p| p.page_size),
+            }),
+            ..self
+        }
+    }
+}
+
+impl DatabaseSort {
+    /// Sorts by the value of the property called `property`.
+    pub fn by_property<S: Into<String>>(
+        property: S,
+        direction: SortDirection,
+    ) -> Self {
+        DatabaseSort {
+            property: Some(property.into()),
+            timestamp: None,
+            direction,
+        }
+    }
+
+    /// Sorts by when the pages were created.
+    pub fn by_created_time(direction: SortDirection) -> Self {
+        DatabaseSort {
+            property: None,
+            timestamp: Some(DatabaseSortTimestamp::CreatedTime),
+            direction,
+        }
+    }
+
+    /// Sorts by when the pages were last edited.
+    pub fn by_last_edited_time(direction: SortDirection) -> Self {
+        DatabaseSort {
+            property: None,
+            timestamp: Some(DatabaseSortTimestamp::LastEditedTime),
+            direction,
+        }
+    }
+}
+
+impl DatabaseQuery {
+    /// Adds a sort to the query.
+    /// Earlier sorts take precedence, later ones order pages the earlier ones consider equal.
+    pub fn with_sort(
+        self,
+        sort: DatabaseSort,
+    ) -> Self {
+        let mut sorts = self.sorts.unwrap_or_default();
+        sorts.push(sort);
+        DatabaseQuery {
+            sorts: Some(sorts),
+            ..self
+        }
+    }
+
+    /// Sets the page size and starting cursor of the query.
+    pub fn with_paging(
+        self,
+        paging: Paging,
+    ) -> Self {
+        DatabaseQuery {
+            paging: Some(paging),
+            ..self
+        }
+    }
+}
+
 impl Pageable for DatabaseQuery {
     fn start_from(
         self,
@@ -343,12 +495,33 @@ pub enum NotionSearch {
 }
 
 impl NotionSearch {
+    /// Starts a search for pages and databases whose title matches `query`,
+    /// which can be narrowed down further.
+    ///
+    /// ```
+    /// use notion::models::search::{NotionSearch, SearchRequest};
+    ///
+    /// let request: SearchRequest = NotionSearch::query("roadmap")
+    ///     .filter_by_pages()
+    ///     .sort_descending();
+    /// ```
+    pub fn query<S: Into<String>>(query: S) -> SearchRequest {
+        SearchRequest::from(NotionSearch::Query(query.into()))
+    }
+
     pub fn filter_by_databases() -> Self {
         Self::Filter {
             property: FilterProperty::Object,
             value: FilterValue::Database,
         }
     }
+
+    pub fn filter_by_pages() -> Self {
+        Self::Filter {
+            property: FilterProperty::Object,
+            value: FilterValue::Page,
+        }
+    }
 }
 
 impl From<NotionSearch> for SearchRequest {
@@ -379,10 +552,14 @@ impl From<NotionSearch> for SearchRequest {
 #[cfg(test)]
 mod tests {
     mod text_filters {
-        use crate::models::search::PropertyCondition::{Checkbox, Number, RichText, Select};
+        use crate::models::search::PropertyCondition::{
+            Checkbox, Email, Number, PhoneNumber, RichText, Select, Title, Url,
+        };
         use crate::models::search::{
-            CheckboxCondition, FilterCondition, NumberCondition, SelectCondition, TextCondition,
+            CheckboxCondition, DateCondition, FilterCondition, NumberCondition, SelectCondition,
+            TextCondition, TimestampCondition,
         };
+        use chrono::{DateTime, Utc};
         use serde_json::json;
 
         #[test]
@@ -441,6 +618,42 @@ mod tests {
             Ok(())
         }
 
+        #[test]
+        fn text_like_properties() -> Result<(), Box<dyn std::error::Error>> {
+            let conditions = vec![
+                (
+                    "Name",
+                    Title(TextCondition::StartsWith("Tuscan".to_string())),
+                ),
+                ("Site", Url(TextCondition::EndsWith(".org".to_string()))),
+                (
+                    "Contact",
+                    Email(TextCondition::Contains("@example.com".to_string())),
+                ),
+                ("Phone", PhoneNumber(TextCondition::IsNotEmpty)),
+            ];
+            let json: Vec<_> = conditions
+                .into_iter()
+                .map(|(property, condition)| {
+                    serde_json::to_value(&FilterCondition::Property {
+                        property: property.to_string(),
+                        condition,
+                    })
+                })
+                .collect::<Result<_, _>>()?;
+            assert_eq!(
+                json,
+                vec![
+                    json!({"property":"Name","title":{"starts_with":"Tuscan"}}),
+                    json!({"property":"Site","url":{"ends_with":".org"}}),
+                    json!({"property":"Contact","email":{"contains":"@example.com"}}),
+                    json!({"property":"Phone","phone_number":{"is_not_empty":true}}),
+                ]
+            );
+
+            Ok(())
+        }
+
         #[test]
         fn compound_query_and() -> Result<(), Box<dyn std::error::Error>> {
             let json = serde_json::to_value(&FilterCondition::And {
@@ -505,5 +718,154 @@ mod tests {
 
             Ok(())
         }
+
+        #[test]
+        fn compound_query_with_timestamp() -> Result<(), Box<dyn std::error::Error>> {
+            let json = serde_json::to_value(&FilterCondition::And {
+                and: vec![
+                    FilterCondition::Timestamp {
+                        condition: TimestampCondition::LastEditedTime {
+                            last_edited_time: DateCondition::OnOrAfter(
+                                "2022-10-13T00:00:00Z".parse::<DateTime<Utc>>()?.into(),
+                            ),
+                        },
+                    },
+                    FilterCondition::Timestamp {
+                        condition: TimestampCondition::CreatedTime {
+                            created_time: DateCondition::PastWeek,
+                        },
+                    },
+                    FilterCondition::Property {
+                        property: "Seen".to_string(),
+                        condition: Checkbox(CheckboxCondition::Equals(false)),
+                    },
+                ],
+            })?;
+            assert_eq!(
+                dbg!(json),
+                json!({"and":[
+                    {"timestamp":"last_edited_time","last_edited_time":{"on_or_after":"2022-10-13T00:00:00Z"}},
+                    {"timestamp":"created_time","created_time":{"past_week":{}}},
+                    {"property":"Seen","checkbox":{"equals":false}}
+                ]})
+            );
+
+            Ok(())
+        }
+    }
+
+    mod date_filters {
+        use crate::models::properties::DateOrDateTime;
+        use crate::models::search::{DateCondition, FilterCondition, PropertyCondition};
+        use chrono::NaiveDate;
+        use serde_json::json;
+
+        #[test]
+        fn date_only_condition() -> Result<(), Box<dyn std::error::Error>> {
+            let json = serde_json::to_value(&FilterCondition::Property {
+                property: "Due".to_string(),
+                condition: PropertyCondition::Date(DateCondition::Before(DateOrDateTime::Date(
+                    NaiveDate::from_ymd_opt(2022, 6, 1).unwrap(),
+                ))),
+            })?;
+            assert_eq!(
+                json,
+                json!({"property":"Due","date":{"before":"2022-06-01"}})
+            );
+
+            let json = serde_json::to_value(&FilterCondition::Property {
+                property: "Due".to_string(),
+                condition: PropertyCondition::Date(DateCondition::ThisWeek),
+            })?;
+            assert_eq!(json, json!({"property":"Due","date":{"this_week":{}}}));
+
+            Ok(())
+        }
+    }
+
+    mod sorts {
+        use crate::models::search::{DatabaseQuery, DatabaseSort, SortDirection};
+        use serde_json::json;
+
+        #[test]
+        fn query_with_several_sorts() -> Result<(), Box<dyn std::error::Error>> {
+            let query = DatabaseQuery::default()
+                .with_sort(DatabaseSort::by_property("Due", SortDirection::Ascending))
+                .with_sort(DatabaseSort::by_created_time(SortDirection::Descending))
+                .with_sort(DatabaseSort::by_last_edited_time(SortDirection::Ascending));
+
+            assert_eq!(
+                serde_json::to_value(&query)?,
+                json!({"sorts": [
+                    {"property": "Due", "direction": "ascending"},
+                    {"timestamp": "created_time", "direction": "descending"},
+                    {"timestamp": "last_edited_time", "direction": "ascending"},
+                ]})
+            );
+
+            Ok(())
+        }
+    }
+
+    mod search {
+        use crate::models::search::{NotionSearch, SearchRequest};
+        use serde_json::json;
+
+        #[test]
+        fn combines_query_filter_and_sort() -> Result<(), Box<dyn std::error::Error>> {
+            let request = NotionSearch::query("roadmap")
+                .filter_by_pages()
+                .sort_descending();
+
+            assert_eq!(
+                serde_json::to_value(&request)?,
+                json!({
+                    "query": "roadmap",
+                    "filter": {"property": "object", "value": "page"},
+                    "sort": {"timestamp": "last_edited_time", "direction": "descending"}
+                })
+            );
+            assert_eq!(
+                SearchRequest::from(NotionSearch::filter_by_databases()).sort_ascending(),
+                SearchRequest::default()
+                    .sort_ascending()
+                    .filter_by_pages()
+                    .filter_by_databases()
+            );
+
+            Ok(())
+        }
+    }
+
+    mod paging {
+        use crate::models::paging::{Paging, PagingCursor};
+        use crate::models::search::{DatabaseQuery, NotionSearch, SearchRequest};
+        use serde_json::json;
+
+        #[test]
+        fn search_with_paging() -> Result<(), Box<dyn std::error::Error>> {
+            let request = SearchRequest::from(NotionSearch::Query("roadmap".to_string()))
+                .with_paging(
+                    Paging::new()
+                        .with_page_size(10)
+                        .with_start_cursor(PagingCursor::new("fe2cc560".to_string())),
+                );
+
+            assert_eq!(
+                serde_json::to_value(&request)?,
+                json!({"query": "roadmap", "start_cursor": "fe2cc560", "page_size": 10})
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn query_with_page_size() -> Result<(), Box<dyn std::error::Error>> {
+            let query = DatabaseQuery::default().with_paging(Paging::new().with_page_size(100));
+
+            assert_eq!(serde_json::to_value(&query)?, json!({"page_size": 100}));
+
+            Ok(())
+        }
     }
 }
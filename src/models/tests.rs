@@ -1,16 +1,132 @@
-use crate::ids::UserId;
-use crate::models::properties::{DateOrDateTime, DateValue};
+use crate::ids::{DatabaseId, PageId, UserId};
+use crate::models::block::{
+    self, CreateBlock, FileFields, FileObject, TextAndChildren, ToDoFields,
+};
+use crate::models::properties::{
+    Color, CreatePropertyConfiguration, CreatePropertyValue, CreateSelectOption, DateOrDateTime,
+    DateValue, NumberFormat, PaginatedPropertyItem, PropertyItem, PropertyValue, RollupFunction,
+};
 use crate::models::text::{
-    Annotations, Link, MentionObject, RichText, RichTextCommon, Text, TextColor,
+    Annotations, Link, MentionObject, RichText, RichTextBuilder, RichTextCommon, TemplateMention,
+    TemplateMentionDate, TemplateMentionUser, Text, TextColor,
+};
+use crate::models::unfurl::{Unfurl, UnfurlAttribute, UnfurlSection};
+use crate::models::users::{MentionedUser, Person, User, UserCommon};
+use crate::models::{
+    borrowed, Database, DatabaseCreateRequest, IconObject, ListResponse, Object, Page,
+    PageCreateRequestBuilder, PageUpdateRequest, Parent, SearchResult,
 };
-use crate::models::users::{Person, User, UserCommon};
-use crate::models::{ListResponse, Object, Page};
 use chrono::{DateTime, NaiveDate};
+use serde_json::json;
+use std::borrow::Cow;
 use std::str::FromStr;
 
 #[test]
 fn deserialize_page() {
-    let _page: Page = serde_json::from_str(include_str!("tests/page.json")).unwrap();
+    let page: Page = serde_json::from_str(include_str!("tests/page.json")).unwrap();
+    assert_eq!(
+        page.cover,
+        Some(FileObject::external(
+            "https://upload.wikimedia.org/wikipedia/commons/6/62/Tuscankale.jpg"
+        ))
+    );
+    assert_eq!(
+        page.url.as_deref(),
+        Some("https://www.notion.so/Stuff-b55c9c91384d452b81dbd1ef79372b75")
+    );
+    assert_eq!(page.public_url, None);
+    assert_eq!(
+        page.created_by.map(|user| user.id.to_string()).as_deref(),
+        Some("6419f912-5293-4ea8-b2c8-9c3ce44f90e3")
+    );
+    assert_eq!(
+        page.last_edited_by
+            .map(|user| user.id.to_string())
+            .as_deref(),
+        Some("e2507360-468c-4e0f-a928-7bbcbbb45353")
+    );
+}
+
+#[test]
+fn display_title_includes_emoji_icon() {
+    let mut page: Page = serde_json::from_str(include_str!("tests/page.json")).unwrap();
+    let title = page.title().unwrap();
+    assert_eq!(page.display_title(), title);
+
+    page.icon = Some(IconObject::Emoji {
+        emoji: "🥬".to_string(),
+    });
+    assert_eq!(page.display_title(), format!("🥬 {}", title));
+
+    page.icon = Some(IconObject::File {
+        file: FileObject::external("https://example.com/kale.png"),
+    });
+    assert_eq!(page.display_title(), title);
+}
+
+#[test]
+fn deserialize_database() {
+    let database: Database = serde_json::from_str(include_str!("tests/database.json")).unwrap();
+
+    assert_eq!(database.title_plain_text(), "Grocery List");
+    assert_eq!(
+        database.parent,
+        Parent::Page {
+            page_id: PageId::from_str("98ad959b-2b6a-4774-80ee-00246fb0ea9b").unwrap()
+        }
+    );
+    assert_eq!(
+        database
+            .description
+            .iter()
+            .map(|text| text.plain_text())
+            .collect::<String>(),
+        "Grocery list for just kale 🥬"
+    );
+    assert_eq!(
+        database.created_by.map(|user| user.id),
+        Some(UserId::from_str("6419f912-5293-4ea8-b2c8-9c3ce44f90e3").unwrap())
+    );
+    assert_eq!(
+        database.last_edited_by.map(|user| user.id),
+        Some(UserId::from_str("e2507360-468c-4e0f-a928-7bbcbbb45353").unwrap())
+    );
+    assert_eq!(
+        database.cover,
+        Some(FileObject::external(
+            "https://website.domain/images/image.png"
+        ))
+    );
+    assert!(database.is_inline);
+    assert!(!database.archived);
+    assert_eq!(
+        database.url.as_deref(),
+        Some("https://www.notion.so/bc1211cae3f14939ae34260b16f627c")
+    );
+}
+
+#[test]
+fn serializes_properties_in_name_order() {
+    let mut page: serde_json::Value =
+        serde_json::from_str(include_str!("tests/page.json")).unwrap();
+    let names = ["Zebra", "apple", "Mango", "Banana", "cherry"];
+    for name in names {
+        page["properties"][name] = json!({"id": name, "type": "checkbox", "checkbox": true});
+    }
+    let page: Page = serde_json::from_value(page).unwrap();
+
+    let json = serde_json::to_string(&page).unwrap();
+    // A page read back from the json has its own hash order.
+    let read_back: Page = serde_json::from_str(&json).unwrap();
+    assert_eq!(json, serde_json::to_string(&read_back).unwrap());
+
+    let mut expected: Vec<_> = page.properties.properties.keys().collect();
+    expected.sort();
+    let positions: Vec<_> = expected
+        .iter()
+        .map(|name| json.find(&format!("\"{}\":{{", name)).unwrap())
+        .collect();
+    assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
 }
 
 #[test]
@@ -19,12 +135,81 @@ fn deserialize_query_result() {
         serde_json::from_str(include_str!("tests/query_result.json")).unwrap();
 }
 
+#[test]
+fn list_response_conversions() {
+    let list: ListResponse<Object> =
+        serde_json::from_str(include_str!("tests/query_result.json")).unwrap();
+    let count = list.results.len();
+
+    assert!(matches!(
+        list.clone().into_databases(),
+        Err(crate::Error::UnexpectedResponse { .. })
+    ));
+    let pages = list.into_pages().unwrap();
+    assert_eq!((&pages).into_iter().count(), count);
+
+    let ids = pages.map(|page| page.id);
+    assert_eq!(ids.iter().count(), count);
+    assert_eq!(ids.into_iter().count(), count);
+}
+
 #[test]
 fn deserialize_number_format() {
     let _search_results: ListResponse<Object> =
         serde_json::from_str(include_str!("tests/issue_15.json")).unwrap();
 }
 
+#[test]
+fn search_results_are_pages_or_databases() {
+    let object: Object = serde_json::from_str(include_str!("tests/search_results.json")).unwrap();
+    let results: ListResponse<SearchResult> = match object {
+        Object::List { list } => list.into_search_results().unwrap(),
+        object => panic!("Expected a list, got {:?}", object),
+    };
+
+    assert!(results.results[0].is_database());
+    assert!(results.results[1].is_page());
+    assert!(results
+        .results
+        .iter()
+        .all(|result| matches!(result.parent(), Parent::Page { .. })));
+    assert_eq!(results.clone().only_pages().results.len(), 1);
+    assert_eq!(results.only_databases().results.len(), 1);
+}
+
+#[test]
+fn deserialize_property_item_list() {
+    let object: Object =
+        serde_json::from_str(include_str!("tests/property_item_list.json")).unwrap();
+    let list = match object {
+        Object::List { list } => list.into_property_items().unwrap(),
+        object => panic!("Expected a list, got {:?}", object),
+    };
+
+    assert!(list.has_more);
+    assert_eq!(list.results.len(), 2);
+    assert!(matches!(
+        &list.results[1],
+        PropertyItem::Paginated(PaginatedPropertyItem::Title { title, .. }) if title.plain_text() == "Toast"
+    ));
+}
+
+#[test]
+fn deserialize_property_item_value() {
+    let object: Object =
+        serde_json::from_str(include_str!("tests/property_item_number.json")).unwrap();
+
+    assert!(matches!(
+        object,
+        Object::PropertyItem {
+            item: PropertyItem::Value(PropertyValue::Number {
+                number: Some(_),
+                ..
+            })
+        }
+    ));
+}
+
 #[test]
 fn rich_text() {
     let rich_text_text: RichText =
@@ -74,7 +259,7 @@ fn rich_text_mention_user_person() {
         }),
       },
       mention: MentionObject::User {
-        user: User::Person {
+        user: MentionedUser::User(User::Person {
           common: UserCommon {
             id: UserId::from_str("1118608e-35e8-4fa3-aef7-a4ced85ce8e0").unwrap(),
             name: Some("John Doe".to_string()),
@@ -86,7 +271,7 @@ fn rich_text_mention_user_person() {
           person: Person {
             email: "john.doe@gmail.com".to_string()
           },
-        }
+        })
       },
     }
   )
@@ -222,3 +407,456 @@ fn rich_text_mention_date_with_end_and_time() {
         }
     )
 }
+
+#[test]
+fn rich_text_mention_link_preview() {
+    let rich_text: RichText =
+        serde_json::from_str(include_str!("tests/rich_text_mention_link_preview.json")).unwrap();
+    let url = "https://github.com/jakeswenson/notion/pull/42";
+    assert_eq!(rich_text.plain_text(), url);
+    assert!(matches!(
+        rich_text,
+        RichText::Mention {
+            mention: MentionObject::LinkPreview { link_preview },
+            ..
+        } if link_preview.url == url
+    ));
+}
+
+#[test]
+fn rich_text_mention_template() {
+    let today: RichText =
+        serde_json::from_str(include_str!("tests/rich_text_mention_template_date.json")).unwrap();
+    let me: RichText =
+        serde_json::from_str(include_str!("tests/rich_text_mention_template_user.json")).unwrap();
+
+    assert!(matches!(
+        today,
+        RichText::Mention {
+            mention: MentionObject::TemplateMention {
+                template_mention: TemplateMention::Date {
+                    template_mention_date: TemplateMentionDate::Today
+                }
+            },
+            ..
+        }
+    ));
+    assert!(matches!(
+        &me,
+        RichText::Mention {
+            mention: MentionObject::TemplateMention {
+                template_mention: TemplateMention::User {
+                    template_mention_user: TemplateMentionUser::Me
+                }
+            },
+            ..
+        }
+    ));
+    assert_eq!(
+        serde_json::to_value(&me).unwrap()["mention"],
+        json!({
+            "type": "template_mention",
+            "template_mention": {
+                "type": "template_mention_user",
+                "template_mention_user": "me"
+            }
+        })
+    );
+}
+
+#[test]
+fn serialize_database_create_request() {
+    let parent = PageId::from_str("98ad959b-2b6a-4774-80ee-00246fb0ea9b").unwrap();
+    let related = DatabaseId::from_str("668d797c-76fa-4934-9b05-ad288df2d136").unwrap();
+    let request = DatabaseCreateRequest::new(
+        parent,
+        vec![RichText::Text {
+            rich_text: RichTextCommon {
+                plain_text: "Grocery List".to_string(),
+                href: None,
+                annotations: None,
+            },
+            text: Text {
+                content: "Grocery List".to_string(),
+                link: None,
+            },
+        }],
+    )
+    .property("Name", CreatePropertyConfiguration::title())
+    .property("In stock", CreatePropertyConfiguration::Checkbox {})
+    .property(
+        "Price",
+        CreatePropertyConfiguration::number(NumberFormat::Dollar),
+    )
+    .property(
+        "Food group",
+        CreatePropertyConfiguration::select(vec![
+            CreateSelectOption::new("🥦Vegetable").with_color(Color::Green),
+            "🍎Fruit".into(),
+        ]),
+    )
+    .property(
+        "Recipes",
+        CreatePropertyConfiguration::relation(related.clone()),
+    )
+    .property(
+        "Featured in",
+        CreatePropertyConfiguration::single_property_relation(related),
+    )
+    .property(
+        "Cost of recipes",
+        CreatePropertyConfiguration::rollup("Recipes", "Price", RollupFunction::Sum),
+    );
+
+    assert_eq!(
+        serde_json::to_value(&request).unwrap(),
+        json!({
+            "parent": {"type": "page_id", "page_id": "98ad959b-2b6a-4774-80ee-00246fb0ea9b"},
+            "title": [{"type": "text", "plain_text": "Grocery List", "text": {"content": "Grocery List", "link": null}}],
+            "properties": {
+                "Name": {"title": {}},
+                "In stock": {"checkbox": {}},
+                "Price": {"number": {"format": "dollar"}},
+                "Food group": {"select": {"options": [
+                    {"name": "🥦Vegetable", "color": "green"},
+                    {"name": "🍎Fruit"}
+                ]}},
+                "Recipes": {"relation": {
+                    "database_id": "668d797c-76fa-4934-9b05-ad288df2d136",
+                    "type": "dual_property",
+                    "dual_property": {}
+                }},
+                "Featured in": {"relation": {
+                    "database_id": "668d797c-76fa-4934-9b05-ad288df2d136",
+                    "type": "single_property",
+                    "single_property": {}
+                }},
+                "Cost of recipes": {"rollup": {
+                    "relation_property_name": "Recipes",
+                    "rollup_property_name": "Price",
+                    "function": "sum"
+                }}
+            }
+        })
+    );
+}
+
+#[test]
+fn serialize_page_create_request_builder() {
+    let database = DatabaseId::from_str("668d797c-76fa-4934-9b05-ad288df2d136").unwrap();
+    let project = PageId::from_str("98ad959b-2b6a-4774-80ee-00246fb0ea9b").unwrap();
+    let other = PageId::from_str("b5a3a8d3-3a3c-4d6b-9d8e-0c2f1e6a7b90").unwrap();
+    let request = PageCreateRequestBuilder::in_database(&database)
+        .title("Tuscan kale")
+        .select("Status", "Done")
+        .multi_select("Tags", vec!["Vegetable", "Green"])
+        .number("Price", 3)
+        .date("Due", NaiveDate::from_ymd_opt(2022, 6, 1).unwrap())
+        .relation("Project", &project)
+        .relation("Project", &other)
+        .people(
+            "Owner",
+            vec![UserId::from_str("6419f912-5293-4ea8-b2c8-9c3ce44f90e3").unwrap()],
+        )
+        .checkbox("In stock", true)
+        .icon("🥬")
+        .cover(FileObject::external("https://example.com/kale.jpg"))
+        .build();
+
+    assert_eq!(
+        serde_json::to_value(&request).unwrap(),
+        json!({
+            "parent": {"type": "database_id", "database_id": "668d797c-76fa-4934-9b05-ad288df2d136"},
+            "properties": {
+                "title": {"title": [
+                    {"type": "text", "plain_text": "Tuscan kale", "text": {"content": "Tuscan kale", "link": null}}
+                ]},
+                "Status": {"select": {"name": "Done"}},
+                "Tags": {"multi_select": [{"name": "Vegetable"}, {"name": "Green"}]},
+                "Price": {"number": 3},
+                "Due": {"date": {"start": "2022-06-01", "end": null, "time_zone": null}},
+                "Project": {"relation": [
+                    {"id": "98ad959b-2b6a-4774-80ee-00246fb0ea9b"},
+                    {"id": "b5a3a8d3-3a3c-4d6b-9d8e-0c2f1e6a7b90"}
+                ]},
+                "Owner": {"people": [{"id": "6419f912-5293-4ea8-b2c8-9c3ce44f90e3"}]},
+                "In stock": {"checkbox": true},
+            },
+            "icon": {"type": "emoji", "emoji": "🥬"},
+            "cover": {"type": "external", "external": {"url": "https://example.com/kale.jpg"}}
+        })
+    );
+}
+
+#[test]
+fn serialize_page_update_request() {
+    let project = PageId::from_str("98ad959b-2b6a-4774-80ee-00246fb0ea9b").unwrap();
+    let other = PageId::from_str("b5a3a8d3-3a3c-4d6b-9d8e-0c2f1e6a7b90").unwrap();
+    let request = PageUpdateRequest::default()
+        .property(
+            "Projects",
+            CreatePropertyValue::relation([&project, &other]),
+        )
+        .property(
+            "Blocked by",
+            CreatePropertyValue::relation(Vec::<PageId>::new()),
+        );
+
+    assert_eq!(
+        serde_json::to_value(&request).unwrap(),
+        json!({
+            "properties": {
+                "Blocked by": {"relation": []},
+                "Projects": {"relation": [
+                    {"id": "98ad959b-2b6a-4774-80ee-00246fb0ea9b"},
+                    {"id": "b5a3a8d3-3a3c-4d6b-9d8e-0c2f1e6a7b90"}
+                ]}
+            }
+        })
+    );
+}
+
+#[test]
+fn serialize_create_page_request() {
+    let database = DatabaseId::from_str("d9824bdc-8445-4327-be8b-5b47500af6ce").unwrap();
+    let request = PageCreateRequestBuilder::in_database(&database)
+        .title("Tuscan kale")
+        .text("Description", "A dark green leafy vegetable")
+        .select("Food group", "Vegetable")
+        .number("Price", 2)
+        .icon("🥬")
+        .cover(FileObject::external(
+            "https://upload.wikimedia.org/wikipedia/commons/6/62/Tuscankale.jpg",
+        ))
+        .children(vec![
+            CreateBlock::Heading2 {
+                heading_2: block::Text {
+                    rich_text: vec![RichText::text("Lacinato kale")],
+                },
+            },
+            CreateBlock::Divider,
+        ])
+        .build();
+
+    assert_eq!(
+        serde_json::to_value(&request).unwrap(),
+        serde_json::from_str::<serde_json::Value>(include_str!("tests/create_page_request.json"))
+            .unwrap()
+    );
+}
+
+#[test]
+fn serialize_append_block_children_request() {
+    let text = |content: &str| vec![RichText::text(content)];
+    let children = vec![
+        CreateBlock::Heading2 {
+            heading_2: block::Text {
+                rich_text: text("Lacinato kale"),
+            },
+        },
+        CreateBlock::Paragraph {
+            paragraph: TextAndChildren {
+                rich_text: vec![RichTextBuilder::text("Lacinato kale")
+                    .link("https://en.wikipedia.org/wiki/Lacinato_kale")
+                    .into()],
+                children: None,
+                color: TextColor::Default,
+            },
+        },
+        CreateBlock::ToDo {
+            to_do: ToDoFields {
+                rich_text: text("Harvest"),
+                checked: false,
+                children: None,
+                color: TextColor::Default,
+            },
+        },
+        CreateBlock::File {
+            file: FileFields {
+                file: FileObject::external("https://example.com/kale.pdf"),
+                caption: text("Growing guide"),
+            },
+        },
+        CreateBlock::Divider,
+        CreateBlock::Breadcrumb,
+        CreateBlock::equation("e=mc^2"),
+    ];
+
+    assert_eq!(
+        json!({ "children": children }),
+        serde_json::from_str::<serde_json::Value>(include_str!(
+            "tests/append_block_children_request.json"
+        ))
+        .unwrap()
+    );
+}
+
+#[test]
+fn serialize_rich_text_builder() {
+    let text: Vec<RichText> = vec![
+        RichTextBuilder::text("docs")
+            .bold()
+            .italic()
+            .link("https://developers.notion.com")
+            .into(),
+        RichTextBuilder::mention_user(
+            UserId::from_str("1118608e-35e8-4fa3-aef7-a4ced85ce8e0").unwrap(),
+        )
+        .into(),
+        RichTextBuilder::equation("e=mc^2")
+            .color(TextColor::Red)
+            .into(),
+    ];
+
+    assert_eq!(
+        serde_json::to_value(&text).unwrap(),
+        json!([
+            {
+                "type": "text",
+                "plain_text": "docs",
+                "annotations": {"bold": true, "italic": true},
+                "text": {"content": "docs", "link": {"url": "https://developers.notion.com"}}
+            },
+            {
+                "type": "mention",
+                "mention": {"type": "user", "user": {"id": "1118608e-35e8-4fa3-aef7-a4ced85ce8e0"}}
+            },
+            {
+                "type": "equation",
+                "plain_text": "e=mc^2",
+                "annotations": {"color": "red"},
+                "equation": {"expression": "e=mc^2"}
+            }
+        ])
+    );
+}
+
+#[test]
+fn deserialize_rich_text_equation_and_partial_user() {
+    let text: Vec<RichText> = serde_json::from_value(json!([
+        {
+            "type": "equation",
+            "plain_text": "e=mc^2",
+            "href": null,
+            "equation": {"expression": "e=mc^2"}
+        },
+        {
+            "type": "mention",
+            "plain_text": "@Anonymous",
+            "href": null,
+            "mention": {"type": "user", "user": {"object": "user", "id": "1118608e-35e8-4fa3-aef7-a4ced85ce8e0"}}
+        }
+    ]))
+    .unwrap();
+
+    assert!(matches!(
+        &text[0],
+        RichText::Equation { equation, .. } if equation.expression == "e=mc^2"
+    ));
+    assert!(matches!(
+        &text[1],
+        RichText::Mention {
+            mention: MentionObject::User {
+                user: MentionedUser::Partial { .. }
+            },
+            ..
+        }
+    ));
+}
+
+#[test]
+fn deserialize_borrowed_query_result() {
+    let json = include_str!("tests/query_result.json");
+    let pages: borrowed::ListResponse<borrowed::Page> = serde_json::from_str(json).unwrap();
+    let owned: ListResponse<Page> = serde_json::from_str(json).unwrap();
+
+    let page = &pages.results[0];
+    assert!(matches!(page.id, Cow::Borrowed(_)));
+    assert_eq!(page.id, owned.results[0].id.to_string());
+    assert_eq!(page.title().unwrap(), owned.results[0].title());
+    assert_eq!(
+        page.property("Name").unwrap().as_ref(),
+        owned.results[0].properties.properties.get("Name")
+    );
+    assert_eq!(page.property("Missing").unwrap(), None);
+}
+
+#[test]
+fn serialize_unfurl() {
+    let unfurl = Unfurl::new("https://example.com/tasks/42", "Fix the build", "Acme")
+        .attribute(UnfurlAttribute::plain_text(
+            "state",
+            "State",
+            "Open",
+            UnfurlSection::Primary,
+        ))
+        .attribute(UnfurlAttribute::embed(
+            "media",
+            "Embed",
+            "https://example.com/tasks/42/embed",
+        ));
+
+    assert_eq!(
+        serde_json::to_value(&unfurl).unwrap(),
+        json!({
+            "uri": "https://example.com/tasks/42",
+            "operations": [{
+                "path": "attributes",
+                "set": [
+                    {"id": "title", "name": "Title", "type": "inline",
+                        "inline": {"title": {"value": "Fix the build", "section": "title"}}},
+                    {"id": "dev", "name": "Developer Name", "type": "inline",
+                        "inline": {"plain_text": {"value": "Acme", "section": "secondary"}}},
+                    {"id": "state", "name": "State", "type": "inline",
+                        "inline": {"plain_text": {"value": "Open", "section": "primary"}}},
+                    {"id": "media", "name": "Embed", "type": "embed",
+                        "embed": {"src_url": "https://example.com/tasks/42/embed"}},
+                ]
+            }]
+        })
+    );
+    assert_eq!(
+        serde_json::from_value::<Unfurl>(serde_json::to_value(&unfurl).unwrap()).unwrap(),
+        unfurl
+    );
+}
+
+#[test]
+fn deserialize_partial_users() {
+    let people: PropertyValue = serde_json::from_value(json!({
+        "id": "p",
+        "type": "people",
+        "people": [
+            {"object": "user", "id": "6419f912-5293-4ea8-b2c8-9c3ce44f90e3"},
+            {
+                "object": "user",
+                "id": "e2507360-468c-4e0f-a928-7bbcbbb45353",
+                "type": "person",
+                "name": "John Doe",
+                "avatar_url": null,
+                "person": {"email": "john@example.com"}
+            }
+        ]
+    }))
+    .unwrap();
+
+    let people = match people {
+        PropertyValue::People { people, .. } => people,
+        value => panic!("not people: {:?}", value),
+    };
+    assert_eq!(
+        people[0],
+        User::Partial {
+            id: UserId::from_str("6419f912-5293-4ea8-b2c8-9c3ce44f90e3").unwrap()
+        }
+    );
+    assert_eq!(people[0].name(), None);
+    assert_eq!(people[1].name(), Some("John Doe"));
+    assert_eq!(
+        serde_json::to_value(CreatePropertyValue::people(people.iter().map(User::id))).unwrap(),
+        json!({"people": [
+            {"id": "6419f912-5293-4ea8-b2c8-9c3ce44f90e3"},
+            {"id": "e2507360-468c-4e0f-a928-7bbcbbb45353"}
+        ]})
+    );
+}
@@ -1,5 +1,6 @@
+use crate::ids::UserId;
 use crate::models::properties::DateValue;
-use crate::models::users::User;
+use crate::models::users::MentionedUser;
 use crate::{Database, Page};
 use serde::{Deserialize, Serialize};
 
@@ -29,13 +30,19 @@ pub enum TextColor {
 
 /// Rich text annotations
 /// See <https://developers.notion.com/reference/rich-text#annotations>
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Default)]
 pub struct Annotations {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub bold: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub code: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub color: Option<TextColor>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub italic: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub strikethrough: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub underline: Option<bool>,
 }
 
@@ -43,6 +50,8 @@ pub struct Annotations {
 /// See <https://developers.notion.com/reference/rich-text#all-rich-text>
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 pub struct RichTextCommon {
+    /// Filled in by Notion, requests don't need to set it.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub plain_text: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub href: Option<String>,
@@ -61,13 +70,20 @@ pub struct Text {
     pub link: Option<Link>,
 }
 
+/// See <https://developers.notion.com/reference/rich-text#equation-objects>
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct Equation {
+    /// The LaTeX string representing the equation.
+    pub expression: String,
+}
+
 /// See https://developers.notion.com/reference/rich-text#mention-objects
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 #[serde(tag = "type")]
 #[serde(rename_all = "snake_case")]
 pub enum MentionObject {
     User {
-        user: User,
+        user: MentionedUser,
     },
     // TODO: need to add tests
     Page {
@@ -80,14 +96,55 @@ pub enum MentionObject {
     Date {
         date: DateValue,
     },
-    // TODO: need to add LinkPreview
-    // LinkPreview {
-    //
-    // },
+    /// A link shown as a preview of the page it links to.
+    LinkPreview {
+        link_preview: LinkPreview,
+    },
+    /// A placeholder in a database template, filled in when a page is created from it.
+    TemplateMention {
+        template_mention: TemplateMention,
+    },
     #[serde(other)]
     Unknown,
 }
 
+/// See <https://developers.notion.com/reference/rich-text#link-preview-mentions>
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct LinkPreview {
+    pub url: String,
+}
+
+/// See <https://developers.notion.com/reference/rich-text#template-mentions>
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Copy, Clone)]
+#[serde(tag = "type")]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateMention {
+    #[serde(rename = "template_mention_date")]
+    Date {
+        template_mention_date: TemplateMentionDate,
+    },
+    #[serde(rename = "template_mention_user")]
+    User {
+        template_mention_user: TemplateMentionUser,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Copy, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateMentionDate {
+    /// The date the page was created from the template.
+    Today,
+    /// The date and time the page was created from the template.
+    Now,
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Copy, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateMentionUser {
+    /// The user who created the page from the template.
+    Me,
+}
+
 /// Rich text objects contain data for displaying formatted text, mentions, and equations.
 /// A rich text object also contains annotations for style information.
 /// Arrays of rich text objects are used within property objects and property
@@ -95,6 +152,7 @@ pub enum MentionObject {
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 #[serde(tag = "type")]
 #[serde(rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
 pub enum RichText {
     /// See <https://developers.notion.com/reference/rich-text#text-objects>
     Text {
@@ -112,10 +170,27 @@ pub enum RichText {
     Equation {
         #[serde(flatten)]
         rich_text: RichTextCommon,
+        equation: Equation,
     },
 }
 
 impl RichText {
+    /// Creates an unformatted text object, e.g. for titles and text property values.
+    pub fn text<S: Into<String>>(content: S) -> Self {
+        let content = content.into();
+        RichText::Text {
+            rich_text: RichTextCommon {
+                plain_text: content.clone(),
+                href: None,
+                annotations: None,
+            },
+            text: Text {
+                content,
+                link: None,
+            },
+        }
+    }
+
     pub fn plain_text(&self) -> &str {
         use RichText::*;
         match self {
@@ -125,3 +200,128 @@ impl RichText {
         }
     }
 }
+
+/// The text of a run of rich text, without its formatting or links.
+pub fn plain_text(rich_text: &[RichText]) -> String {
+    rich_text.iter().map(RichText::plain_text).collect()
+}
+
+/// Builds a single [RichText] for use in requests, without having to fill in
+/// the fields Notion computes itself.
+///
+/// ```
+/// use notion::models::text::{RichText, RichTextBuilder, TextColor};
+///
+/// let text: Vec<RichText> = vec![
+///     RichTextBuilder::text("Read ").into(),
+///     RichTextBuilder::text("the docs")
+///         .bold()
+///         .link("https://developers.notion.com")
+///         .into(),
+///     RichTextBuilder::equation("e=mc^2").color(TextColor::Red).into(),
+/// ];
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RichTextBuilder {
+    rich_text: RichText,
+}
+
+impl RichTextBuilder {
+    /// Starts a text object with the given content.
+    pub fn text<S: Into<String>>(content: S) -> Self {
+        Self {
+            rich_text: RichText::text(content),
+        }
+    }
+
+    /// Starts a mention of the user with the given id.
+    pub fn mention_user(user_id: UserId) -> Self {
+        Self {
+            rich_text: RichText::Mention {
+                rich_text: RichTextCommon {
+                    plain_text: String::new(),
+                    href: None,
+                    annotations: None,
+                },
+                mention: MentionObject::User {
+                    user: MentionedUser::Partial { id: user_id },
+                },
+            },
+        }
+    }
+
+    /// Starts an inline equation from a LaTeX expression.
+    pub fn equation<S: Into<String>>(expression: S) -> Self {
+        let expression = expression.into();
+        Self {
+            rich_text: RichText::Equation {
+                rich_text: RichTextCommon {
+                    plain_text: expression.clone(),
+                    href: None,
+                    annotations: None,
+                },
+                equation: Equation { expression },
+            },
+        }
+    }
+
+    pub fn bold(self) -> Self {
+        self.annotate(|annotations| annotations.bold = Some(true))
+    }
+
+    pub fn italic(self) -> Self {
+        self.annotate(|annotations| annotations.italic = Some(true))
+    }
+
+    pub fn strikethrough(self) -> Self {
+        self.annotate(|annotations| annotations.strikethrough = Some(true))
+    }
+
+    pub fn underline(self) -> Self {
+        self.annotate(|annotations| annotations.underline = Some(true))
+    }
+
+    pub fn code(self) -> Self {
+        self.annotate(|annotations| annotations.code = Some(true))
+    }
+
+    pub fn color(
+        self,
+        color: TextColor,
+    ) -> Self {
+        self.annotate(|annotations| annotations.color = Some(color))
+    }
+
+    /// Links the text to `url`. Only text objects can be links,
+    /// for mentions and equations this has no effect.
+    pub fn link<S: Into<String>>(
+        mut self,
+        url: S,
+    ) -> Self {
+        if let RichText::Text { text, .. } = &mut self.rich_text {
+            text.link = Some(Link { url: url.into() });
+        }
+        self
+    }
+
+    pub fn build(self) -> RichText {
+        self.rich_text
+    }
+
+    fn annotate<F: FnOnce(&mut Annotations)>(
+        mut self,
+        annotate: F,
+    ) -> Self {
+        let (RichText::Text { rich_text, .. }
+        | RichText::Mention { rich_text, .. }
+        | RichText::Equation { rich_text, .. }) = &mut self.rich_text;
+        annotate(rich_text.annotations.get_or_insert_with(Default::default));
+        self
+    }
+}
+
+impl From<RichTextBuilder> for RichText {
+    fn from(builder: RichTextBuilder) -> Self {
+        builder.build()
+    }
+}
@@ -0,0 +1,197 @@
+//! Attachments returned to Notion when it asks a Link Preview integration to unfurl a link.
+//!
+//! Every unfurl needs a `title` and a `dev` (the developer name) attribute,
+//! see [Unfurl::new()].
+//! <https://developers.notion.com/docs/link-previews>
+
+use crate::models::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// The reply to an unfurl callback, setting the attributes of the preview for `uri`.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct Unfurl {
+    pub uri: String,
+    pub operations: Vec<UnfurlOperation>,
+}
+
+impl Unfurl {
+    /// An unfurl with the attributes every preview needs.
+    pub fn new<U, T, D>(
+        uri: U,
+        title: T,
+        developer: D,
+    ) -> Self
+    where
+        U: Into<String>,
+        T: Into<String>,
+        D: Into<String>,
+    {
+        Self {
+            uri: uri.into(),
+            operations: vec![UnfurlOperation {
+                path: "attributes".to_string(),
+                set: vec![
+                    UnfurlAttribute::title(title),
+                    UnfurlAttribute::plain_text(
+                        "dev",
+                        "Developer Name",
+                        developer,
+                        UnfurlSection::Secondary,
+                    ),
+                ],
+            }],
+        }
+    }
+
+    /// Adds an attribute to the preview.
+    pub fn attribute(
+        mut self,
+        attribute: UnfurlAttribute,
+    ) -> Self {
+        match self.operations.first_mut() {
+            Some(operation) => operation.set.push(attribute),
+            None => self.operations.push(UnfurlOperation {
+                path: "attributes".to_string(),
+                set: vec![attribute],
+            }),
+        }
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct UnfurlOperation {
+    /// What the operation changes, `attributes` for the attributes of the preview.
+    pub path: String,
+    pub set: Vec<UnfurlAttribute>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct UnfurlAttribute {
+    pub id: String,
+    pub name: String,
+    #[serde(flatten)]
+    pub value: UnfurlAttributeValue,
+}
+
+impl UnfurlAttribute {
+    /// The title of the preview.
+    pub fn title<T: Into<String>>(title: T) -> Self {
+        Self {
+            id: "title".to_string(),
+            name: "Title".to_string(),
+            value: UnfurlAttributeValue::Inline {
+                inline: InlineAttribute::Title(InlineValue {
+                    value: title.into(),
+                    section: UnfurlSection::Title,
+                }),
+            },
+        }
+    }
+
+    pub fn plain_text<I, N, V>(
+        id: I,
+        name: N,
+        value: V,
+        section: UnfurlSection,
+    ) -> Self
+    where
+        I: Into<String>,
+        N: Into<String>,
+        V: Into<String>,
+    {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            value: UnfurlAttributeValue::Inline {
+                inline: InlineAttribute::PlainText(InlineValue {
+                    value: value.into(),
+                    section,
+                }),
+            },
+        }
+    }
+
+    pub fn datetime<I, N>(
+        id: I,
+        name: N,
+        value: DateTime<Utc>,
+        section: UnfurlSection,
+    ) -> Self
+    where
+        I: Into<String>,
+        N: Into<String>,
+    {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            value: UnfurlAttributeValue::Inline {
+                inline: InlineAttribute::Datetime(InlineValue { value, section }),
+            },
+        }
+    }
+
+    /// Embeds the content at `src_url` in the expanded preview.
+    pub fn embed<I, N, S>(
+        id: I,
+        name: N,
+        src_url: S,
+    ) -> Self
+    where
+        I: Into<String>,
+        N: Into<String>,
+        S: Into<String>,
+    {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            value: UnfurlAttributeValue::Embed {
+                embed: EmbedAttribute {
+                    src_url: src_url.into(),
+                },
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UnfurlAttributeValue {
+    Inline { inline: InlineAttribute },
+    Embed { embed: EmbedAttribute },
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum InlineAttribute {
+    Title(InlineValue<String>),
+    PlainText(InlineValue<String>),
+    Datetime(InlineValue<DateTime<Utc>>),
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct InlineValue<T> {
+    pub value: T,
+    /// Where the value is shown in the preview.
+    pub section: UnfurlSection,
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct EmbedAttribute {
+    pub src_url: String,
+}
+
+/// The part of a link preview an attribute is shown in.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Copy, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum UnfurlSection {
+    Title,
+    Description,
+    Background,
+    Identifier,
+    Entity,
+    Avatar,
+    Embed,
+    Primary,
+    Secondary,
+}
@@ -1,4 +1,4 @@
-use crate::ids::UserId;
+use crate::ids::{AsIdentifier, UserId};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
@@ -31,4 +31,52 @@ pub enum User {
         common: UserCommon,
         bot: Bot,
     },
+    /// A user Notion only shares the id of, e.g. the users of some property values,
+    /// or one referenced in a request.
+    #[serde(untagged)]
+    Partial { id: UserId },
+}
+
+impl User {
+    pub fn id(&self) -> &UserId {
+        match self {
+            User::Person { common, .. } | User::Bot { common, .. } => &common.id,
+            User::Partial { id } => id,
+        }
+    }
+
+    /// The name of the user, unless Notion only shared their id.
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            User::Person { common, .. } | User::Bot { common, .. } => common.name.as_deref(),
+            User::Partial { .. } => None,
+        }
+    }
+}
+
+/// A user mentioned in rich text.
+/// Responses usually include the full user, while writing a mention only needs the id.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[serde(untagged, from = "User")]
+pub enum MentionedUser {
+    User(User),
+    /// A user Notion only shares the id of, or one referenced in a request.
+    Partial {
+        id: UserId,
+    },
+}
+
+impl AsIdentifier<UserId> for User {
+    fn as_id(&self) -> &UserId {
+        self.id()
+    }
+}
+
+impl From<User> for MentionedUser {
+    fn from(user: User) -> Self {
+        match user {
+            User::Partial { id } => MentionedUser::Partial { id },
+            user => MentionedUser::User(user),
+        }
+    }
 }
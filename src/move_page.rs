@@ -0,0 +1,593 @@
+use crate::batch::BatchReport;
+use crate::ids::{AsIdentifier, BlockId, DatabaseId, PageId};
+use crate::models::block::{Block, CreateBlock};
+use crate::models::properties::{CreatePropertyValue, PropertyValue};
+use crate::models::text::RichText;
+use crate::models::{Page, PageCreateRequest, Parent};
+use crate::schema::{self, PropertyMapping};
+use crate::{Error, NotionApi};
+use futures::future::{BoxFuture, FutureExt};
+
+/// The pages involved in [moving a page](NotionApi::move_page_to_database()) to another database.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MovedPage {
+    /// The original page, now archived.
+    pub archived: PageId,
+    /// The page created in the target database.
+    pub created: PageId,
+}
+
+impl NotionApi {
+    /// Moves a page to another database, which the API can't do directly.
+    ///
+    /// A page is created in `database` with the page's icon, cover and property values
+    /// written to the properties `mapping` picks, converted to their types with
+    /// [convert_value](schema::convert_value()). Without a mapping, one is
+    /// [inferred](PropertyMapping::infer()) from the schemas of both databases.
+    /// The page's content is copied to the new page, then the page is archived.
+    ///
+    /// Child pages, child databases and blocks the API doesn't support aren't copied.
+    /// When copying fails part way, the original page is left as it is.
+    pub async fn move_page_to_database<P, D>(
+        &self,
+        page_id: P,
+        database: D,
+        mapping: Option<PropertyMapping>,
+    ) -> Result<MovedPage, Error>
+    where
+        P: AsIdentifier<PageId>,
+        D: AsIdentifier<DatabaseId>,
+    {
+        let page = self.get_page(page_id).await?;
+        let parent = Parent::Database {
+            database_id: database.as_id().clone(),
+        };
+        let created = self.copy_page(&page, parent, mapping).await?;
+        self.archive_page(&page.id).await?;
+
+        Ok(MovedPage {
+            archived: page.id,
+            created: created.id,
+        })
+    }
+
+    /// Moves each page to `database` like [move_page_to_database](NotionApi::move_page_to_database()),
+    /// one at a time. Pages that fail to move are reported without stopping the others.
+    pub async fn move_pages_to_database<I, D>(
+        &self,
+        pages: I,
+        database: D,
+        mapping: Option<PropertyMapping>,
+    ) -> BatchReport<MovedPage>
+    where
+        I: IntoIterator,
+        I::Item: AsIdentifier<PageId>,
+        D: AsIdentifier<DatabaseId>,
+    {
+        let database = database.as_id();
+        BatchReport::run(pages, |page| {
+            self.move_page_to_database(page, database, mapping.clone())
+        })
+        .await
+    }
+
+    /// Duplicates a page under `parent`, which has to be a page or a database:
+    /// other parents fail with [Error::InvalidPageParent] before any request is made.
+    ///
+    /// The copy gets the page's icon, cover and content. Its property values are
+    /// [converted](schema::convert_value()) to the properties of a database parent with the same
+    /// names, see [PropertyMapping::infer()], while a page parent only takes the title.
+    /// Child pages, child databases and blocks the API doesn't support aren't copied.
+    pub async fn duplicate_page<P: AsIdentifier<PageId>>(
+        &self,
+        page_id: P,
+        parent: Parent,
+    ) -> Result<Page, Error> {
+        let page = self.get_page(page_id).await?;
+        self.copy_page(&page, parent, None).await
+    }
+
+    /// Creates a copy of `page` under `parent`, with its content.
+    async fn copy_page(
+        &self,
+        page: &Page,
+        parent: Parent,
+        mapping: Option<PropertyMapping>,
+    ) -> Result<Page, Error> {
+        let properties = match &parent {
+            Parent::Database { database_id } => {
+                let target = self.get_database(database_id).await?;
+                let mapping = match (mapping, &page.parent) {
+                    (Some(mapping), _) => mapping,
+                    (None, Parent::Database { database_id }) => {
+                        PropertyMapping::infer(&self.get_database(database_id).await?, &target)
+                    }
+                    // Pages outside databases only have a title.
+                    (None, _) => match (title(page), schema::title_name(&target)) {
+                        (Some((from, _)), Some(to)) => PropertyMapping::new().map(from, to),
+                        _ => PropertyMapping::new(),
+                    },
+                };
+                mapping.values(&page.properties, &target)
+            }
+            // Pages outside databases can only have a title, which is always named `title`.
+            Parent::Page { .. } => title(page)
+                .map(|(_, text)| {
+                    (
+                        "title".to_string(),
+                        CreatePropertyValue::Title(text.clone()),
+                    )
+                })
+                .into_iter()
+                .collect(),
+            parent => {
+                return Err(Error::InvalidPageParent {
+                    parent: parent.clone(),
+                })
+            }
+        };
+
+        let created = self
+            .create_page(PageCreateRequest {
+                parent,
+                properties,
+                children: None,
+                icon: page.icon.clone(),
+                cover: page.cover.clone(),
+            })
+            .await?;
+        self.copy_children(page.id.clone().into(), created.id.clone().into())
+            .await?;
+
+        Ok(created)
+    }
+
+    /// Appends copies of the children of `from` to `to`, with their own children,
+    /// through [append_block_tree](NotionApi::append_block_tree()).
+    fn copy_children(
+        &self,
+        from: BlockId,
+        to: BlockId,
+    ) -> BoxFuture<'_, Result<(), Error>> {
+        async move {
+            let (children, sources) = self.copy_tree(&from).await?.into_iter().unzip();
+            let copies = self.append_block_tree(&to, children).await?;
+            self.copy_remaining(sources, copies).await
+        }
+        .boxed()
+    }
+
+    /// Copies of the children of `block_id`, each with the children it can be created with,
+    /// and the blocks they were copied from.
+    fn copy_tree<'a>(
+        &'a self,
+        block_id: &'a BlockId,
+    ) -> BoxFuture<'a, Result<Vec<(CreateBlock, Source)>, Error>> {
+        async move {
+            let mut copies = vec![];
+            for block in self.get_block_children_all(block_id).await? {
+                if !is_copyable(&block) {
+                    continue;
+                }
+                let mut copy = CreateBlock::from(block.clone());
+                let mut source = Source {
+                    block_id: block.as_id().clone(),
+                    appends_children: false,
+                    children: vec![],
+                };
+                // Duplicates show the children of the synced block they duplicate.
+                if block.common().has_children && !is_synced_duplicate(&block) {
+                    if can_hold_children(&copy) {
+                        let (children, sources) =
+                            self.copy_tree(block.as_id()).await?.into_iter().unzip();
+                        copy.set_children(children);
+                        source.children = sources;
+                    } else {
+                        source.appends_children = true;
+                    }
+                }
+                copies.push((copy, source));
+            }
+            Ok(copies)
+        }
+        .boxed()
+    }
+
+    /// Appends the children of the blocks whose copies couldn't be created with them.
+    fn copy_remaining(
+        &self,
+        sources: Vec<Source>,
+        copies: Vec<Block>,
+    ) -> BoxFuture<'_, Result<(), Error>> {
+        async move {
+            for (source, copy) in sources.into_iter().zip(copies) {
+                if source.appends_children {
+                    self.copy_children(source.block_id, copy.as_id().clone())
+                        .await?;
+                } else if source.has_remaining() {
+                    let copied = self.get_block_children_all(copy.as_id()).await?;
+                    self.copy_remaining(source.children, copied).await?;
+                }
+            }
+            Ok(())
+        }
+        .boxed()
+    }
+}
+
+/// The block a copy was made from.
+struct Source {
+    block_id: BlockId,
+    /// Whether the copy was created without the block's children, which are appended to it.
+    appends_children: bool,
+    /// The sources of the children the copy was created with.
+    children: Vec<Source>,
+}
+
+impl Source {
+    fn has_remaining(&self) -> bool {
+        self.appends_children || self.children.iter().any(Source::has_remaining)
+    }
+}
+
+/// Whether the block can be created with children, as opposed to having them appended.
+fn can_hold_children(block: &CreateBlock) -> bool {
+    use CreateBlock::*;
+    matches!(
+        block,
+        Paragraph { .. }
+            | Quote { .. }
+            | BulletedListItem { .. }
+            | NumberedListItem { .. }
+            | Toggle { .. }
+            | ToDo { .. }
+            | ColumnList { .. }
+            | Column { .. }
+            | Template { .. }
+            | SyncedBlock { .. }
+            | Table { .. }
+    )
+}
+
+/// The name and text of the page's title property.
+fn title(page: &Page) -> Option<(&String, &Vec<RichText>)> {
+    page.properties
+        .properties
+        .iter()
+        .find_map(|(name, value)| match value {
+            PropertyValue::Title { title, .. } => Some((name, title)),
+            _ => None,
+        })
+}
+
+fn is_copyable(block: &Block) -> bool {
+    !matches!(
+        block,
+        Block::ChildPage { .. }
+            | Block::ChildDatabase { .. }
+            | Block::Unsupported { .. }
+            | Block::Unknown { .. }
+    )
+}
+
+/// Duplicates show the children of the synced block they duplicate.
+fn is_synced_duplicate(block: &Block) -> bool {
+    matches!(block, Block::SyncedBlock { synced_block, .. } if synced_block.synced_from.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ids::PageId;
+    use crate::models::Parent;
+    use crate::{Error, NotionApi, TransportResponse};
+    use reqwest::{Method, StatusCode};
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    const PAGE: &str = "b55c9c91-384d-452b-81db-d1ef79372b75";
+    const COPY: &str = "5d7e2a1c-3b4f-4e6a-8c9d-0e1f2a3b4c5d";
+
+    /// A fake workspace, which creates blocks like Notion does and checks the limits of appends.
+    #[derive(Default)]
+    struct Workspace {
+        children: HashMap<String, Vec<Value>>,
+        created: usize,
+        /// Whether blocks are created directly, without the limits of an append.
+        seeding: bool,
+    }
+
+    impl Workspace {
+        fn create(
+            &mut self,
+            parent: &str,
+            mut block: Value,
+            depth: usize,
+        ) -> Value {
+            self.created += 1;
+            let id = format!("00000000-0000-4000-8000-{:012}", self.created);
+            let kind = block["type"].as_str().unwrap().to_string();
+            let children = block[&kind]
+                .as_object_mut()
+                .and_then(|fields| fields.remove("children"))
+                .unwrap_or_else(|| json!([]));
+            let children = children.as_array().unwrap();
+            if !self.seeding {
+                assert!(children.len() <= 100, "{} children", children.len());
+                assert!(children.is_empty() || depth < 2, "children nested too deep");
+                assert!(kind != "table" || !children.is_empty(), "tables need a row");
+            }
+            for child in children {
+                self.create(&id, child.clone(), depth + 1);
+            }
+
+            let user = json!({"object": "user", "id": "6419f912-5293-4ea8-b2c8-9c3ce44f90e3"});
+            let object = block.as_object_mut().unwrap();
+            object.insert("object".into(), "block".into());
+            object.insert("id".into(), id.into());
+            object.insert("created_time".into(), "2022-05-12T21:15:00.000Z".into());
+            object.insert("last_edited_time".into(), "2022-05-12T21:15:00.000Z".into());
+            object.insert("created_by".into(), user.clone());
+            object.insert("last_edited_by".into(), user);
+            object.insert("has_children".into(), false.into());
+            self.children
+                .entry(parent.to_string())
+                .or_default()
+                .push(block.clone());
+            block
+        }
+
+        fn list(
+            &self,
+            parent: &str,
+        ) -> Vec<Value> {
+            let mut children = self.children.get(parent).cloned().unwrap_or_default();
+            for child in &mut children {
+                let id = child["id"].as_str().unwrap();
+                child["has_children"] = self.children.contains_key(id).into();
+            }
+            children
+        }
+
+        fn trash(
+            &mut self,
+            block_id: &str,
+        ) -> Value {
+            for children in self.children.values_mut() {
+                if let Some(index) = children.iter().position(|child| child["id"] == block_id) {
+                    return children.remove(index);
+                }
+            }
+            panic!("no block {}", block_id)
+        }
+
+        /// The kinds and text of the blocks under `parent`, indented by their depth.
+        fn outline(
+            &self,
+            parent: &str,
+            depth: usize,
+        ) -> Vec<String> {
+            let mut lines = vec![];
+            for child in self.list(parent) {
+                let kind = child["type"].as_str().unwrap();
+                let text = match kind {
+                    "table_row" => &child[kind]["cells"][0][0],
+                    _ => &child[kind]["rich_text"][0],
+                };
+                let text = text["plain_text"].as_str().unwrap_or_default();
+                lines.push(format!("{}{} {}", "  ".repeat(depth), kind, text));
+                lines.extend(self.outline(child["id"].as_str().unwrap(), depth + 1));
+            }
+            lines
+        }
+    }
+
+    fn text(content: &str) -> Value {
+        json!([{"type": "text", "text": {"content": content}, "plain_text": content}])
+    }
+
+    fn paragraph(content: &str) -> Value {
+        json!({"type": "paragraph", "paragraph": {"rich_text": text(content), "color": "default"}})
+    }
+
+    fn table(rows: usize) -> Value {
+        let rows: Vec<_> = (0..rows)
+            .map(|row| {
+                json!({"type": "table_row", "table_row": {"cells": [text(&format!("row {}", row))]}})
+            })
+            .collect();
+        json!({"type": "table", "table": {
+            "table_width": 1, "has_column_header": false, "has_row_header": false, "children": rows
+        }})
+    }
+
+    fn column(children: Vec<Value>) -> Value {
+        json!({"type": "column", "column": {"children": children}})
+    }
+
+    #[tokio::test]
+    async fn duplicates_large_tables_and_tables_in_columns(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let workspace = Arc::new(Mutex::new(Workspace::default()));
+        {
+            let mut workspace = workspace.lock().unwrap();
+            workspace.seeding = true;
+            let source = json!([
+                paragraph("intro"),
+                table(150),
+                json!({"type": "column_list", "column_list": {"children": [
+                    column(vec![table(3), paragraph("after")]),
+                    column(vec![paragraph("right")]),
+                ]}}),
+                json!({"type": "callout", "callout": {
+                    "rich_text": text("note"), "icon": {"type": "emoji", "emoji": "💡"},
+                    "color": "default", "children": [paragraph("inside")]
+                }}),
+            ]);
+            seed(&mut workspace, PAGE, Some(source));
+            workspace.seeding = false;
+        }
+
+        let fake = workspace.clone();
+        let api = NotionApi::builder("secret".to_string())
+            .transport(move |request: reqwest::Request| {
+                let mut workspace = fake.lock().unwrap();
+                let path = request.url().path();
+                let parent = path
+                    .trim_start_matches("/v1/blocks/")
+                    .trim_end_matches("/children")
+                    .to_string();
+                let list = |results: Vec<Value>| {
+                    json!({"object": "list", "results": results, "next_cursor": null, "has_more": false})
+                };
+                let body = match (request.method(), path) {
+                    (&Method::GET, "/v1/pages/b55c9c91-384d-452b-81db-d1ef79372b75") => {
+                        serde_json::from_str(include_str!("models/tests/page.json")).unwrap()
+                    }
+                    (&Method::POST, "/v1/pages") => {
+                        let mut page: Value =
+                            serde_json::from_str(include_str!("models/tests/page.json")).unwrap();
+                        page["id"] = COPY.into();
+                        page
+                    }
+                    (&Method::GET, _) => list(workspace.list(&parent)),
+                    (&Method::PATCH, path) if !path.ends_with("/children") => {
+                        workspace.trash(&parent)
+                    }
+                    (&Method::PATCH, _) => {
+                        let body = request.body().and_then(|body| body.as_bytes()).unwrap();
+                        let body: Value = serde_json::from_slice(body).unwrap();
+                        let children = body["children"].as_array().unwrap().clone();
+                        assert!(children.len() <= 100);
+                        let created = children
+                            .into_iter()
+                            .map(|child| workspace.create(&parent, child, 0))
+                            .collect();
+                        list(created)
+                    }
+                    (method, path) => panic!("unexpected request {} {}", method, path),
+                };
+                Ok(TransportResponse::new(StatusCode::OK, body.to_string()))
+            })
+            .build()?;
+
+        let parent: PageId = PAGE.parse()?;
+        api.duplicate_page(PAGE.parse::<PageId>()?, Parent::Page { page_id: parent })
+            .await?;
+
+        let workspace = workspace.lock().unwrap();
+        let copy = workspace.outline(COPY, 0);
+        assert_eq!(copy, workspace.outline(PAGE, 0));
+        assert_eq!(copy.len(), 1 + 151 + 9 + 2);
+        assert_eq!(
+            &copy[152..],
+            &[
+                "column_list ",
+                "  column ",
+                "    table ",
+                "      table_row row 0",
+                "      table_row row 1",
+                "      table_row row 2",
+                "    paragraph after",
+                "  column ",
+                "    paragraph right",
+                "callout note",
+                "  paragraph inside",
+            ]
+        );
+
+        Ok(())
+    }
+
+    /// Creates the source blocks under `parent`, with their own children, at any depth.
+    fn seed(
+        workspace: &mut Workspace,
+        parent: &str,
+        children: Option<Value>,
+    ) {
+        for mut child in children
+            .into_iter()
+            .flat_map(|c| c.as_array().unwrap().clone())
+        {
+            let kind = child["type"].as_str().unwrap().to_string();
+            let nested = child[&kind]
+                .as_object_mut()
+                .and_then(|fields| fields.remove("children"));
+            let created = workspace.create(parent, child, 0);
+            seed(workspace, created["id"].as_str().unwrap(), nested);
+        }
+    }
+
+    #[tokio::test]
+    async fn refuses_parents_pages_cannot_be_created_under(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let api = NotionApi::builder("secret".to_string())
+            .transport(|request: reqwest::Request| {
+                assert_eq!(request.method(), Method::GET);
+                Ok(TransportResponse::new(
+                    StatusCode::OK,
+                    include_str!("models/tests/page.json"),
+                ))
+            })
+            .build()?;
+
+        for parent in [
+            Parent::Workspace,
+            Parent::Block {
+                block_id: PAGE.parse()?,
+            },
+        ] {
+            let result = api.duplicate_page(PAGE.parse::<PageId>()?, parent).await;
+            assert!(matches!(result, Err(Error::InvalidPageParent { .. })));
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn duplicates_title_and_content_under_a_page() -> Result<(), Box<dyn std::error::Error>> {
+        let created = Arc::new(Mutex::new(None));
+        let recorded = created.clone();
+        let api = NotionApi::builder("secret".to_string())
+            .transport(move |request: reqwest::Request| {
+                let body = match (request.method(), request.url().path()) {
+                    (&Method::GET, path) if path.ends_with("/children") => {
+                        r#"{"object": "list", "results": [], "next_cursor": null, "has_more": false}"#
+                    }
+                    (&Method::POST, "/v1/pages") => {
+                        let body = request.body().and_then(|body| body.as_bytes()).unwrap();
+                        *recorded.lock().unwrap() =
+                            Some(serde_json::from_slice::<serde_json::Value>(body).unwrap());
+                        include_str!("models/tests/page.json")
+                    }
+                    (&Method::GET, _) => include_str!("models/tests/page.json"),
+                    (method, path) => panic!("unexpected request {} {}", method, path),
+                };
+                Ok(TransportResponse::new(StatusCode::OK, body))
+            })
+            .build()?;
+
+        let parent: PageId = "b55c9c91384d452b81dbd1ef79372b75".parse()?;
+        api.duplicate_page(
+            "b55c9c91384d452b81dbd1ef79372b75".parse::<PageId>()?,
+            Parent::Page { page_id: parent },
+        )
+        .await?;
+
+        let request = created.lock().unwrap().take().unwrap();
+        assert_eq!(
+            request["parent"]["page_id"],
+            "b55c9c91-384d-452b-81db-d1ef79372b75"
+        );
+        assert_eq!(
+            request["properties"]
+                .as_object()
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["title"]
+        );
+
+        Ok(())
+    }
+}
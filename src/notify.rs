@@ -0,0 +1,141 @@
+//! Delivery of change summaries to chat tools, webhooks and other external systems.
+
+use crate::diff::PropertyChange;
+use crate::ids::{notion_url, PageId};
+use crate::models::Page;
+use crate::Error;
+use futures::future::BoxFuture;
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+
+/// A rendered summary of the changes made to a page, ready to be sent to a [NotificationSink].
+#[derive(Serialize, Debug, Eq, PartialEq, Clone)]
+pub struct ChangeSummary {
+    pub page_id: PageId,
+    pub title: String,
+    pub url: String,
+    /// One human readable line per change.
+    pub changes: Vec<String>,
+}
+
+impl ChangeSummary {
+    /// Summarizes the property changes of a page,
+    /// as reported by [diff_properties](crate::diff::diff_properties).
+    pub fn from_property_changes(
+        page: &Page,
+        changes: &[PropertyChange],
+    ) -> Self {
+        Self {
+            page_id: page.id.clone(),
+            title: page.title().unwrap_or_default(),
+            url: notion_url(&page.id),
+            changes: changes.iter().map(ToString::to_string).collect(),
+        }
+    }
+}
+
+impl Display for ChangeSummary {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(f, "{} ({})", self.title, self.url)?;
+        for change in &self.changes {
+            write!(f, "\n• {}", change)?;
+        }
+        Ok(())
+    }
+}
+
+/// Somewhere change summaries are delivered to, like a chat channel or an HTTP endpoint.
+pub trait NotificationSink: Send + Sync {
+    fn notify<'a>(
+        &'a self,
+        summary: &'a ChangeSummary,
+    ) -> BoxFuture<'a, Result<(), Error>>;
+}
+
+/// Posts change summaries as JSON to a webhook url.
+///
+/// The body has a `text` field with the rendered summary, which is what Slack and
+/// most chat incoming webhooks display, next to the summary's individual fields.
+#[cfg(feature = "webhook")]
+#[derive(Clone, Debug)]
+pub struct WebhookSink {
+    client: reqwest::Client,
+    url: String,
+}
+
+#[cfg(feature = "webhook")]
+impl WebhookSink {
+    pub fn new(url: String) -> Self {
+        Self::with_client(reqwest::Client::new(), url)
+    }
+
+    pub fn with_client(
+        client: reqwest::Client,
+        url: String,
+    ) -> Self {
+        Self { client, url }
+    }
+}
+
+#[cfg(feature = "webhook")]
+impl NotificationSink for WebhookSink {
+    fn notify<'a>(
+        &'a self,
+        summary: &'a ChangeSummary,
+    ) -> BoxFuture<'a, Result<(), Error>> {
+        #[derive(Serialize)]
+        struct WebhookBody<'a> {
+            text: String,
+            #[serde(flatten)]
+            summary: &'a ChangeSummary,
+        }
+
+        Box::pin(async move {
+            self.client
+                .post(&self.url)
+                .json(&WebhookBody {
+                    text: summary.to_string(),
+                    summary,
+                })
+                .send()
+                .await?
+                .error_for_status()?;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diff::diff_properties;
+    use crate::models::{Page, Properties};
+    use crate::notify::ChangeSummary;
+    use serde_json::json;
+
+    #[test]
+    fn renders_property_changes() {
+        let page: Page = serde_json::from_str(include_str!("models/tests/page.json")).unwrap();
+        let old: Properties = serde_json::from_value(json!({
+            "Status": {"id": "s", "type": "select", "select": {"name": "Todo", "color": "red"}},
+            "Tags": {"id": "t", "type": "multi_select", "multi_select": [{"name": "a", "color": "red"}]}
+        }))
+        .unwrap();
+        let new: Properties = serde_json::from_value(json!({
+            "Status": {"id": "s", "type": "select", "select": {"name": "Done", "color": "green"}},
+            "Tags": {"id": "t", "type": "multi_select", "multi_select": [{"name": "b", "color": "red"}]}
+        }))
+        .unwrap();
+
+        let summary = ChangeSummary::from_property_changes(&page, &diff_properties(&old, &new));
+
+        assert_eq!(
+            summary.to_string(),
+            "Stuffsome (https://www.notion.so/b55c9c91384d452b81dbd1ef79372b75)\n\
+             • Status: Todo → Done\n\
+             • Tags: added b; removed a"
+        );
+    }
+}
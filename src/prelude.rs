@@ -0,0 +1,39 @@
+//! The types most programs need, in a single import.
+//!
+//! ```no_run
+//! use notion::prelude::*;
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let api = NotionApi::from_env()?;
+//! let database: DatabaseId = "b55c9c91384d452b81dbd1ef79372b75".parse()?;
+//! let query = DatabaseQuery {
+//!     filter: Some(filter::property("Done").checkbox(false).build()?),
+//!     ..Default::default()
+//! };
+//!
+//! let mut pages = api.query_database_stream(database, query).boxed();
+//! while let Some(page) = pages.try_next().await? {
+//!     println!("{}", page.title().unwrap_or_default());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Everything here stays importable from the prelude across releases, even when
+//! the module defining it is reorganized. Import from the modules directly for
+//! anything else.
+
+pub use crate::ids::{AsIdentifier, BlockId, DatabaseId, Identifier, PageId, PropertyId, UserId};
+pub use crate::models::block::{Block, CreateBlock};
+pub use crate::models::filter::{self, Filter};
+pub use crate::models::properties::{
+    CreatePropertyValue, DateOrDateTime, DateValue, PropertyConfiguration, PropertyValue,
+};
+pub use crate::models::search::{DatabaseQuery, DatabaseSort, SearchRequest, SortDirection};
+pub use crate::models::text::RichText;
+pub use crate::models::{
+    Database, ListResponse, Page, PageCreateRequest, PageUpdateRequest, Parent, Properties,
+    SearchResult,
+};
+pub use crate::{ApiVersion, Error, NotionApi, NotionApiBuilder, RetryPolicy};
+pub use futures::{Stream, StreamExt, TryStreamExt};
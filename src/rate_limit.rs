@@ -0,0 +1,102 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A token bucket shared by every clone of a [NotionApi](crate::NotionApi) client.
+///
+/// The bucket holds up to one second worth of requests. Each request takes a token,
+/// and once the bucket is empty requests are delayed until a token has been refilled.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    requests_per_second: f64,
+    bucket: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    /// Goes negative when requests are waiting for tokens that haven't been refilled yet.
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(requests_per_second: u32) -> Self {
+        let requests_per_second = f64::from(requests_per_second.max(1));
+        Self {
+            requests_per_second,
+            bucket: Mutex::new(Bucket {
+                tokens: requests_per_second,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// A separate limiter for the same rate.
+    pub(crate) fn with_same_rate(&self) -> Self {
+        Self::new(self.requests_per_second as u32)
+    }
+
+    /// Waits until a request may be sent.
+    pub(crate) async fn acquire(&self) {
+        let delay = self.reserve(Instant::now());
+        if !delay.is_zero() {
+            tracing::trace!(delay_ms = delay.as_millis() as u64, "Throttling request");
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Takes a token and returns how long to wait until it's available.
+    fn reserve(
+        &self,
+        now: Instant,
+    ) -> Duration {
+        let mut bucket = self.bucket.lock().unwrap_or_else(|e| e.into_inner());
+
+        let elapsed = now.saturating_duration_since(bucket.refilled_at);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * self.requests_per_second)
+            .min(self.requests_per_second);
+        bucket.refilled_at = now;
+        bucket.tokens -= 1.0;
+
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / self.requests_per_second)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rate_limit::RateLimiter;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn delays_requests_beyond_the_burst() {
+        let limiter = RateLimiter::new(2);
+        let now = Instant::now();
+
+        let delays: Vec<_> = (0..4).map(|_| limiter.reserve(now)).collect();
+
+        assert_eq!(
+            delays,
+            vec![
+                Duration::ZERO,
+                Duration::ZERO,
+                Duration::from_millis(500),
+                Duration::from_secs(1)
+            ]
+        );
+    }
+
+    #[test]
+    fn refills_over_time() {
+        let limiter = RateLimiter::new(3);
+        let now = Instant::now();
+        for _ in 0..3 {
+            limiter.reserve(now);
+        }
+
+        assert!(limiter.reserve(now + Duration::from_millis(400)).is_zero());
+        assert!(!limiter.reserve(now + Duration::from_millis(400)).is_zero());
+    }
+}
@@ -0,0 +1,406 @@
+use crate::conflict_pacer::object_id;
+use crate::instrument::{self, RequestInfo, ResponseInfo};
+use crate::models::error::ErrorResponse;
+use crate::models::Object;
+use crate::{batch, builder, Error, NotionApi, TransportResponse};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Method, RequestBuilder, StatusCode};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, Instant};
+use tracing::{field, Instrument};
+
+/// Controls how a [NotionApi] client retries requests that were rate limited (`429`)
+/// or failed with a server error (`5xx`).
+///
+/// Retries wait with exponential backoff, starting at `initial_backoff` and doubling
+/// up to `max_backoff`. When the response includes a `Retry-After` header, that delay
+/// is used instead.
+///
+/// Set it with [NotionApiBuilder::retry_policy()](crate::NotionApiBuilder::retry_policy()).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Creates a policy that sends a request at most `max_attempts` times, including the first.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_initial_backoff(
+        self,
+        initial_backoff: Duration,
+    ) -> Self {
+        Self {
+            initial_backoff,
+            ..self
+        }
+    }
+
+    pub fn with_max_backoff(
+        self,
+        max_backoff: Duration,
+    ) -> Self {
+        Self {
+            max_backoff,
+            ..self
+        }
+    }
+
+    /// When enabled (the default), each backoff is a random delay up to the computed one,
+    /// so concurrent clients don't retry in lockstep.
+    /// Delays requested by a `Retry-After` header are never shortened.
+    pub fn with_jitter(
+        self,
+        jitter: bool,
+    ) -> Self {
+        Self { jitter, ..self }
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// How long to wait before sending attempt `attempt + 1`.
+    fn delay(
+        &self,
+        attempt: u32,
+        retry_after: Option<Duration>,
+    ) -> Duration {
+        if let Some(retry_after) = retry_after {
+            return retry_after;
+        }
+
+        let backoff = self
+            .initial_backoff
+            .checked_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff);
+
+        if self.jitter {
+            backoff.mul_f64(random_fraction())
+        } else {
+            backoff
+        }
+    }
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Reads a `Retry-After` header given in seconds, which is the form Notion sends.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// A value in `[0, 1)`, good enough to spread out retries.
+fn random_fraction() -> f64 {
+    let random = RandomState::new().build_hasher().finish();
+    (random >> 11) as f64 / (1u64 << 53) as f64
+}
+
+impl NotionApi {
+    pub(crate) async fn make_json_request(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<Object, Error> {
+        let response = self.send_request(request).await?;
+        let json = &response.body;
+
+        #[cfg(test)]
+        {
+            dbg!(serde_json::from_str::<serde_json::Value>(json)
+                .map_err(|source| Error::JsonParseError { source })?);
+        }
+        let result =
+            serde_json::from_str(json).map_err(|source| Error::JsonParseError { source })?;
+
+        match result {
+            Object::Error { error } => Err(api_error(error, &response)),
+            response => Ok(response),
+        }
+    }
+
+    /// Sends a request, retrying it as configured, and returns the final response.
+    pub(crate) async fn send_request(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<TransportResponse, Error> {
+        let mut request = request.build()?;
+        request.headers_mut().extend(self.headers.clone());
+        if let (Some(timeout), None) = (self.timeout, request.timeout()) {
+            *request.timeout_mut() = Some(timeout);
+        }
+
+        // Changes to a page or database make its cached copy stale.
+        let stale = match &self.cache {
+            Some(cache)
+                if request.method() != Method::GET && !request.url().path().ends_with("/query") =>
+            {
+                object_id(request.url()).map(|object| (cache, object))
+            }
+            _ => None,
+        };
+
+        let endpoint = instrument::endpoint(request.method(), request.url());
+        let span = tracing::debug_span!(
+            "request",
+            endpoint = %endpoint,
+            status = field::Empty,
+            retries = field::Empty,
+            latency_ms = field::Empty,
+        );
+        let started = Instant::now();
+        let response = self
+            .send_with_retries(request, &endpoint)
+            .instrument(span.clone())
+            .await;
+        span.record("latency_ms", started.elapsed().as_millis() as u64);
+
+        // Dropped once the change is made, so that reads made meanwhile don't cache the
+        // object as it was. Failed requests may have changed it too, e.g. when they timed out.
+        if let Some((cache, object)) = stale {
+            cache.invalidate(&object);
+        }
+        response
+    }
+
+    async fn send_with_retries(
+        &self,
+        mut request: reqwest::Request,
+        endpoint: &str,
+    ) -> Result<TransportResponse, Error> {
+        let conflict_object = match &self.conflict_pacer {
+            Some(pacer) => object_id(request.url()).map(|object| (pacer, object)),
+            None => None,
+        };
+
+        let mut attempt = 1;
+        let response = loop {
+            let url = request.url();
+            tracing::trace!(
+                method = request.method().as_str(),
+                url = url.as_str(),
+                attempt,
+                "Sending request"
+            );
+
+            let retry = match &self.retry_policy {
+                Some(policy) if attempt < policy.max_attempts => {
+                    request.try_clone().map(|request| (policy, request))
+                }
+                _ => None,
+            };
+
+            if let Some((pacer, object)) = &conflict_object {
+                pacer.acquire(object).await;
+            }
+            if let Some(rate_limiter) = &self.rate_limiter {
+                rate_limiter.acquire().await;
+            }
+
+            // Signed last, and for every attempt, so the signature covers the client's
+            // headers and a signer can replace them.
+            if let Some(signer) = &self.request_signer {
+                builder::sign(signer, &mut request);
+            }
+
+            let method = request.method().clone();
+            if let Some(on_request) = &self.on_request {
+                on_request(&RequestInfo {
+                    endpoint: endpoint.to_string(),
+                    method: method.clone(),
+                    url: request.url().clone(),
+                    attempt,
+                });
+            }
+
+            let sent = Instant::now();
+            let response = self
+                .transport
+                .send(request)
+                .instrument(tracing::trace_span!("Sending request"))
+                .await;
+            if let Some(on_response) = &self.on_response {
+                on_response(&ResponseInfo {
+                    endpoint: endpoint.to_string(),
+                    method,
+                    status: response.as_ref().ok().map(|response| response.status),
+                    attempt,
+                    latency: sent.elapsed(),
+                });
+            }
+            let response = response?;
+
+            let conflicted = response.status == StatusCode::CONFLICT;
+            if let Some((pacer, object)) = &conflict_object {
+                if conflicted {
+                    pacer.conflicted(object, retry_after(&response.headers), Instant::now());
+                } else {
+                    pacer.settled(object);
+                }
+            }
+
+            match retry {
+                // The pacer delays the retry, along with other requests to the object.
+                Some((_, retry_request)) if conflicted && conflict_object.is_some() => {
+                    tracing::debug!(attempt, "Retrying conflicted request");
+                    batch::record_retry();
+                    request = retry_request;
+                    attempt += 1;
+                }
+                Some((policy, retry_request)) if is_retryable(response.status) => {
+                    let delay = policy.delay(attempt, retry_after(&response.headers));
+                    tracing::debug!(
+                        status = response.status.as_u16(),
+                        attempt,
+                        delay_ms = delay.as_millis() as u64,
+                        "Retrying request"
+                    );
+                    tokio::time::sleep(delay).await;
+                    batch::record_retry();
+                    request = retry_request;
+                    attempt += 1;
+                }
+                _ => break response,
+            }
+        };
+
+        let span = tracing::Span::current();
+        span.record("status", response.status.as_u16());
+        span.record("retries", attempt - 1);
+        tracing::debug!("JSON Response: {}", response.body);
+        Ok(response)
+    }
+}
+
+/// The error Notion responded with, along with the delay the response asked for.
+pub(crate) fn api_error(
+    mut error: ErrorResponse,
+    response: &TransportResponse,
+) -> Error {
+    error.retry_after = retry_after(&response.headers);
+    Error::ApiError { error }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ids::PageId;
+    use crate::models::error::ErrorCode;
+    use crate::request::{is_retryable, RetryPolicy};
+    use crate::{NotionApi, TransportResponse};
+    use reqwest::header::{HeaderValue, RETRY_AFTER};
+    use reqwest::StatusCode;
+    use std::time::Duration;
+
+    #[test]
+    fn backoff_doubles_up_to_max() {
+        let policy = RetryPolicy::new(10)
+            .with_initial_backoff(Duration::from_millis(100))
+            .with_max_backoff(Duration::from_millis(500))
+            .with_jitter(false);
+
+        let delays: Vec<_> = (1..=5).map(|attempt| policy.delay(attempt, None)).collect();
+
+        assert_eq!(
+            delays,
+            vec![100, 200, 400, 500, 500]
+                .into_iter()
+                .map(Duration::from_millis)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn retry_after_overrides_backoff() {
+        let policy = RetryPolicy::default();
+
+        assert_eq!(
+            policy.delay(1, Some(Duration::from_secs(7))),
+            Duration::from_secs(7)
+        );
+    }
+
+    #[test]
+    fn jitter_stays_within_backoff() {
+        let policy = RetryPolicy::default().with_initial_backoff(Duration::from_secs(1));
+
+        for attempt in 1..=3 {
+            assert!(policy.delay(attempt, None) <= Duration::from_secs(1 << (attempt - 1)));
+        }
+    }
+
+    #[test]
+    fn retries_rate_limits_and_server_errors() {
+        assert!(is_retryable(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable(StatusCode::BAD_GATEWAY));
+        assert!(!is_retryable(StatusCode::BAD_REQUEST));
+        assert!(!is_retryable(StatusCode::OK));
+    }
+
+    #[tokio::test]
+    async fn api_errors_expose_status_request_id_and_retry_after(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let api = NotionApi::builder("secret".to_string())
+            .transport(|_| {
+                let mut response = TransportResponse::new(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    r#"{
+                        "object": "error", "status": 429, "code": "rate_limited",
+                        "message": "Rate limited", "request_id": "0c3fa1e2-5d6b-4f7a-8e9d-1a2b3c4d5e6f"
+                    }"#,
+                );
+                response
+                    .headers
+                    .insert(RETRY_AFTER, HeaderValue::from_static("3"));
+                Ok(response)
+            })
+            .build()?;
+
+        let error = api
+            .get_page("b55c9c91384d452b81dbd1ef79372b75".parse::<PageId>()?)
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.status(), Some(StatusCode::TOO_MANY_REQUESTS));
+        assert_eq!(
+            error.api_error().map(|error| &error.code),
+            Some(&ErrorCode::RateLimited)
+        );
+        assert_eq!(
+            error.request_id(),
+            Some("0c3fa1e2-5d6b-4f7a-8e9d-1a2b3c4d5e6f")
+        );
+        assert!(error.is_retryable());
+        assert_eq!(error.retry_after(), Some(Duration::from_secs(3)));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,605 @@
+//! Comparisons between the schemas of two databases,
+//! and conversions of property values from one schema to another.
+
+use crate::models::properties::{
+    CreatePerson, CreatePropertyValue, DateOrDateTime, DateValue, FormulaResultValue,
+    PropertyConfiguration, PropertyValue, RollupValue,
+};
+use crate::models::text::RichText;
+use crate::models::users::User;
+use crate::models::{Database, Properties};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+/// What happens to the values of a property when pages move from one database to another.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Compatibility {
+    /// The target has a property of the same type, values carry over as they are.
+    Clean,
+    /// The target property has a different type, values have to be converted
+    /// and may lose detail, e.g. a number becoming text.
+    Convert {
+        from: &'static str,
+        to: &'static str,
+    },
+    /// The target has no property of that name, or no way to hold its values.
+    Dropped,
+}
+
+/// How a property of the source database maps onto the target database.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct PropertyCompatibility {
+    pub name: String,
+    pub compatibility: Compatibility,
+}
+
+/// How the properties of one database map onto another, see [compatibility].
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct CompatibilityReport {
+    /// Every property of the source database, ordered by name.
+    pub properties: Vec<PropertyCompatibility>,
+}
+
+impl CompatibilityReport {
+    /// Properties whose values carry over as they are.
+    pub fn clean(&self) -> impl Iterator<Item = &PropertyCompatibility> {
+        self.with(|compatibility| matches!(compatibility, Compatibility::Clean))
+    }
+
+    /// Properties whose values have to be converted.
+    pub fn converted(&self) -> impl Iterator<Item = &PropertyCompatibility> {
+        self.with(|compatibility| matches!(compatibility, Compatibility::Convert { .. }))
+    }
+
+    /// Properties whose values are lost.
+    pub fn dropped(&self) -> impl Iterator<Item = &PropertyCompatibility> {
+        self.with(|compatibility| matches!(compatibility, Compatibility::Dropped))
+    }
+
+    /// Whether every property carries over as it is.
+    pub fn is_clean(&self) -> bool {
+        self.clean().count() == self.properties.len()
+    }
+
+    fn with<F: Fn(&Compatibility) -> bool>(
+        &self,
+        predicate: F,
+    ) -> impl Iterator<Item = &PropertyCompatibility> {
+        self.properties
+            .iter()
+            .filter(move |property| predicate(&property.compatibility))
+    }
+}
+
+impl Display for PropertyCompatibility {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        match &self.compatibility {
+            Compatibility::Clean => write!(f, "{}: maps cleanly", self.name),
+            Compatibility::Convert { from, to } => {
+                write!(f, "{}: converted from {} to {}", self.name, from, to)
+            }
+            Compatibility::Dropped => write!(f, "{}: dropped", self.name),
+        }
+    }
+}
+
+/// Compares the schema of `source` with `target`, matching properties by name,
+/// to tell how the values of pages moved from `source` to `target` carry over.
+pub fn compatibility(
+    source: &Database,
+    target: &Database,
+) -> CompatibilityReport {
+    let mut properties: Vec<_> = source
+        .properties
+        .iter()
+        .map(|(name, source_property)| PropertyCompatibility {
+            name: name.clone(),
+            compatibility: match target.properties.get(name) {
+                Some(target_property) => property_compatibility(source_property, target_property),
+                None => Compatibility::Dropped,
+            },
+        })
+        .collect();
+    properties.sort_by(|a, b| a.name.cmp(&b.name));
+
+    CompatibilityReport { properties }
+}
+
+fn property_compatibility(
+    source: &PropertyConfiguration,
+    target: &PropertyConfiguration,
+) -> Compatibility {
+    use PropertyConfiguration::*;
+
+    let convert = Compatibility::Convert {
+        from: source.type_name(),
+        to: target.type_name(),
+    };
+    match (source, target) {
+        // Values of these are computed by Notion, so nothing can be written to them.
+        (
+            _,
+            Formula { .. }
+            | Rollup { .. }
+            | CreatedTime { .. }
+            | CreatedBy { .. }
+            | LastEditedTime { .. }
+            | LastEditBy { .. }
+            | UniqueId { .. }
+            | Button { .. },
+        ) => Compatibility::Dropped,
+        (Button { .. }, _) => Compatibility::Dropped,
+        (Relation { relation: from, .. }, Relation { relation: to, .. }) => {
+            if from.database_id == to.database_id {
+                Compatibility::Clean
+            } else {
+                Compatibility::Dropped
+            }
+        }
+        _ if source.type_name() == target.type_name() => Compatibility::Clean,
+        // Everything has a textual form.
+        (_, Title { .. } | Text { .. }) => convert,
+        (
+            Select { .. } | Status { .. } | MultiSelect { .. },
+            Select { .. } | Status { .. } | MultiSelect { .. },
+        ) => convert,
+        (CreatedTime { .. } | LastEditedTime { .. }, Date { .. }) => convert,
+        (CreatedBy { .. } | LastEditBy { .. }, People { .. }) => convert,
+        (
+            Title { .. } | Text { .. } | Url { .. } | Email { .. } | PhoneNumber { .. },
+            Url { .. } | Email { .. } | PhoneNumber { .. },
+        ) => convert,
+        _ => Compatibility::Dropped,
+    }
+}
+
+/// Which property of a target database each property of a source database is written to,
+/// e.g. when [moving pages](crate::NotionApi::move_page_to_database()) between databases.
+///
+/// Properties without a target aren't written.
+#[derive(Debug, Default, Eq, PartialEq, Clone)]
+pub struct PropertyMapping {
+    /// Target property names keyed by source property name.
+    targets: HashMap<String, String>,
+}
+
+impl PropertyMapping {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes the values of the source property `from` to the target property `to`.
+    pub fn map<S: Into<String>, T: Into<String>>(
+        mut self,
+        from: S,
+        to: T,
+    ) -> Self {
+        self.targets.insert(from.into(), to.into());
+        self
+    }
+
+    /// Maps each property that isn't [dropped](Compatibility::Dropped) to the property
+    /// of the same name, and the title to the title even if they're named differently.
+    pub fn infer(
+        source: &Database,
+        target: &Database,
+    ) -> Self {
+        let mapping = compatibility(source, target)
+            .properties
+            .into_iter()
+            .filter(|property| property.compatibility != Compatibility::Dropped)
+            .fold(Self::new(), |mapping, property| {
+                mapping.map(property.name.clone(), property.name)
+            });
+
+        match (title_name(source), title_name(target)) {
+            (Some(from), Some(to)) => mapping.map(from, to),
+            _ => mapping,
+        }
+    }
+
+    /// The target property the source property `name` is written to.
+    pub fn target(
+        &self,
+        name: &str,
+    ) -> Option<&str> {
+        self.targets.get(name).map(String::as_str)
+    }
+
+    /// The values of `properties` converted for `target`, keyed by target property name.
+    ///
+    /// Values without a target property, or that can't be
+    /// [converted](convert_value()) to its type, are left out.
+    pub fn values(
+        &self,
+        properties: &Properties,
+        target: &Database,
+    ) -> HashMap<String, CreatePropertyValue> {
+        properties
+            .properties
+            .iter()
+            .filter_map(|(name, value)| {
+                let target_name = self.target(name)?;
+                let configuration = target.properties.get(target_name)?;
+                Some((
+                    target_name.to_string(),
+                    convert_value(value, configuration)?,
+                ))
+            })
+            .collect()
+    }
+}
+
+/// The name of the database's title property.
+pub(crate) fn title_name(database: &Database) -> Option<String> {
+    database
+        .properties
+        .iter()
+        .find(|(_, configuration)| matches!(configuration, PropertyConfiguration::Title { .. }))
+        .map(|(name, _)| name.clone())
+}
+
+/// Converts a page's property value to a value that can be written to
+/// a property configured as `target`, following the rules of [compatibility].
+///
+/// Returns `None` when the value can't be written to the property.
+/// Status options are matched by name, since statuses can't gain new options.
+pub fn convert_value(
+    value: &PropertyValue,
+    target: &PropertyConfiguration,
+) -> Option<CreatePropertyValue> {
+    use PropertyConfiguration as Target;
+    use PropertyValue as Value;
+
+    let converted = match (value, target) {
+        (
+            Value::Title { title: text, .. }
+            | Value::Text {
+                rich_text: text, ..
+            },
+            Target::Title { .. },
+        ) => CreatePropertyValue::Title(text.clone()),
+        (
+            Value::Title { title: text, .. }
+            | Value::Text {
+                rich_text: text, ..
+            },
+            Target::Text { .. },
+        ) => CreatePropertyValue::Text(text.clone()),
+        (value, Target::Title { .. }) => CreatePropertyValue::Title(rich_text(value_text(value)?)),
+        (value, Target::Text { .. }) => CreatePropertyValue::Text(rich_text(value_text(value)?)),
+        (Value::Number { number, .. }, Target::Number { .. }) => {
+            CreatePropertyValue::Number(number.clone())
+        }
+        (value, Target::Select { .. }) => {
+            CreatePropertyValue::Select(option_names(value)?.into_iter().next().map(Into::into))
+        }
+        (value, Target::MultiSelect { .. }) => CreatePropertyValue::MultiSelect(
+            option_names(value)?.into_iter().map(Into::into).collect(),
+        ),
+        (value, Target::Status { status, .. }) => CreatePropertyValue::Status(
+            option_names(value)?
+                .into_iter()
+                .find(|name| status.options.iter().any(|option| &option.name == name))
+                .map(Into::into),
+        ),
+        (Value::Date { date, .. }, Target::Date { .. }) => CreatePropertyValue::Date(date.clone()),
+        (
+            Value::CreatedTime {
+                created_time: time, ..
+            }
+            | Value::LastEditedTime {
+                last_edited_time: time,
+                ..
+            },
+            Target::Date { .. },
+        ) => CreatePropertyValue::Date(Some(DateValue {
+            start: DateOrDateTime::DateTime(*time),
+            end: None,
+            time_zone: None,
+        })),
+        (Value::People { people, .. }, Target::People { .. }) => {
+            CreatePropertyValue::People(people.iter().map(person).collect())
+        }
+        (
+            Value::CreatedBy {
+                created_by: user, ..
+            }
+            | Value::LastEditedBy {
+                last_edited_by: user,
+                ..
+            },
+            Target::People { .. },
+        ) => CreatePropertyValue::People(vec![person(user)]),
+        (Value::Relation { relation, .. }, Target::Relation { .. }) => {
+            CreatePropertyValue::Relation(relation.clone().unwrap_or_default())
+        }
+        (Value::Checkbox { checkbox, .. }, Target::Checkbox { .. }) => {
+            CreatePropertyValue::Checkbox(*checkbox)
+        }
+        (value, Target::Url { .. }) => CreatePropertyValue::Url(link_text(value)?),
+        (value, Target::Email { .. }) => CreatePropertyValue::Email(link_text(value)?),
+        (value, Target::PhoneNumber { .. }) => CreatePropertyValue::PhoneNumber(link_text(value)?),
+        _ => return None,
+    };
+    Some(converted)
+}
+
+fn rich_text(text: String) -> Vec<RichText> {
+    match text.is_empty() {
+        true => vec![],
+        false => vec![RichText::text(text)],
+    }
+}
+
+fn person(user: &User) -> CreatePerson {
+    CreatePerson {
+        id: user.id().clone(),
+    }
+}
+
+/// The selected option names of a select, status or multi-select value.
+fn option_names(value: &PropertyValue) -> Option<Vec<String>> {
+    let options = match value {
+        PropertyValue::Select { select: option, .. }
+        | PropertyValue::Status { status: option, .. } => option.iter().collect::<Vec<_>>(),
+        PropertyValue::MultiSelect { multi_select, .. } => multi_select.iter().flatten().collect(),
+        _ => return None,
+    };
+    Some(
+        options
+            .into_iter()
+            .filter_map(|option| option.name.clone())
+            .collect(),
+    )
+}
+
+/// The text of a title, rich text, url, email or phone number value, `None` inside when empty.
+fn link_text(value: &PropertyValue) -> Option<Option<String>> {
+    match value {
+        PropertyValue::Title { .. }
+        | PropertyValue::Text { .. }
+        | PropertyValue::Url { .. }
+        | PropertyValue::Email { .. }
+        | PropertyValue::PhoneNumber { .. } => {
+            Some(value_text(value).filter(|text| !text.is_empty()))
+        }
+        _ => None,
+    }
+}
+
+/// The value as text, as Notion would show it.
+fn value_text(value: &PropertyValue) -> Option<String> {
+    let joined = |texts: Vec<String>| texts.join(", ");
+    let text = match value {
+        PropertyValue::Title { title: text, .. }
+        | PropertyValue::Text {
+            rich_text: text, ..
+        } => text.iter().map(RichText::plain_text).collect(),
+        PropertyValue::Number { number, .. } => number.as_ref()?.to_string(),
+        PropertyValue::Select { .. }
+        | PropertyValue::Status { .. }
+        | PropertyValue::MultiSelect { .. } => joined(option_names(value)?),
+        PropertyValue::Date { date, .. } => date_text(date.as_ref()?),
+        PropertyValue::Formula { formula, .. } => match formula {
+            FormulaResultValue::String { string } => string.clone()?,
+            FormulaResultValue::Number { number } => number.as_ref()?.to_string(),
+            FormulaResultValue::Boolean { boolean } => boolean.as_ref()?.to_string(),
+            FormulaResultValue::Date { date } => date_text(date.as_ref()?),
+        },
+        PropertyValue::Relation { relation, .. } => joined(
+            relation
+                .iter()
+                .flatten()
+                .map(|page| page.id.to_string())
+                .collect(),
+        ),
+        PropertyValue::Rollup { rollup, .. } => match rollup.as_ref()? {
+            RollupValue::Number { number } => number.as_ref()?.to_string(),
+            RollupValue::Date { date } => date_text(date.as_ref()?),
+            _ => return None,
+        },
+        PropertyValue::People { people, .. } => {
+            joined(people.iter().filter_map(user_name).collect())
+        }
+        PropertyValue::Files { files, .. } => joined(
+            files
+                .iter()
+                .flatten()
+                .map(|file| file.name.clone())
+                .collect(),
+        ),
+        PropertyValue::Checkbox { checkbox, .. } => checkbox.to_string(),
+        PropertyValue::Url { url: text, .. } | PropertyValue::Email { email: text, .. } => {
+            text.clone()?
+        }
+        PropertyValue::PhoneNumber { phone_number, .. } => phone_number.clone(),
+        PropertyValue::CreatedTime {
+            created_time: time, ..
+        }
+        | PropertyValue::LastEditedTime {
+            last_edited_time: time,
+            ..
+        } => time.to_rfc3339(),
+        PropertyValue::CreatedBy {
+            created_by: user, ..
+        }
+        | PropertyValue::LastEditedBy {
+            last_edited_by: user,
+            ..
+        } => user_name(user)?,
+        PropertyValue::UniqueId { unique_id, .. } => match &unique_id.prefix {
+            Some(prefix) => format!("{}-{}", prefix, unique_id.number),
+            None => unique_id.number.to_string(),
+        },
+        PropertyValue::Button { .. } => return None,
+    };
+    Some(text)
+}
+
+fn user_name(user: &User) -> Option<String> {
+    user.name().map(str::to_string)
+}
+
+fn date_text(date: &DateValue) -> String {
+    let format = |date: &DateOrDateTime| match date {
+        DateOrDateTime::Date(date) => date.to_string(),
+        DateOrDateTime::DateTime(date_time) => date_time.to_rfc3339(),
+        DateOrDateTime::LocalDateTime(date_time) => {
+            date_time.format("%Y-%m-%dT%H:%M:%S").to_string()
+        }
+    };
+    let range = match &date.end {
+        Some(end) => format!("{} → {}", format(&date.start), format(end)),
+        None => format(&date.start),
+    };
+    match &date.time_zone {
+        Some(time_zone) => format!("{} ({})", range, time_zone),
+        None => range,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::properties::{CreatePropertyValue, CreateSelectedValue};
+    use crate::models::text::RichText;
+    use crate::models::{Database, Properties};
+    use crate::schema::{compatibility, Compatibility, PropertyCompatibility, PropertyMapping};
+    use serde_json::json;
+
+    fn database(properties: serde_json::Value) -> Database {
+        serde_json::from_value(json!({
+            "id": "database",
+            "created_time": "2022-05-12T21:15:00.000Z",
+            "last_edited_time": "2022-05-12T21:15:00.000Z",
+            "title": [],
+            "parent": {"type": "workspace"},
+            "icon": null,
+            "properties": properties,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn reports_clean_converted_and_dropped_properties() {
+        let select = json!({"options": []});
+        let source = database(json!({
+            "Name": {"id": "title", "type": "title", "title": {}},
+            "Status": {"id": "s", "type": "select", "select": select},
+            "Price": {"id": "p", "type": "number", "number": {"format": "dollar"}},
+            "Created": {"id": "c", "type": "created_time", "created_time": {}},
+            "Projects": {"id": "r", "type": "relation", "relation": {"database_id": "projects", "synced_property_name": null, "synced_property_id": null}},
+            "Notes": {"id": "n", "type": "rich_text", "rich_text": {}},
+        }));
+        let target = database(json!({
+            "Name": {"id": "title", "type": "title", "title": {}},
+            "Status": {"id": "s", "type": "multi_select", "multi_select": select},
+            "Price": {"id": "p", "type": "rich_text", "rich_text": {}},
+            "Created": {"id": "c", "type": "date", "date": {}},
+            "Projects": {"id": "r", "type": "relation", "relation": {"database_id": "archive", "synced_property_name": null, "synced_property_id": null}},
+        }));
+
+        let report = compatibility(&source, &target);
+
+        let names = |properties: Vec<_>| {
+            properties
+                .into_iter()
+                .map(|p: &PropertyCompatibility| p.name.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(report.clean().collect()), ["Name"]);
+        assert_eq!(
+            names(report.converted().collect()),
+            ["Created", "Price", "Status"]
+        );
+        assert_eq!(names(report.dropped().collect()), ["Notes", "Projects"]);
+        assert!(!report.is_clean());
+
+        let price = &report.properties[3];
+        assert_eq!(price.name, "Price");
+        assert_eq!(
+            price.compatibility,
+            Compatibility::Convert {
+                from: "number",
+                to: "rich_text"
+            }
+        );
+        assert_eq!(
+            price.to_string(),
+            "Price: converted from number to rich_text"
+        );
+        assert!(compatibility(&target, &target).is_clean());
+        // Nothing can be written to computed properties.
+        assert_eq!(
+            names(compatibility(&source, &source).dropped().collect()),
+            ["Created"]
+        );
+    }
+
+    #[test]
+    fn maps_and_converts_values() -> Result<(), Box<dyn std::error::Error>> {
+        let source = database(json!({
+            "Name": {"id": "title", "type": "title", "title": {}},
+            "Price": {"id": "p", "type": "number", "number": {"format": "dollar"}},
+            "Tags": {"id": "t", "type": "multi_select", "multi_select": {"options": []}},
+            "Stage": {"id": "s", "type": "select", "select": {"options": []}},
+            "Done": {"id": "d", "type": "checkbox", "checkbox": {}},
+        }));
+        let target = database(json!({
+            "Task": {"id": "title", "type": "title", "title": {}},
+            "Price": {"id": "p", "type": "rich_text", "rich_text": {}},
+            "Tags": {"id": "t", "type": "select", "select": {"options": []}},
+            "Stage": {"id": "s", "type": "status", "status": {
+                "options": [{"id": "1", "name": "Done", "color": "green"}],
+                "groups": [],
+            }},
+            "Done": {"id": "d", "type": "number", "number": {"format": "number"}},
+        }));
+        let properties: Properties = serde_json::from_value(json!({
+            "Name": {"id": "title", "type": "title", "title": [{
+                "type": "text",
+                "text": {"content": "Ship it", "link": null},
+                "annotations": {"bold": false, "italic": false, "strikethrough": false, "underline": false, "code": false, "color": "default"},
+                "plain_text": "Ship it",
+                "href": null,
+            }]},
+            "Price": {"id": "p", "type": "number", "number": 4.5},
+            "Tags": {"id": "t", "type": "multi_select", "multi_select": [
+                {"id": "a", "name": "urgent", "color": "red"},
+                {"id": "b", "name": "small", "color": "blue"},
+            ]},
+            "Stage": {"id": "s", "type": "select", "select": {"id": "x", "name": "Doing", "color": "red"}},
+            "Done": {"id": "d", "type": "checkbox", "checkbox": true},
+        }))?;
+
+        let mapping = PropertyMapping::infer(&source, &target);
+        assert_eq!(mapping.target("Name"), Some("Task"));
+        assert_eq!(mapping.target("Done"), None);
+
+        let values = mapping.values(&properties, &target);
+        let title = match &values["Task"] {
+            CreatePropertyValue::Title(title) => title,
+            value => panic!("Expected a title, got {:?}", value),
+        };
+        assert_eq!(
+            title.iter().map(RichText::plain_text).collect::<String>(),
+            "Ship it"
+        );
+        assert_eq!(
+            values["Price"],
+            CreatePropertyValue::Text(vec![RichText::text("4.5")])
+        );
+        assert_eq!(
+            values["Tags"],
+            CreatePropertyValue::Select(Some(CreateSelectedValue::from("urgent")))
+        );
+        // Statuses only take options they already have.
+        assert_eq!(values["Stage"], CreatePropertyValue::Status(None));
+        assert_eq!(values.len(), 4);
+
+        Ok(())
+    }
+}
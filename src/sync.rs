@@ -0,0 +1,357 @@
+//! Crawls everything shared with an integration, e.g. to back up a workspace.
+//!
+//! [NotionApi::crawl()] finds every database and page with [search](NotionApi::search()),
+//! and fetches the content of the pages, several pages at a time. The objects are
+//! [streamed](Crawl::stream()) as they're fetched, or collected into a [Snapshot] that
+//! serializes for storage.
+//!
+//! Requests go through the client, so its [rate limit](crate::NotionApiBuilder::requests_per_second())
+//! and [retry policy](crate::NotionApiBuilder::retry_policy()) apply to the crawl.
+//! Comments aren't crawled.
+//!
+//! After a full crawl, [NotionApi::changes_since()] finds what changed since, to keep
+//! a copy up to date without crawling again.
+
+use crate::ids::BlockId;
+use crate::models::block::Block;
+use crate::models::search::{NotionSearch, SearchRequest, SortDirection, SortTimestamp};
+use crate::models::{Database, Page, SearchResult};
+use crate::{Error, NotionApi};
+use chrono::{DateTime, Utc};
+use futures::future::{BoxFuture, FutureExt};
+use futures::{stream, Stream, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// How many pages have their content fetched at the same time, by default.
+const DEFAULT_CONCURRENCY: usize = 4;
+
+/// Search only roughly sorts by last edited time, so [NotionApi::changes_since()] stops
+/// after this many results in a row are older than the changes, a full page of results.
+const OLDER_RESULTS_BEFORE_STOPPING: usize = 100;
+
+/// An object found by a [Crawl].
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[allow(clippy::large_enum_variant)]
+pub enum SyncObject {
+    Database(Database),
+    Page(Page),
+    /// The children of a page or block, in order.
+    Children {
+        parent: BlockId,
+        blocks: Vec<Block>,
+    },
+}
+
+/// Everything a [Crawl] found.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Eq, PartialEq)]
+pub struct Snapshot {
+    pub databases: Vec<Database>,
+    pub pages: Vec<Page>,
+    /// The children of pages and blocks that have any, by the id of their parent.
+    pub children: BTreeMap<String, Vec<Block>>,
+}
+
+impl Extend<SyncObject> for Snapshot {
+    fn extend<I: IntoIterator<Item = SyncObject>>(
+        &mut self,
+        objects: I,
+    ) {
+        for object in objects {
+            match object {
+                SyncObject::Database(database) => self.databases.push(database),
+                SyncObject::Page(page) => self.pages.push(page),
+                SyncObject::Children { parent, blocks } => {
+                    self.children.insert(parent.to_string(), blocks);
+                }
+            }
+        }
+    }
+}
+
+/// A crawl of everything shared with an integration, created with [NotionApi::crawl()].
+pub struct Crawl<'a> {
+    api: &'a NotionApi,
+    concurrency: usize,
+    content: bool,
+}
+
+impl NotionApi {
+    /// Crawls every database and page shared with the integration, with the content of the pages.
+    pub fn crawl(&self) -> Crawl<'_> {
+        Crawl {
+            api: self,
+            concurrency: DEFAULT_CONCURRENCY,
+            content: true,
+        }
+    }
+
+    /// The pages and databases edited at or after `since`, most recently edited first.
+    ///
+    /// Results are read from a search sorted by last edited time until a full page
+    /// of them in a row is older than `since`, because the sort isn't exact.
+    /// Notion rounds edit times down to the minute, so changes from the minute of `since`
+    /// are included again: pass the time the previous sync started, and expect repeats.
+    pub async fn changes_since(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<SearchResult>, Error> {
+        let mut results = Box::pin(self.search_stream(NotionSearch::Sort {
+            timestamp: SortTimestamp::LastEditedTime,
+            direction: SortDirection::Descending,
+        }));
+
+        let mut changes = vec![];
+        let mut seen = HashSet::new();
+        let mut older = 0;
+        while let Some(result) = results.try_next().await? {
+            if result.last_edited_time() < since {
+                older += 1;
+                if older == OLDER_RESULTS_BEFORE_STOPPING {
+                    break;
+                }
+                continue;
+            }
+
+            older = 0;
+            let id = match &result {
+                SearchResult::Page { page } => page.id.to_string(),
+                SearchResult::Database { database } => database.id.to_string(),
+            };
+            // Results can move between pages of the search while it's read.
+            if seen.insert(id) {
+                changes.push(result);
+            }
+        }
+
+        changes.sort_by_key(|result| std::cmp::Reverse(result.last_edited_time()));
+        Ok(changes)
+    }
+
+    /// The children of the block, followed by the children of each of them.
+    /// Child pages and databases are left to the search that found their parent.
+    fn crawl_children(
+        &self,
+        block_id: BlockId,
+    ) -> BoxFuture<'_, Result<Vec<SyncObject>, Error>> {
+        async move {
+            let blocks = self.get_block_children_all(&block_id).await?;
+            let nested: Vec<BlockId> = blocks
+                .iter()
+                .filter(|block| {
+                    block.common().has_children
+                        && !matches!(block, Block::ChildPage { .. } | Block::ChildDatabase { .. })
+                })
+                .map(|block| block.common().id.clone())
+                .collect();
+
+            let mut objects = vec![];
+            if !blocks.is_empty() {
+                objects.push(SyncObject::Children {
+                    parent: block_id,
+                    blocks,
+                });
+            }
+            for block_id in nested {
+                objects.extend(self.crawl_children(block_id).await?);
+            }
+            Ok(objects)
+        }
+        .boxed()
+    }
+}
+
+impl<'a> Crawl<'a> {
+    /// Fetches the content of up to `concurrency` pages at the same time. Defaults to 4.
+    pub fn concurrency(
+        self,
+        concurrency: usize,
+    ) -> Self {
+        Self {
+            concurrency: concurrency.max(1),
+            ..self
+        }
+    }
+
+    /// Whether to fetch the content of pages. Enabled by default.
+    pub fn content(
+        self,
+        content: bool,
+    ) -> Self {
+        Self { content, ..self }
+    }
+
+    /// The objects in the order search returns them, each page followed by its content.
+    /// The first error ends the stream.
+    pub fn stream(self) -> impl Stream<Item = Result<SyncObject, Error>> + 'a {
+        let Crawl {
+            api,
+            concurrency,
+            content,
+        } = self;
+
+        api.search_stream(SearchRequest::default())
+            .map_ok(move |result| async move {
+                match result {
+                    SearchResult::Database { database } => Ok(vec![SyncObject::Database(database)]),
+                    SearchResult::Page { page } if content => {
+                        let children = api.crawl_children(page.id.clone().into()).await?;
+                        let mut objects = vec![SyncObject::Page(page)];
+                        objects.extend(children);
+                        Ok(objects)
+                    }
+                    SearchResult::Page { page } => Ok(vec![SyncObject::Page(page)]),
+                }
+            })
+            .try_buffered(concurrency)
+            .map_ok(|objects| stream::iter(objects.into_iter().map(Ok)))
+            .try_flatten()
+    }
+
+    /// Collects everything the crawl finds.
+    pub async fn snapshot(self) -> Result<Snapshot, Error> {
+        let mut snapshot = Snapshot::default();
+        let mut objects = Box::pin(self.stream());
+        while let Some(object) = objects.next().await {
+            snapshot.extend(Some(object?));
+        }
+        Ok(snapshot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::block::Block;
+    use crate::{NotionApi, TransportResponse};
+    use reqwest::{Method, StatusCode};
+    use serde_json::json;
+
+    // The id of the page in the search results.
+    const PAGE: &str = "71";
+    const TOGGLE: &str = "9e891834-6a03-475c-a2b8-421e17f0f3aa";
+
+    fn toggle(
+        id: &str,
+        has_children: bool,
+    ) -> serde_json::Value {
+        let user = json!({"object": "user", "id": "6419f912-5293-4ea8-b2c8-9c3ce44f90e3"});
+        json!({
+            "object": "block",
+            "id": id,
+            "created_time": "2022-05-12T21:15:00.000Z",
+            "last_edited_time": "2022-05-12T22:10:00.000Z",
+            "created_by": user,
+            "last_edited_by": user,
+            "has_children": has_children,
+            "type": "toggle",
+            "toggle": {"rich_text": [], "color": "default"},
+        })
+    }
+
+    #[tokio::test]
+    async fn snapshots_databases_pages_and_content() -> Result<(), Box<dyn std::error::Error>> {
+        let api = NotionApi::builder("secret".to_string())
+            .transport(|request: reqwest::Request| {
+                let results = match (request.method(), request.url().path()) {
+                    (&Method::POST, "/v1/search") => {
+                        return Ok(TransportResponse::new(
+                            StatusCode::OK,
+                            include_str!("models/tests/search_results.json"),
+                        ))
+                    }
+                    (&Method::GET, path) if path == format!("/v1/blocks/{}/children", PAGE) => {
+                        vec![toggle(TOGGLE, true)]
+                    }
+                    (&Method::GET, path) if path == format!("/v1/blocks/{}/children", TOGGLE) => {
+                        vec![toggle("00e8829a-a7b8-4075-884a-8f53be145d2f", false)]
+                    }
+                    (&Method::GET, _) => vec![],
+                    (method, path) => panic!("unexpected request {} {}", method, path),
+                };
+                let list = json!({"object": "list", "results": results, "next_cursor": null, "has_more": false});
+                Ok(TransportResponse::new(StatusCode::OK, list.to_string()))
+            })
+            .build()?;
+
+        let snapshot = api.crawl().concurrency(2).snapshot().await?;
+
+        assert_eq!(snapshot.databases.len(), 1);
+        assert_eq!(snapshot.pages.len(), 1);
+        assert_eq!(
+            snapshot.children.keys().collect::<Vec<_>>(),
+            vec![PAGE, TOGGLE]
+        );
+        assert!(matches!(
+            snapshot.children[PAGE][..],
+            [Block::Toggle { .. }]
+        ));
+
+        let json = serde_json::to_string(&snapshot)?;
+        assert_eq!(serde_json::from_str::<super::Snapshot>(&json)?, snapshot);
+
+        let pages_only = api.crawl().content(false).snapshot().await?;
+        assert!(pages_only.children.is_empty());
+
+        Ok(())
+    }
+
+    fn page(
+        id: usize,
+        last_edited_time: &str,
+    ) -> serde_json::Value {
+        let mut page: serde_json::Value =
+            serde_json::from_str(include_str!("models/tests/page.json")).unwrap();
+        page["id"] = format!("00000000-0000-4000-8000-{:012}", id).into();
+        page["last_edited_time"] = last_edited_time.into();
+        page
+    }
+
+    #[tokio::test]
+    async fn finds_changes_in_roughly_sorted_search_results(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let api = NotionApi::builder("secret".to_string())
+            .transport(|request: reqwest::Request| {
+                let body: serde_json::Value = serde_json::from_slice(
+                    request.body().and_then(|body| body.as_bytes()).unwrap(),
+                )
+                .unwrap();
+                assert_eq!(body["sort"]["direction"], "descending");
+                let (results, next) = match body["start_cursor"].as_str() {
+                    None => (
+                        vec![
+                            page(1, "2022-01-20T10:00:00.000Z"),
+                            page(2, "2022-01-05T10:00:00.000Z"),
+                            page(3, "2022-01-15T10:00:00.000Z"),
+                        ],
+                        Some("second"),
+                    ),
+                    // Repeats a result, then runs out of changes.
+                    Some("second") => (
+                        std::iter::once(page(3, "2022-01-15T10:00:00.000Z"))
+                            .chain((10..110).map(|id| page(id, "2022-01-01T10:00:00.000Z")))
+                            .collect(),
+                        Some("third"),
+                    ),
+                    Some(cursor) => panic!("read past the changes to {}", cursor),
+                };
+                let list = json!({
+                    "object": "list", "results": results, "next_cursor": next, "has_more": true
+                });
+                Ok(TransportResponse::new(StatusCode::OK, list.to_string()))
+            })
+            .build()?;
+
+        let changes = api.changes_since("2022-01-10T00:00:00Z".parse()?).await?;
+
+        let times: Vec<_> = changes
+            .iter()
+            .map(|change| change.last_edited_time().to_rfc3339())
+            .collect();
+        assert_eq!(
+            times,
+            vec!["2022-01-20T10:00:00+00:00", "2022-01-15T10:00:00+00:00"]
+        );
+
+        Ok(())
+    }
+}
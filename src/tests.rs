@@ -3,24 +3,11 @@ use crate::models::search::PropertyCondition::Text;
 use crate::models::search::{
     DatabaseQuery, FilterCondition, FilterProperty, FilterValue, NotionSearch, TextCondition,
 };
-use crate::models::Object;
+use crate::models::SearchResult;
 use crate::NotionApi;
 
-fn test_token() -> String {
-    let token = {
-        if let Some(token) = std::env::var("NOTION_API_TOKEN").ok() {
-            token
-        } else if let Some(token) = std::fs::read_to_string(".api_token").ok() {
-            token
-        } else {
-            panic!("No API Token found in environment variable 'NOTION_API_TOKEN'!")
-        }
-    };
-    token.trim().to_string()
-}
-
 fn test_client() -> NotionApi {
-    NotionApi::new(test_token()).unwrap()
+    NotionApi::from_env().unwrap()
 }
 
 #[tokio::test]
@@ -79,7 +66,7 @@ async fn get_database() -> Result<(), Box<dyn std::error::Error>> {
         .results()
         .iter()
         .filter_map(|o| match o {
-            Object::Database { database } => Some(database),
+            SearchResult::Database { database } => Some(database),
             _ => None,
         })
         .next()
@@ -109,7 +96,7 @@ async fn get_block_children() -> Result<(), Box<dyn std::error::Error>> {
 
     for object in search_response.results {
         match object {
-            Object::Page { page } => api
+            SearchResult::Page { page } => api
                 .get_block_children(BlockId::from(page.id))
                 .await
                 .unwrap(),
@@ -135,7 +122,7 @@ async fn query_database() -> Result<(), Box<dyn std::error::Error>> {
         .results()
         .iter()
         .filter_map(|o| match o {
-            Object::Database { database } => Some(database),
+            SearchResult::Database { database } => Some(database),
             _ => None,
         })
         .next()
@@ -0,0 +1,114 @@
+use crate::Error;
+use futures::future::{BoxFuture, FutureExt};
+use reqwest::header::HeaderMap;
+use reqwest::{Client, Request, StatusCode};
+
+/// Sends the requests of a [NotionApi](crate::NotionApi) client, which uses
+/// [ReqwestTransport] unless [another is set](crate::NotionApiBuilder::transport()).
+///
+/// Requests arrive complete, with the client's headers, timeout and
+/// [signature](crate::NotionApiBuilder::sign_request()). Retries, rate limiting and
+/// parsing happen around the transport, so a mock only needs to answer requests.
+///
+/// Closures taking a [Request] implement the trait, which makes for short mocks:
+///
+/// ```
+/// use notion::{NotionApi, TransportResponse};
+/// use notion::ids::PageId;
+/// use reqwest::{Request, StatusCode};
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let api = NotionApi::builder("secret".to_string())
+///     .transport(|request: Request| {
+///         assert_eq!(request.url().path(), "/v1/pages/b55c9c91-384d-452b-81db-d1ef79372b75");
+///         Ok(TransportResponse::new(StatusCode::NOT_FOUND, r#"{
+///             "object": "error", "status": 404, "code": "object_not_found", "message": "Not found"
+///         }"#))
+///     })
+///     .build()?;
+///
+/// assert!(api.get_page("b55c9c91384d452b81dbd1ef79372b75".parse::<PageId>()?).await.is_err());
+/// # Ok(())
+/// # }
+/// # tokio::runtime::Runtime::new().unwrap().block_on(run()).unwrap();
+/// ```
+pub trait NotionTransport: Send + Sync {
+    fn send(
+        &self,
+        request: Request,
+    ) -> BoxFuture<'_, Result<TransportResponse, Error>>;
+}
+
+/// A response received by a [NotionTransport], with its body read.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TransportResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+impl TransportResponse {
+    /// A response without headers.
+    pub fn new<B: Into<String>>(
+        status: StatusCode,
+        body: B,
+    ) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body: body.into(),
+        }
+    }
+}
+
+/// Sends requests over HTTP with a [reqwest::Client].
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport {
+    client: Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+impl NotionTransport for ReqwestTransport {
+    fn send(
+        &self,
+        request: Request,
+    ) -> BoxFuture<'_, Result<TransportResponse, Error>> {
+        async move {
+            let response = self
+                .client
+                .execute(request)
+                .await
+                .map_err(|source| Error::RequestFailed { source })?;
+            let status = response.status();
+            let headers = response.headers().clone();
+            let body = response
+                .text()
+                .await
+                .map_err(|source| Error::ResponseIoError { source })?;
+
+            Ok(TransportResponse {
+                status,
+                headers,
+                body,
+            })
+        }
+        .boxed()
+    }
+}
+
+impl<F> NotionTransport for F
+where
+    F: Fn(Request) -> Result<TransportResponse, Error> + Send + Sync,
+{
+    fn send(
+        &self,
+        request: Request,
+    ) -> BoxFuture<'_, Result<TransportResponse, Error>> {
+        futures::future::ready(self(request)).boxed()
+    }
+}
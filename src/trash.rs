@@ -0,0 +1,116 @@
+use crate::ids::{AsIdentifier, BlockId, PageId};
+use crate::models::block::Block;
+use crate::models::{Object, Page};
+use crate::{Error, NotionApi};
+
+impl NotionApi {
+    /// Moves a page to the trash, and returns the trashed page.
+    pub async fn trash_page<T: AsIdentifier<PageId>>(
+        &self,
+        page_id: T,
+    ) -> Result<Page, Error> {
+        self.set_page_in_trash(page_id.as_id(), true).await
+    }
+
+    /// Restores a page from the trash, whether it was trashed or
+    /// [archived](NotionApi::archive_page()), and returns the restored page.
+    pub async fn restore_page<T: AsIdentifier<PageId>>(
+        &self,
+        page_id: T,
+    ) -> Result<Page, Error> {
+        self.set_page_in_trash(page_id.as_id(), false).await
+    }
+
+    /// Moves a block to the trash, with its children, and returns the trashed block.
+    pub async fn trash_block<T: AsIdentifier<BlockId>>(
+        &self,
+        block_id: T,
+    ) -> Result<Block, Error> {
+        self.set_block_in_trash(block_id.as_id(), true).await
+    }
+
+    /// Restores a block from the trash, and returns the restored block.
+    pub async fn restore_block<T: AsIdentifier<BlockId>>(
+        &self,
+        block_id: T,
+    ) -> Result<Block, Error> {
+        self.set_block_in_trash(block_id.as_id(), false).await
+    }
+
+    async fn set_page_in_trash(
+        &self,
+        page_id: &PageId,
+        in_trash: bool,
+    ) -> Result<Page, Error> {
+        let result = self
+            .make_json_request(
+                self.client
+                    .patch(format!("{}/pages/{}", self.base_url, page_id))
+                    .json(&serde_json::json!({ "in_trash": in_trash })),
+            )
+            .await?;
+
+        match result {
+            Object::Page { page } => Ok(page),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+
+    async fn set_block_in_trash(
+        &self,
+        block_id: &BlockId,
+        in_trash: bool,
+    ) -> Result<Block, Error> {
+        let result = self
+            .make_json_request(
+                self.client
+                    .patch(format!("{}/blocks/{}", self.base_url, block_id))
+                    .json(&serde_json::json!({ "in_trash": in_trash })),
+            )
+            .await?;
+
+        match result {
+            Object::Block { block } => Ok(block),
+            response => Err(Error::UnexpectedResponse { response }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ids::{BlockId, PageId};
+    use crate::{NotionApi, TransportResponse};
+    use reqwest::{Method, StatusCode};
+
+    #[tokio::test]
+    async fn trashes_and_restores_pages_and_blocks() -> Result<(), Box<dyn std::error::Error>> {
+        let api = NotionApi::builder("secret".to_string())
+            .transport(|request: reqwest::Request| {
+                assert_eq!(request.method(), Method::PATCH);
+                let body: serde_json::Value = serde_json::from_slice(
+                    request.body().and_then(|body| body.as_bytes()).unwrap(),
+                )
+                .unwrap();
+                let fixture = if request.url().path().starts_with("/v1/pages/") {
+                    include_str!("models/tests/page.json")
+                } else {
+                    include_str!("models/block/tests/heading_1.json")
+                };
+                let mut object: serde_json::Value = serde_json::from_str(fixture).unwrap();
+                object["in_trash"] = body["in_trash"].clone();
+                Ok(TransportResponse::new(StatusCode::OK, object.to_string()))
+            })
+            .build()?;
+        let page_id: PageId = "b55c9c91-384d-452b-81db-d1ef79372b75".parse()?;
+        let block_id: BlockId = "9e891834-6a03-475c-a2b8-421e17f0f3aa".parse()?;
+
+        let page = api.trash_page(&page_id).await?;
+        assert!(page.in_trash && !page.archived);
+        assert!(!api.restore_page(&page_id).await?.in_trash);
+
+        assert!(api.trash_block(&block_id).await?.common().in_trash);
+        assert!(!api.restore_block(&block_id).await?.common().in_trash);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,235 @@
+use crate::models::properties::{CreatePropertyConfiguration, RelationKind};
+use crate::{Error, NotionApi};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// The versions of the Notion API this crate knows about, sent as the `Notion-Version` header.
+///
+/// The models are written against the [default](ApiVersion::default()) version.
+/// Pinning an older or newer version with
+/// [NotionApiBuilder::api_version()](crate::NotionApiBuilder::api_version())
+/// lets the client refuse requests the pinned version doesn't support,
+/// rather than sending them and failing on Notion's side.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+#[non_exhaustive]
+pub enum ApiVersion {
+    V2021_08_16,
+    #[default]
+    V2022_02_22,
+    V2022_06_28,
+}
+
+impl ApiVersion {
+    /// The value of the `Notion-Version` header, e.g. `2022-02-22`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiVersion::V2021_08_16 => "2021-08-16",
+            ApiVersion::V2022_02_22 => "2022-02-22",
+            ApiVersion::V2022_06_28 => "2022-06-28",
+        }
+    }
+
+    /// Whether relations may be [single property](crate::models::properties::RelationKind::SingleProperty),
+    /// rather than always synced to a property of the related database.
+    pub fn supports_single_property_relations(&self) -> bool {
+        *self >= ApiVersion::V2022_06_28
+    }
+}
+
+impl Display for ApiVersion {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Unknown Notion API version: {}", .0)]
+pub struct UnknownApiVersion(String);
+
+impl FromStr for ApiVersion {
+    type Err = UnknownApiVersion;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "2021-08-16" => Ok(ApiVersion::V2021_08_16),
+            "2022-02-22" => Ok(ApiVersion::V2022_02_22),
+            "2022-06-28" => Ok(ApiVersion::V2022_06_28),
+            _ => Err(UnknownApiVersion(s.to_string())),
+        }
+    }
+}
+
+impl NotionApi {
+    /// The version sent in the `Notion-Version` header,
+    /// or `None` when it was [overridden](crate::NotionApiBuilder::notion_version())
+    /// with a version this crate doesn't know.
+    pub fn api_version(&self) -> Option<ApiVersion> {
+        self.api_version
+    }
+
+    /// The JSON body of a database request, with its relations in the shape the pinned version
+    /// expects: versions before `2022-06-28` only know synced relations and have no `type` tag.
+    ///
+    /// Fails when the schema uses a kind of property the pinned version doesn't support.
+    pub(crate) fn database_body<T: Serialize>(
+        &self,
+        request: &T,
+        properties: &HashMap<String, CreatePropertyConfiguration>,
+    ) -> Result<Value, Error> {
+        let mut body =
+            serde_json::to_value(request).map_err(|source| Error::JsonParseError { source })?;
+        let version = match self.api_version {
+            Some(version) if !version.supports_single_property_relations() => version,
+            _ => return Ok(body),
+        };
+
+        for (name, property) in properties {
+            let relation = match property {
+                CreatePropertyConfiguration::Relation(relation) => relation,
+                _ => continue,
+            };
+            let dual_property = match &relation.kind {
+                RelationKind::DualProperty { dual_property } => dual_property,
+                RelationKind::SingleProperty { .. } => {
+                    return Err(Error::UnsupportedByApiVersion {
+                        feature: "single property relations",
+                        version,
+                    })
+                }
+            };
+            let mut legacy = json!({ "database_id": relation.database_id });
+            if let Some(synced_property_name) = &dual_property.synced_property_name {
+                legacy["synced_property_name"] = json!(synced_property_name);
+            }
+            if let Some(synced_property_id) = &dual_property.synced_property_id {
+                legacy["synced_property_id"] = json!(synced_property_id);
+            }
+            body["properties"][name]["relation"] = legacy;
+        }
+        Ok(body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::properties::CreatePropertyConfiguration;
+    use crate::models::text::RichText;
+    use crate::models::DatabaseCreateRequest;
+    use crate::{ApiVersion, Error, NotionApi, TransportResponse};
+    use reqwest::StatusCode;
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn parses_and_orders_versions() {
+        assert_eq!(ApiVersion::default().as_str(), crate::NOTION_API_VERSION);
+        assert_eq!(
+            "2022-06-28".parse::<ApiVersion>().unwrap(),
+            ApiVersion::V2022_06_28
+        );
+        assert!("2020-01-01".parse::<ApiVersion>().is_err());
+        assert!(ApiVersion::V2021_08_16 < ApiVersion::V2022_02_22);
+        assert!(!ApiVersion::default().supports_single_property_relations());
+        assert!(ApiVersion::V2022_06_28.supports_single_property_relations());
+    }
+
+    #[tokio::test]
+    async fn refuses_single_property_relations_before_they_exist(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let client = |api_version| {
+            NotionApi::builder("secret".to_string())
+                .api_version(api_version)
+                .transport(|_| {
+                    Ok(TransportResponse::new(
+                        StatusCode::OK,
+                        include_str!("models/tests/database.json"),
+                    ))
+                })
+                .build()
+        };
+        let database = DatabaseCreateRequest::new(
+            "b55c9c91384d452b81dbd1ef79372b75".parse::<crate::ids::PageId>()?,
+            vec![RichText::text("Tasks")],
+        )
+        .property(
+            "Project",
+            CreatePropertyConfiguration::single_property_relation(
+                "58bbd1b2-9d14-4a2d-9d2e-3dd1dc6a1a3e".parse()?,
+            ),
+        );
+
+        let old = client(ApiVersion::V2022_02_22)?;
+        assert_eq!(old.api_version(), Some(ApiVersion::V2022_02_22));
+        assert!(matches!(
+            old.create_database(database.clone()).await,
+            Err(Error::UnsupportedByApiVersion {
+                version: ApiVersion::V2022_02_22,
+                ..
+            })
+        ));
+        client(ApiVersion::V2022_06_28)?
+            .create_database(database)
+            .await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sends_synced_relations_the_way_the_version_expects(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let bodies = Arc::new(Mutex::new(Vec::new()));
+        let client = |builder: crate::NotionApiBuilder| {
+            let bodies = bodies.clone();
+            builder
+                .transport(move |request: reqwest::Request| {
+                    let body = request.body().and_then(|body| body.as_bytes()).unwrap();
+                    bodies
+                        .lock()
+                        .unwrap()
+                        .push(serde_json::from_slice::<serde_json::Value>(body).unwrap());
+                    Ok(TransportResponse::new(
+                        StatusCode::OK,
+                        include_str!("models/tests/database.json"),
+                    ))
+                })
+                .build()
+        };
+        let database = DatabaseCreateRequest::new(
+            "b55c9c91384d452b81dbd1ef79372b75".parse::<crate::ids::PageId>()?,
+            vec![RichText::text("Tasks")],
+        )
+        .property(
+            "Project",
+            CreatePropertyConfiguration::relation("58bbd1b2-9d14-4a2d-9d2e-3dd1dc6a1a3e".parse()?),
+        );
+
+        client(NotionApi::builder("secret".to_string()))?
+            .create_database(database.clone())
+            .await?;
+        client(NotionApi::builder("secret".to_string()).api_version(ApiVersion::V2022_06_28))?
+            .create_database(database)
+            .await?;
+
+        let bodies = bodies.lock().unwrap();
+        assert_eq!(
+            bodies[0]["properties"]["Project"]["relation"],
+            json!({"database_id": "58bbd1b2-9d14-4a2d-9d2e-3dd1dc6a1a3e"})
+        );
+        assert_eq!(
+            bodies[1]["properties"]["Project"]["relation"],
+            json!({
+                "database_id": "58bbd1b2-9d14-4a2d-9d2e-3dd1dc6a1a3e",
+                "type": "dual_property",
+                "dual_property": {}
+            })
+        );
+
+        Ok(())
+    }
+}